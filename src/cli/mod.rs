@@ -0,0 +1,16 @@
+pub mod nm;
+pub mod readelf;
+pub mod size;
+
+use elf_parser::parse_error::ParseError;
+use std::fs;
+
+// Whole content of the file at `path`
+pub fn read_file(path: &str) -> Result<Vec<u8>, ParseError> {
+    Ok(fs::read(path)?)
+}
+
+// "1 entry" or "3 entries"
+pub fn entries(count: usize) -> String {
+    if count == 1 { "1 entry".to_string() } else { format!("{} entries", count) }
+}
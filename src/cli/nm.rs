@@ -0,0 +1,311 @@
+use super::read_file;
+use elf_parser::elf::*;
+use elf_parser::elf_file::ElfFile;
+use elf_parser::parse_error::ParseError;
+use elf_parser::symbol::*;
+use elf_parser::version::VersionedSymbol;
+use std::cmp::Ordering;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: nm [option(s)] [file(s)]
+ List symbols in [file(s)] (a.out by default).
+ The options are:
+  -D, --dynamic          Display dynamic symbols instead of normal symbols
+  -U, --defined-only     Display only defined symbols
+  -u, --undefined-only   Display only undefined symbols
+  -n, --numeric-sort     Sort symbols numerically by address
+  -p, --no-sort          Do not sort the symbols
+  -r, --reverse-sort     Reverse the sense of the sort
+      --size-sort        Sort symbols by size
+      --sort=KEY         Sort by KEY: name, address, size or none
+  -S, --print-size       Print size of defined symbols
+      --size             An alias for --print-size
+  -h, --help             Display this information";
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum SortKey {
+    Name,
+    Address,
+    Size,
+    None
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    dynamic: bool,
+    defined_only: bool,
+    undefined_only: bool,
+    sort: SortKey,
+    reverse: bool,
+    print_size: bool
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            dynamic: false,
+            defined_only: false,
+            undefined_only: false,
+            sort: SortKey::Name,
+            reverse: false,
+            print_size: false
+        }
+    }
+}
+
+// `None` when only the help was asked for
+fn parse_args(args: &[String]) -> Result<Option<(Options, Vec<&str>)>, String> {
+    let mut options = Options::default();
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--dynamic" => options.dynamic = true,
+            "--defined-only" => options.defined_only = true,
+            "--undefined-only" => options.undefined_only = true,
+            "--numeric-sort" => options.sort = SortKey::Address,
+            "--no-sort" => options.sort = SortKey::None,
+            "--reverse-sort" => options.reverse = true,
+            "--size-sort" => options.sort = SortKey::Size,
+            "--print-size" | "--size" => options.print_size = true,
+            "--help" => return Ok(None),
+            long if long.starts_with("--sort=") => {
+                options.sort = match &long["--sort=".len()..] {
+                    "name" => SortKey::Name,
+                    "address" => SortKey::Address,
+                    "size" => SortKey::Size,
+                    "none" => SortKey::None,
+                    key => return Err(format!("invalid sort key '{}'", key))
+                }
+            },
+            long if long.starts_with("--") => return Err(format!("unrecognized option '{}'", long)),
+            short if short.starts_with('-') && short.len() > 1 => {
+                // Short options can be grouped, like -Dn
+                for c in short.chars().skip(1) {
+                    match c {
+                        'D' => options.dynamic = true,
+                        'U' => options.defined_only = true,
+                        'u' => options.undefined_only = true,
+                        'n' | 'v' => options.sort = SortKey::Address,
+                        'p' => options.sort = SortKey::None,
+                        'r' => options.reverse = true,
+                        'S' => options.print_size = true,
+                        'h' => return Ok(None),
+                        c => return Err(format!("invalid option -- '{}'", c))
+                    }
+                }
+            },
+            file => files.push(file)
+        }
+    }
+    if options.defined_only && options.undefined_only {
+        return Err("cannot use --defined-only and --undefined-only together".to_string());
+    }
+    if files.is_empty() { files.push("a.out") }
+    Ok(Some((options, files)))
+}
+
+// Entry point of the nm mode, returns a failure when an option is wrong or
+// a file could not be parsed
+pub fn run(args: &[String]) -> ExitCode {
+    let (options, files) = match parse_args(args) {
+        Ok(Some(parsed)) => parsed,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(message) => {
+            eprintln!("nm: {}", message);
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    for path in &files {
+        if files.len() > 1 { println!("\n{}:", path) }
+        if let Err(e) = display_file(path, &options) {
+            eprintln!("nm: {}: {}", path, e);
+            status = ExitCode::FAILURE;
+        }
+    }
+    status
+}
+
+fn display_file(path: &str, options: &Options) -> Result<(), ParseError> {
+    let content = read_file(path)?;
+    let elf = Elf::parse(&content)?;
+    match list_symbols(&elf, options)? {
+        Some(symbols) => {
+            for symbol in &symbols {
+                println!("{}", format_symbol(symbol, elf.bits(), options));
+            }
+        },
+        None => eprintln!("nm: {}: no symbols", path)
+    }
+    Ok(())
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct NmSymbol {
+    name: String,
+    key: String, // Name without the version, used for sorting
+    value: u64,
+    size: u64,
+    letter: char
+}
+
+impl NmSymbol {
+    fn is_undefined(&self) -> bool {
+        matches!(self.letter, 'U' | 'w' | 'v')
+    }
+}
+
+// Letter of a section, following binutils: a few well known names first, then the flags
+fn section_letter(name: &str, section: &SectionHeader) -> char {
+    const KNOWN: [(&str, char); 12] = [
+        (".bss", 'b'), ("zerovars", 'b'), (".data", 'd'), ("vars", 'd'), (".rdata", 'r'), (".rodata", 'r'),
+        (".sbss", 's'), (".scommon", 'c'), (".sdata", 'g'), (".text", 't'), ("code", 't'), (".drectve", 'i')
+    ];
+    const DEBUG: [&str; 5] = [".debug", ".zdebug", ".gnu.linkonce.wi.", ".line", ".stab"];
+
+    if let Some((_, letter)) = KNOWN.iter().find(|(prefix, _)| name.starts_with(prefix)) {
+        return *letter;
+    }
+    let contents = section.sh_type != SectionType::SHT_NOBITS;
+    let read_only = !section.sh_flags.contains(SectionFlags::WRITE);
+    if section.sh_flags.contains(SectionFlags::EXECINSTR) { return 't' }
+    if section.sh_flags.contains(SectionFlags::ALLOC) && contents {
+        return if read_only { 'r' } else { 'd' };
+    }
+    if !contents { return 'b' }
+    if DEBUG.iter().any(|prefix| name.starts_with(prefix)) { return 'N' }
+    if read_only { 'n' } else { '?' }
+}
+
+// nm type letter of `symbol`, upper case for global symbols
+fn symbol_letter(elf: &Elf, symbol: &Symbol) -> Result<char, ParseError> {
+    let weak = symbol.binding == SymbolBinding::STB_WEAK;
+    let object = symbol.r#type == SymbolType::STT_OBJECT;
+    let letter = match symbol.section {
+        SymbolSection::SHN_COMMON => return Ok('C'),
+        SymbolSection::SHN_UNDEF if weak => return Ok(if object { 'v' } else { 'w' }),
+        SymbolSection::SHN_UNDEF => return Ok('U'),
+        _ if symbol.r#type == SymbolType::STT_GNU_IFUNC => return Ok('i'),
+        _ if weak => return Ok(if object { 'V' } else { 'W' }),
+        _ if symbol.binding == SymbolBinding::STB_GNU_UNIQUE => return Ok('u'),
+        _ if !matches!(symbol.binding, SymbolBinding::STB_GLOBAL | SymbolBinding::STB_LOCAL) => return Ok('?'),
+        SymbolSection::SHN_ABS => 'a',
+        SymbolSection::Section(index) => match elf.section_header(index) {
+            Some(section) => section_letter(elf.section_name(&section)?, &section),
+            None => '?'
+        },
+        SymbolSection::Reserved(_) => '?'
+    };
+    Ok(if symbol.binding == SymbolBinding::STB_GLOBAL { letter.to_ascii_uppercase() } else { letter })
+}
+
+// Symbols to print, already filtered and sorted. `None` when the file has
+// no symbol table at all
+fn list_symbols(elf: &Elf, options: &Options) -> Result<Option<Vec<NmSymbol>>, ParseError> {
+    let table = if options.dynamic { elf.dynamic_symbol_table()? } else { elf.symbol_table()? };
+    let Some(table) = table else { return Ok(None) };
+    let versions = if options.dynamic { elf.symbol_versions()? } else { None };
+
+    let mut symbols = Vec::new();
+    // The first entry is the null symbol
+    for (i, symbol) in table.iter().enumerate().skip(1) {
+        let symbol = symbol?;
+        // Like binutils, file and section symbols are debugging information
+        if matches!(symbol.r#type, SymbolType::STT_FILE | SymbolType::STT_SECTION) { continue }
+
+        let letter = symbol_letter(elf, &symbol)?;
+        let name = match &versions {
+            Some(versions) => VersionedSymbol { symbol, version: versions.version_of(i)? }.to_string(),
+            None => symbol.name.to_string()
+        };
+        let symbol = NmSymbol { name, key: symbol.name.to_string(), value: symbol.value, size: symbol.size, letter };
+        if options.defined_only && symbol.is_undefined() { continue }
+        if options.undefined_only && !symbol.is_undefined() { continue }
+        symbols.push(symbol);
+    }
+
+    // Ties keep the order of the table, even when reversed
+    let order = |ordering: Ordering| if options.reverse { ordering.reverse() } else { ordering };
+    match options.sort {
+        SortKey::Name => symbols.sort_by(|a, b| order(a.key.cmp(&b.key))),
+        // Undefined symbols come first, sorted by name whatever their value
+        SortKey::Address => {
+            let address = |symbol: &NmSymbol| if symbol.is_undefined() { None } else { Some(symbol.value) };
+            symbols.sort_by(|a, b| order((address(a), &a.key).cmp(&(address(b), &b.key))));
+        },
+        // Symbols without a size are left out
+        SortKey::Size => {
+            symbols.retain(|symbol| !symbol.is_undefined() && symbol.size != 0);
+            // Versions of the same symbol stay sorted by address
+            symbols.sort_by(|a, b| order((a.size, &a.key).cmp(&(b.size, &b.key))).then(a.value.cmp(&b.value)));
+        },
+        SortKey::None => {}
+    }
+    Ok(Some(symbols))
+}
+
+fn format_symbol(symbol: &NmSymbol, bits: BitType, options: &Options) -> String {
+    let width = match bits {
+        BitType::_32 => 8,
+        BitType::_64 => 16
+    };
+    if symbol.is_undefined() {
+        return format!("{:width$} {} {}", "", symbol.letter, symbol.name, width = width);
+    }
+
+    // Sorting by size shows the size in place of the value
+    let value = if options.sort == SortKey::Size && !options.print_size { symbol.size } else { symbol.value };
+    let mut line = format!("{:0width$x} ", value, width = width);
+    if options.print_size && (symbol.size != 0 || symbol.letter == 'C') {
+        line += &format!("{:0width$x} ", symbol.size, width = width);
+    }
+    line + &format!("{} {}", symbol.letter, symbol.name)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn args() {
+        use super::*;
+
+        let args: Vec<String> = ["-DnS", "--sort=size", "liba.so"].iter().map(|s| s.to_string()).collect();
+        let (options, files) = parse_args(&args).unwrap().unwrap();
+        assert!(options.dynamic && options.print_size);
+        assert_eq!(options.sort, SortKey::Size);
+        assert_eq!(files, vec!["liba.so"]);
+
+        assert_eq!(parse_args(&[]).unwrap().unwrap().1, vec!["a.out"]);
+        assert!(parse_args(&["-U".to_string(), "-u".to_string()]).is_err());
+        assert!(parse_args(&["--sort=color".to_string()]).is_err());
+    }
+
+    #[test]
+    fn binutils_output() {
+        use super::*;
+        use std::fs;
+
+        // The expected output in testdata/nm comes from GNU nm 2.40 with the
+        // flags the file is named after
+        let compare = |file: &str, flags: &str, options: Options| {
+            let content = fs::read(format!("testdata/{}", file)).unwrap();
+            let elf = Elf::parse(&content).unwrap();
+            let actual: Vec<String> = list_symbols(&elf, &options).unwrap().unwrap().iter()
+                .map(|symbol| format_symbol(symbol, elf.bits(), &options))
+                .collect();
+            let expected = fs::read_to_string(format!("testdata/nm/{}{}.txt", file, flags)).unwrap();
+            assert_eq!(actual, expected.lines().collect::<Vec<_>>(), "nm {} {}", flags, file);
+        };
+        compare("nm.o", "", Options::default());
+        compare("nm.o", "-n", Options { sort: SortKey::Address, ..Options::default() });
+        compare("nm.o", "-u", Options { undefined_only: true, ..Options::default() });
+        compare("libnm.so.1", "-S--size-sort", Options { sort: SortKey::Size, print_size: true, ..Options::default() });
+        compare("libnm.so.1", "-D--defined-only", Options { dynamic: true, defined_only: true, ..Options::default() });
+        // Versions are not part of the sort key
+        compare("hello", "-D", Options { dynamic: true, ..Options::default() });
+    }
+}
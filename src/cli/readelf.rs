@@ -0,0 +1,811 @@
+use super::{entries, read_file};
+use elf_parser::dynamic::{DynamicFlags1, DynamicTag};
+use elf_parser::elf::*;
+use elf_parser::elf_file::ElfFile;
+use elf_parser::note::*;
+use elf_parser::parse_error::ParseError;
+use elf_parser::relocation::RelocationKind;
+use elf_parser::symbol::*;
+use elf_parser::version::*;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: readelf <option(s)> elf-file(s)
+ Display information about the contents of ELF format files
+ Options are:
+  -a --all               Equivalent to: -h -l -S -s -r -d -V -n
+  -h --file-header       Display the ELF file header
+  -l --program-headers   Display the program headers
+     --segments          An alias for --program-headers
+  -S --section-headers   Display the sections' header
+     --sections          An alias for --section-headers
+  -e --headers           Equivalent to: -h -l -S
+  -s --syms              Display the symbol table
+     --symbols           An alias for --syms
+  -n --notes             Display the core notes (if present)
+  -r --relocs            Display the relocations (if present)
+  -d --dynamic           Display the dynamic section (if present)
+  -V --version-info      Display the version sections (if present)
+  -W --wide              Accepted for compatibility, the output is always wide
+  -H --help              Display this information";
+
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Options {
+    file_header: bool,
+    program_headers: bool,
+    section_headers: bool,
+    symbols: bool,
+    dynamic: bool,
+    relocs: bool,
+    notes: bool,
+    versions: bool
+}
+
+impl Options {
+    fn any(&self) -> bool {
+        *self != Options::default()
+    }
+
+    fn all(&mut self) {
+        *self = Options {
+            file_header: true,
+            program_headers: true,
+            section_headers: true,
+            symbols: true,
+            dynamic: true,
+            relocs: true,
+            notes: true,
+            versions: true
+        };
+    }
+}
+
+// `None` when only the help was asked for
+fn parse_args(args: &[String]) -> Result<Option<(Options, Vec<&str>)>, String> {
+    let mut options = Options::default();
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--all" => options.all(),
+            "--file-header" => options.file_header = true,
+            "--program-headers" | "--segments" => options.program_headers = true,
+            "--section-headers" | "--sections" => options.section_headers = true,
+            "--headers" => {
+                options.file_header = true;
+                options.program_headers = true;
+                options.section_headers = true;
+            },
+            "--syms" | "--symbols" => options.symbols = true,
+            "--notes" => options.notes = true,
+            "--relocs" => options.relocs = true,
+            "--dynamic" => options.dynamic = true,
+            "--version-info" => options.versions = true,
+            "--wide" => {},
+            "--help" => return Ok(None),
+            long if long.starts_with("--") => return Err(format!("unrecognized option '{}'", long)),
+            short if short.starts_with('-') && short.len() > 1 => {
+                // Short options can be grouped, like -lS
+                for c in short.chars().skip(1) {
+                    match c {
+                        'a' => options.all(),
+                        'h' => options.file_header = true,
+                        'l' => options.program_headers = true,
+                        'S' => options.section_headers = true,
+                        'e' => {
+                            options.file_header = true;
+                            options.program_headers = true;
+                            options.section_headers = true;
+                        },
+                        's' => options.symbols = true,
+                        'n' => options.notes = true,
+                        'r' => options.relocs = true,
+                        'd' => options.dynamic = true,
+                        'V' => options.versions = true,
+                        'W' => {},
+                        'H' => return Ok(None),
+                        c => return Err(format!("invalid option -- '{}'", c))
+                    }
+                }
+            },
+            file => files.push(file)
+        }
+    }
+    if !options.any() { return Err("Nothing to do.".to_string()) }
+    if files.is_empty() { return Err("Nothing to do.".to_string()) }
+    Ok(Some((options, files)))
+}
+
+// Entry point of the readelf mode, returns a failure when an option is
+// wrong or a file could not be fully displayed
+pub fn run(args: &[String]) -> ExitCode {
+    let (options, files) = match parse_args(args) {
+        Ok(Some(parsed)) => parsed,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(message) => {
+            eprintln!("readelf: Error: {}", message);
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    for path in &files {
+        if files.len() > 1 { println!("\nFile: {}", path) }
+        if !display_file(path, &options) { status = ExitCode::FAILURE }
+    }
+    status
+}
+
+// Errors are reported per display, the other displays of the file still
+// run. Returns whether everything could be displayed
+fn display_file(path: &str, options: &Options) -> bool {
+    let mut ok = true;
+    let mut report = |result: Result<(), ParseError>| if let Err(e) = result {
+        eprintln!("readelf: Error: {}: {}", path, e);
+        ok = false;
+    };
+    let content = match read_file(path) {
+        Ok(content) => content,
+        Err(e) => {
+            report(Err(e));
+            return false;
+        }
+    };
+    let elf = match Elf::parse(&content) {
+        Ok(elf) => elf,
+        Err(e) => {
+            report(Err(e));
+            return false;
+        }
+    };
+
+    // Same order as GNU readelf, whatever the order of the options
+    if options.file_header { display_file_header(&elf) }
+    if options.section_headers { report(display_section_headers(&elf, options)) }
+    if options.program_headers { report(display_program_headers(&elf, options)) }
+    if options.dynamic { report(display_dynamic(&elf)) }
+    if options.relocs { report(display_relocations(&elf)) }
+    if options.symbols { report(display_symbols(&elf)) }
+    if options.versions { report(display_versions(&elf)) }
+    if options.notes { report(display_notes(&elf)) }
+    ok
+}
+
+fn abi_name(abi: ABI) -> String {
+    match abi {
+        ABI::UnixSystemV => "UNIX - System V",
+        ABI::HP_UX => "UNIX - HP-UX",
+        ABI::NetBSD => "UNIX - NetBSD",
+        ABI::Linux => "UNIX - GNU",
+        ABI::SunSolaris => "UNIX - Solaris",
+        ABI::IBM_AIX => "UNIX - AIX",
+        ABI::SGI_Irix => "UNIX - IRIX",
+        ABI::FreeBSD => "UNIX - FreeBSD",
+        ABI::CompaqTRU64 => "UNIX - TRU64",
+        ABI::NovellModesto => "Novell - Modesto",
+        ABI::OpenBSD => "UNIX - OpenBSD",
+        ABI::ARM_EABI => "ARM EABI",
+        ABI::ARM => "ARM",
+        ABI::Standalone => "Standalone App",
+        ABI::Unknown(v) => return format!("<unknown: {:x}>", v)
+    }.to_string()
+}
+
+fn file_type_name(elf: &Elf) -> String {
+    match elf.file_type() {
+        FileType::ET_NONE => "NONE (None)",
+        FileType::ET_REL => "REL (Relocatable file)",
+        FileType::ET_EXEC => "EXEC (Executable file)",
+        FileType::ET_DYN => {
+            let pie = elf.dynamic().ok().flatten()
+                .and_then(|dynamic| dynamic.flags_1())
+                .is_some_and(|flags| flags.contains(DynamicFlags1::PIE));
+            if pie { "DYN (Position-Independent Executable file)" } else { "DYN (Shared object file)" }
+        },
+        FileType::ET_CORE => "CORE (Core file)",
+        FileType::OsSpecific(v) => return format!("OS Specific: ({:x})", v),
+        FileType::ProcessorSpecific(v) => return format!("Processor Specific: ({:x})", v),
+        FileType::Unknown(v) => return format!("<unknown>: {:x}", v)
+    }.to_string()
+}
+
+fn display_file_header(elf: &Elf) {
+    let header = elf.elf_header();
+    let ident = header.e_ident;
+    let field = |name: &str, value: String| println!("  {:<35}{}", name, value);
+
+    println!("ELF Header:");
+    let magic: String = elf.data()[..EI_NIDENT].iter().map(|b| format!("{:02x} ", b)).collect();
+    println!("  Magic:   {}", magic);
+    field("Class:", match ident.e_bits {
+        BitType::_32 => "ELF32",
+        BitType::_64 => "ELF64"
+    }.to_string());
+    field("Data:", match ident.e_endianness {
+        elf_parser::endianness::Endianness::LittleEndian => "2's complement, little endian",
+        elf_parser::endianness::Endianness::BigEndian => "2's complement, big endian"
+    }.to_string());
+    field("Version:", match ident.e_header_format_version {
+        1 => "1 (current)".to_string(),
+        v => v.to_string()
+    });
+    field("OS/ABI:", abi_name(ident.e_abi));
+    field("ABI Version:", ident.e_abi_version.to_string());
+    field("Type:", file_type_name(elf));
+    field("Machine:", header.e_machine.to_string());
+    field("Version:", match header.e_version {
+        HeaderVersion::None => "0x0".to_string(),
+        HeaderVersion::Current => "0x1".to_string(),
+        HeaderVersion::Unknown(v) => format!("{:#x}", v)
+    });
+    field("Entry point address:", format!("{:#x}", header.e_entry));
+    field("Start of program headers:", format!("{} (bytes into file)", header.e_phoff));
+    field("Start of section headers:", format!("{} (bytes into file)", header.e_shoff));
+    field("Flags:", elf.machine_flags().to_string());
+    field("Size of this header:", format!("{} (bytes)", header.e_ehsize));
+    field("Size of program headers:", format!("{} (bytes)", header.e_phentsize));
+    field("Number of program headers:", header.e_phnum.to_string());
+    field("Size of section headers:", format!("{} (bytes)", header.e_shentsize));
+    // Extended numbering keeps the real values in the first section header
+    field("Number of section headers:", match header.e_shnum {
+        0 if elf.section_count() > 0 => format!("0 ({})", elf.section_count()),
+        n => n.to_string()
+    });
+    field("Section header string table index:", match (header.e_shstrndx, elf.shstrndx()) {
+        (SHN_XINDEX, Some(index)) => format!("{} ({})", SHN_XINDEX, index),
+        (index, _) => index.to_string()
+    });
+}
+
+fn section_type_name(r#type: SectionType) -> String {
+    match r#type {
+        SectionType::SHT_GNU_verdef => "VERDEF".to_string(),
+        SectionType::SHT_GNU_verneed => "VERNEED".to_string(),
+        SectionType::SHT_GNU_versym => "VERSYM".to_string(),
+        SectionType::OsSpecific(v) => format!("LOOS+{:x}", v - SectionType::SHT_LOOS),
+        SectionType::ProcessorSpecific(v) => format!("LOPROC+{:x}", v - SectionType::SHT_LOPROC),
+        SectionType::UserSpecific(v) => format!("LOUSER+{:x}", v - SectionType::SHT_LOUSER),
+        SectionType::Unknown(v) => format!("{:08x}: <unknown>", v),
+        known => format!("{:?}", known).trim_start_matches("SHT_").to_string()
+    }
+}
+
+// Letters of the "Flg" column, in the order of the bits
+fn section_flags(flags: SectionFlags, machine: MachineType) -> String {
+    const LETTERS: [(u64, char); 13] = [
+        (0x1, 'W'), (0x2, 'A'), (0x4, 'X'), (0x10, 'M'), (0x20, 'S'), (0x40, 'I'), (0x80, 'L'),
+        (0x100, 'O'), (0x200, 'G'), (0x400, 'T'), (0x800, 'C'), (0x200000, 'R'), (0x80000000, 'E')
+    ];
+    const X86_64_LARGE: u64 = 0x10000000;
+
+    let bits = flags.bits();
+    let mut letters: String = LETTERS.iter().filter(|(bit, _)| bits & bit != 0).map(|(_, c)| *c).collect();
+    let mut rest = bits & !LETTERS.iter().fold(0, |acc, (bit, _)| acc | bit);
+    if machine == MachineType::x64 && rest & X86_64_LARGE != 0 {
+        letters.push('l');
+        rest &= !X86_64_LARGE;
+    }
+    if rest & SectionFlags::MASKOS.bits() != 0 { letters.push('o') }
+    if rest & SectionFlags::MASKPROC.bits() != 0 { letters.push('p') }
+    if rest & !(SectionFlags::MASKOS.bits() | SectionFlags::MASKPROC.bits()) != 0 { letters.push('x') }
+    letters
+}
+
+fn display_section_headers(elf: &Elf, options: &Options) -> Result<(), ParseError> {
+    if elf.section_count() == 0 {
+        println!("\nThere are no sections in this file.");
+        return Ok(());
+    }
+    if !options.file_header {
+        println!("There are {} section headers, starting at offset {:#x}:", elf.section_count(), elf.elf_header().e_shoff);
+    }
+
+    println!("\nSection Headers:");
+    match elf.bits() {
+        BitType::_32 => println!("  [Nr] Name              Type            Addr     Off    Size   ES Flg Lk Inf Al"),
+        BitType::_64 => println!("  [Nr] Name              Type            Address          Off    Size   ES Flg Lk Inf Al")
+    }
+    for (i, section) in elf.section_headers().enumerate() {
+        let address = match elf.bits() {
+            BitType::_32 => format!("{:08x}", section.sh_addr),
+            BitType::_64 => format!("{:016x}", section.sh_addr)
+        };
+        println!("  [{:>2}] {:<17} {:<15} {} {:06x} {:06x} {:02x} {:>3} {:>2} {:>3} {:>2}",
+                 i, elf.section_name(&section)?, section_type_name(section.sh_type), address,
+                 section.sh_offset, section.sh_size, section.sh_entsize, section_flags(section.sh_flags, elf.machine()),
+                 section.sh_link, section.sh_info, section.sh_addralign);
+    }
+    println!("Key to Flags:");
+    println!("  W (write), A (alloc), X (execute), M (merge), S (strings), I (info),");
+    println!("  L (link order), O (extra OS processing required), G (group), T (TLS),");
+    println!("  C (compressed), x (unknown), o (OS specific), E (exclude),");
+    // SHF_GNU_RETAIN is only known to the GNU and FreeBSD ABIs
+    let retain = matches!(elf.elf_header().e_ident.e_abi, ABI::Linux | ABI::FreeBSD);
+    let large = elf.machine() == MachineType::x64;
+    println!("  {}D (mbind), {}p (processor specific)", if retain { "R (retain), " } else { "" }, if large { "l (large), " } else { "" });
+    Ok(())
+}
+
+fn segment_type_name(r#type: ProgramHeaderType, machine: MachineType) -> String {
+    match (r#type, machine) {
+        (ProgramHeaderType::ProcessorSpecific(v), machine) => match (v, machine) {
+            (ProgramHeaderType::PT_MIPS_REGINFO, MachineType::MIPS_I) => "REGINFO".to_string(),
+            (ProgramHeaderType::PT_MIPS_RTPROC, MachineType::MIPS_I) => "RTPROC".to_string(),
+            (ProgramHeaderType::PT_MIPS_OPTIONS, MachineType::MIPS_I) => "OPTIONS".to_string(),
+            (ProgramHeaderType::PT_MIPS_ABIFLAGS, MachineType::MIPS_I) => "ABIFLAGS".to_string(),
+            (ProgramHeaderType::PT_ARM_EXIDX, MachineType::ARM) => "EXIDX".to_string(),
+            (ProgramHeaderType::PT_AARCH64_MEMTAG_MTE, MachineType::AArch64) => "AARCH64_MEMTAG_MTE".to_string(),
+            (ProgramHeaderType::PT_RISCV_ATTRIBUTES, MachineType::RISC_V) => "RISCV_ATTRIBUTES".to_string(),
+            _ => format!("LOPROC+{:#x}", v - ProgramHeaderType::PT_LOPROC)
+        },
+        (r#type, _) if r#type.is_gnu_mbind() => format!("GNU_MBIND+{:#x}", r#type.value() - ProgramHeaderType::PT_GNU_MBIND_LO),
+        (ProgramHeaderType::OsSpecific(v), _) => format!("LOOS+{:#x}", v - ProgramHeaderType::PT_LOOS),
+        (ProgramHeaderType::Unknown(v), _) => format!("<unknown>: {:x}", v),
+        (known, _) => format!("{:?}", known).trim_start_matches("PT_").to_string()
+    }
+}
+
+fn display_program_headers(elf: &Elf, options: &Options) -> Result<(), ParseError> {
+    if elf.segment_count() == 0 {
+        println!("\nThere are no program headers in this file.");
+        return Ok(());
+    }
+    if !options.file_header {
+        println!("\nElf file type is {}", file_type_name(elf));
+        println!("Entry point {:#x}", elf.entry());
+        println!("There are {} program headers, starting at offset {}", elf.segment_count(), elf.elf_header().e_phoff);
+    }
+
+    println!("\nProgram Headers:");
+    match elf.bits() {
+        BitType::_32 => println!("  Type           Offset   VirtAddr   PhysAddr   FileSiz MemSiz  Flg Align"),
+        BitType::_64 => println!("  Type           Offset   VirtAddr           PhysAddr           FileSiz  MemSiz   Flg Align")
+    }
+    for segment in elf.program_headers() {
+        let flag = |f: ProgramHeaderFlag, c: char| if segment.flags.contains(&f) { c } else { ' ' };
+        let flags = format!("{}{}{}", flag(ProgramHeaderFlag::PF_R, 'R'), flag(ProgramHeaderFlag::PF_W, 'W'), flag(ProgramHeaderFlag::PF_X, 'E'));
+        match elf.bits() {
+            BitType::_32 => println!("  {:<14} 0x{:06x} 0x{:08x} 0x{:08x} 0x{:05x} 0x{:05x} {} {:#x}",
+                                     segment_type_name(segment.r#type, elf.machine()), segment.offset, segment.vaddr, segment.paddr,
+                                     segment.filesz, segment.memsz, flags, segment.align),
+            BitType::_64 => println!("  {:<14} 0x{:06x} 0x{:016x} 0x{:016x} 0x{:06x} 0x{:06x} {} {:#x}",
+                                     segment_type_name(segment.r#type, elf.machine()), segment.offset, segment.vaddr, segment.paddr,
+                                     segment.filesz, segment.memsz, flags, segment.align)
+        }
+        if segment.r#type == ProgramHeaderType::PT_INTERP {
+            let data = elf.segment_data(&segment)?;
+            let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+            println!("      [Requesting program interpreter: {}]", String::from_utf8_lossy(&data[..end]));
+        }
+    }
+
+    if elf.section_count() == 0 { return Ok(()) }
+    println!("\n Section to Segment mapping:");
+    println!("  Segment Sections...");
+    for (i, sections) in elf.section_to_segment_mapping().iter().enumerate() {
+        print!("   {:02}     ", i);
+        for &index in sections {
+            if let Some(section) = elf.section_header(index) {
+                print!("{} ", elf.section_name(&section)?);
+            }
+        }
+        println!();
+    }
+    Ok(())
+}
+
+fn dynamic_tag_name(tag: DynamicTag) -> String {
+    match tag {
+        DynamicTag::OsSpecific(v) => format!("Operating System specific: {:x}", v),
+        DynamicTag::ProcessorSpecific(v) => format!("Processor Specific: {:x}", v),
+        DynamicTag::Unknown(v) => format!("<unknown>: {:x}", v),
+        known => format!("{:?}", known).trim_start_matches("DT_").to_string()
+    }
+}
+
+fn display_dynamic(elf: &Elf) -> Result<(), ParseError> {
+    let Some(dynamic) = elf.dynamic()? else {
+        println!("\nThere is no dynamic section in this file.");
+        return Ok(());
+    };
+    let offset = match elf.program_headers().find(|ph| ph.r#type == ProgramHeaderType::PT_DYNAMIC) {
+        Some(segment) => segment.offset,
+        None => elf.section_headers().find(|sh| sh.sh_type == SectionType::SHT_DYNAMIC).map_or(0, |sh| sh.sh_offset)
+    };
+
+    // The terminating DT_NULL is part of the listing
+    println!("\nDynamic section at offset {:#x} contains {}:", offset, entries(dynamic.entries().len() + 1));
+    println!("  Tag        Type                         Name/Value");
+    let null = (DynamicTag::DT_NULL, 0, None);
+    for (tag, value, string) in dynamic.entries().iter().map(|e| (e.tag, e.value, e.string.clone())).chain([null]) {
+        let (tag_hex, width) = match elf.bits() {
+            BitType::_32 => (format!("0x{:08x}", tag.value()), 27usize),
+            BitType::_64 => (format!("0x{:016x}", tag.value()), 19)
+        };
+        let name = dynamic_tag_name(tag);
+        // Strings that can't be read are shown as their offset, like readelf does
+        let value = match (tag, string) {
+            (DynamicTag::DT_NEEDED, Some(Ok(s))) => format!("Shared library: [{}]", s),
+            (DynamicTag::DT_SONAME, Some(Ok(s))) => format!("Library soname: [{}]", s),
+            (DynamicTag::DT_RPATH, Some(Ok(s))) => format!("Library rpath: [{}]", s),
+            (DynamicTag::DT_RUNPATH, Some(Ok(s))) => format!("Library runpath: [{}]", s),
+            (_, Some(Ok(s))) => s.to_string(),
+            (DynamicTag::DT_FLAGS, _) => {
+                let flags = elf_parser::dynamic::DynamicFlags::from_bits(value);
+                flags.names().collect::<Vec<_>>().join(" ")
+            },
+            (DynamicTag::DT_FLAGS_1, _) => {
+                let flags = DynamicFlags1::from_bits(value);
+                format!("Flags: {}", flags.names().collect::<Vec<_>>().join(" "))
+            },
+            (DynamicTag::DT_PLTREL, _) => match value {
+                7 => "RELA".to_string(),
+                17 => "REL".to_string(),
+                v => format!("{:#x}", v)
+            },
+            (DynamicTag::DT_PLTRELSZ | DynamicTag::DT_RELASZ | DynamicTag::DT_RELAENT | DynamicTag::DT_STRSZ |
+             DynamicTag::DT_SYMENT | DynamicTag::DT_RELSZ | DynamicTag::DT_RELENT | DynamicTag::DT_INIT_ARRAYSZ |
+             DynamicTag::DT_FINI_ARRAYSZ | DynamicTag::DT_PREINIT_ARRAYSZ | DynamicTag::DT_RELRSZ |
+             DynamicTag::DT_RELRENT, _) => format!("{} (bytes)", value),
+            (DynamicTag::DT_VERDEFNUM | DynamicTag::DT_VERNEEDNUM | DynamicTag::DT_RELACOUNT |
+             DynamicTag::DT_RELCOUNT, _) => value.to_string(),
+            _ => format!("{:#x}", value)
+        };
+        println!(" {} ({}){:pad$}{}", tag_hex, name, "", value, pad = width.saturating_sub(name.len()).max(1));
+    }
+    Ok(())
+}
+
+// Name of a symbol as printed next to relocations, section symbols show the section name
+fn symbol_display_name(elf: &Elf, symbol: &Symbol) -> Result<String, ParseError> {
+    match symbol.section {
+        SymbolSection::Section(index) if symbol.r#type == SymbolType::STT_SECTION && symbol.name.is_empty() => {
+            match elf.section_header(index) {
+                Some(section) => Ok(elf.section_name(&section)?.to_string()),
+                None => Ok(String::new())
+            }
+        },
+        _ => Ok(symbol.name.to_string())
+    }
+}
+
+fn display_relocations(elf: &Elf) -> Result<(), ParseError> {
+    let tables = elf.relocation_tables()?;
+    if tables.is_empty() {
+        println!("\nThere are no relocations in this file.");
+        return Ok(());
+    }
+
+    let versions = elf.symbol_versions()?;
+    for table in &tables {
+        let section = elf.section_header(table.section_index()).ok_or(ParseError::InvalidSectionIndex(table.section_index()))?;
+        println!("\nRelocation section '{}' at offset {:#x} contains {}:",
+                 elf.section_name(&section)?, section.sh_offset, entries(table.len()));
+
+        // Packed relative relocations only have an address
+        if table.kind() == RelocationKind::Relr {
+            let offsets: Vec<u64> = table.iter().map(|relocation| relocation.map(|r| r.offset)).collect::<Result<_, _>>()?;
+            println!("  {} offsets", offsets.len());
+            for offset in offsets {
+                match elf.bits() {
+                    BitType::_32 => println!("{:08x}", offset),
+                    BitType::_64 => println!("{:016x}", offset)
+                }
+            }
+            continue;
+        }
+
+        let rela = table.kind() == RelocationKind::Rela;
+        match (elf.bits(), rela) {
+            (BitType::_32, false) => println!(" Offset     Info    Type                Sym. Value  Symbol's Name"),
+            (BitType::_32, true) => println!(" Offset     Info    Type                Sym. Value  Symbol's Name + Addend"),
+            (BitType::_64, false) => println!("    Offset             Info             Type               Symbol's Value  Symbol's Name"),
+            (BitType::_64, true) => println!("    Offset             Info             Type               Symbol's Value  Symbol's Name + Addend")
+        }
+
+        let symbols = match table.symbol_table_index() {
+            0 => None,
+            index => Some((SymbolTable::parse(elf, index)?,
+                           elf.section_header(index).is_some_and(|s| s.sh_type == SectionType::SHT_DYNSYM)))
+        };
+        for relocation in table.iter() {
+            let relocation = relocation?;
+            match elf.bits() {
+                BitType::_32 => print!("{:08x}  {:08x} {:<22}", relocation.offset, relocation.info, relocation.r#type.to_string()),
+                BitType::_64 => print!("{:016x}  {:016x} {:<22}", relocation.offset, relocation.info, relocation.r#type.to_string())
+            }
+            let symbol = match &symbols {
+                Some((symbols, dynamic)) if relocation.symbol != 0 => {
+                    let symbol = symbols.get(relocation.symbol as usize)?;
+                    let name = match (&versions, dynamic) {
+                        (Some(versions), true) => {
+                            let version = versions.version_of(relocation.symbol as usize)?;
+                            VersionedSymbol { symbol, version }.to_string()
+                        },
+                        _ => symbol_display_name(elf, &symbol)?
+                    };
+                    Some((symbol.value, name))
+                },
+                _ => None
+            };
+            match (symbol, relocation.addend) {
+                (Some((value, name)), addend) => {
+                    match elf.bits() {
+                        BitType::_32 => print!(" {:08x}   {}", value, name),
+                        BitType::_64 => print!(" {:016x} {}", value, name)
+                    }
+                    match addend {
+                        Some(a) if a < 0 => print!(" - {:x}", a.unsigned_abs()),
+                        Some(a) => print!(" + {:x}", a),
+                        None => {}
+                    }
+                },
+                (None, Some(addend)) => {
+                    let width = match elf.bits() {
+                        BitType::_32 => 12,
+                        BitType::_64 => 20
+                    };
+                    print!("{:width$}{:x}", "", addend, width = width);
+                },
+                (None, None) => {}
+            }
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn symbol_type_name(r#type: SymbolType) -> String {
+    match r#type {
+        SymbolType::STT_GNU_IFUNC => "IFUNC".to_string(),
+        SymbolType::OsSpecific(v) => format!("<OS specific>: {}", v),
+        SymbolType::ProcessorSpecific(v) => format!("<processor specific>: {}", v),
+        SymbolType::Unknown(v) => format!("<unknown>: {}", v),
+        known => format!("{:?}", known).trim_start_matches("STT_").to_string()
+    }
+}
+
+fn symbol_binding_name(binding: SymbolBinding) -> String {
+    match binding {
+        SymbolBinding::STB_GNU_UNIQUE => "UNIQUE".to_string(),
+        SymbolBinding::OsSpecific(v) => format!("<OS specific>: {}", v),
+        SymbolBinding::ProcessorSpecific(v) => format!("<processor specific>: {}", v),
+        SymbolBinding::Unknown(v) => format!("<unknown>: {}", v),
+        known => format!("{:?}", known).trim_start_matches("STB_").to_string()
+    }
+}
+
+fn symbol_section_name(section: SymbolSection) -> String {
+    match section {
+        SymbolSection::SHN_UNDEF => "UND".to_string(),
+        SymbolSection::SHN_ABS => "ABS".to_string(),
+        SymbolSection::SHN_COMMON => "COM".to_string(),
+        SymbolSection::Section(index) => format!("{:3}", index),
+        SymbolSection::Reserved(index) => format!("RSV[{:#06x}]", index)
+    }
+}
+
+fn display_symbols(elf: &Elf) -> Result<(), ParseError> {
+    let versions = elf.symbol_versions()?;
+    for (index, section) in elf.section_headers().enumerate() {
+        if !matches!(section.sh_type, SectionType::SHT_SYMTAB | SectionType::SHT_DYNSYM) { continue }
+        let table = SymbolTable::parse(elf, index)?;
+        println!("\nSymbol table '{}' contains {}:", elf.section_name(&section)?, entries(table.len()));
+        match elf.bits() {
+            BitType::_32 => println!("   Num:    Value  Size Type    Bind   Vis      Ndx Name"),
+            BitType::_64 => println!("   Num:    Value          Size Type    Bind   Vis      Ndx Name")
+        }
+
+        for (i, symbol) in table.iter().enumerate() {
+            let symbol = symbol?;
+            let value = match elf.bits() {
+                BitType::_32 => format!("{:08x}", symbol.value),
+                BitType::_64 => format!("{:016x}", symbol.value)
+            };
+            // Big sizes switch to hexadecimal
+            let size = if symbol.size <= 99999 { format!("{:5}", symbol.size) } else { format!("{:#x}", symbol.size) };
+            let name = match &versions {
+                Some(versions) if section.sh_type == SectionType::SHT_DYNSYM => {
+                    let versioned = VersionedSymbol { symbol, version: versions.version_of(i)? };
+                    match versioned.version {
+                        // Required versions also show their index
+                        Some(version) if version.file.is_some() => format!("{} ({})", versioned, version.index),
+                        _ => versioned.to_string()
+                    }
+                },
+                _ => symbol_display_name(elf, &symbol)?
+            };
+            println!("{:>6}: {} {} {:<7} {:<6} {:<7} {:>4} {}", i, value, size, symbol_type_name(symbol.r#type),
+                     symbol_binding_name(symbol.binding), format!("{:?}", symbol.visibility).trim_start_matches("STV_"),
+                     symbol_section_name(symbol.section), name);
+        }
+    }
+    Ok(())
+}
+
+fn version_flags(flags: u16) -> String {
+    if flags == 0 { return "none".to_string() }
+    let mut names = Vec::new();
+    if flags & VER_FLG_BASE != 0 { names.push("BASE".to_string()) }
+    if flags & VER_FLG_WEAK != 0 { names.push("WEAK".to_string()) }
+    if flags & 0x4 != 0 { names.push("INFO".to_string()) }
+    if flags & !0x7 != 0 { names.push("<unknown>".to_string()) }
+    names.join(" | ")
+}
+
+// Offsets of the version entries, printed as "000000" for the first one
+fn version_offset(offset: usize) -> String {
+    if offset == 0 { "000000".to_string() } else { format!("{:#06x}", offset) }
+}
+
+fn display_versions(elf: &Elf) -> Result<(), ParseError> {
+    let Some(versions) = elf.symbol_versions()? else {
+        println!("\nNo version information found in this file.");
+        return Ok(());
+    };
+
+    for section in elf.section_headers() {
+        let link = elf.section_header(section.sh_link as usize).map(|s| elf.section_name(&s)).transpose()?.unwrap_or("");
+        let location = format!(" Addr: 0x{:016x}  Offset: 0x{:08x}  Link: {} ({})", section.sh_addr, section.sh_offset, section.sh_link, link);
+        match section.sh_type {
+            SectionType::SHT_GNU_versym => {
+                let count = (section.sh_size / 2) as usize;
+                println!("\nVersion symbols section '{}' contains {}:", elf.section_name(&section)?, entries(count));
+                println!("{}", location);
+                for row in (0..count).step_by(4) {
+                    print!("  {:03x}:", row);
+                    for i in row..(row + 4).min(count) {
+                        let versym = versions.versym(i)?;
+                        match versym {
+                            VER_NDX_LOCAL => print!("   0 (*local*)    "),
+                            VER_NDX_GLOBAL => print!("   1 (*global*)   "),
+                            _ => {
+                                let name = versions.version_of(i).ok().flatten().map_or("???", |v| v.name);
+                                let index = format!("{:4x}{}", versym & VERSYM_VERSION, if versym & VERSYM_HIDDEN != 0 { 'h' } else { ' ' });
+                                let name = format!("({}{:<width$}", name, ")", width = 12usize.abs_diff(name.len()));
+                                print!("{}{}{:pad$}", index, name, "", pad = 18usize.saturating_sub(index.len() + name.len()));
+                            }
+                        }
+                    }
+                    println!();
+                }
+            },
+            SectionType::SHT_GNU_verdef => {
+                println!("\nVersion definition section '{}' contains {}:", elf.section_name(&section)?, entries(section.sh_info as usize));
+                println!("{}", location);
+                for definition in versions.definitions() {
+                    println!("  {}: Rev: {}  Flags: {}  Index: {}  Cnt: {}  Name: {}", version_offset(definition.offset),
+                             definition.version, version_flags(definition.flags), definition.index,
+                             definition.parents.len() + 1, definition.name);
+                    // The linker writes the auxiliary entries right after the definition
+                    for (k, parent) in definition.parents.iter().enumerate() {
+                        let offset = definition.offset + VERDEF_SIZE + VERDAUX_SIZE * (k + 1);
+                        println!("  {}: Parent {}: {}", version_offset(offset), k + 1, parent);
+                    }
+                }
+            },
+            SectionType::SHT_GNU_verneed => {
+                println!("\nVersion needs section '{}' contains {}:", elf.section_name(&section)?, entries(section.sh_info as usize));
+                println!("{}", location);
+                for requirement in versions.requirements() {
+                    println!("  {}: Version: {}  File: {}  Cnt: {}", version_offset(requirement.offset),
+                             requirement.version, requirement.file, requirement.versions.len());
+                    for version in &requirement.versions {
+                        println!("  {}:   Name: {}  Flags: {}  Version: {}", version_offset(version.offset),
+                                 version.name, version_flags(version.flags), version.index);
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn gnu_property(property: &GnuProperty) -> String {
+    let join = |names: Vec<&str>| if names.is_empty() { "<None>".to_string() } else { names.join(", ") };
+    match property {
+        GnuProperty::StackSize(size) => format!("stack size: {:#x}", size),
+        GnuProperty::NoCopyOnProtected => "no copy on protected".to_string(),
+        GnuProperty::X86IsaNeeded(levels) => format!("x86 ISA needed: {}", join(levels.names().collect())),
+        GnuProperty::X86IsaUsed(levels) => format!("x86 ISA used: {}", join(levels.names().collect())),
+        GnuProperty::X86Features { ibt, shstk, .. } => {
+            let names = [(*ibt, "IBT"), (*shstk, "SHSTK")].iter().filter(|(set, _)| *set).map(|(_, n)| *n).collect();
+            format!("x86 feature: {}", join(names))
+        },
+        GnuProperty::AArch64Features { bti, pac, gcs, .. } => {
+            let names = [(*bti, "BTI"), (*pac, "PAC"), (*gcs, "GCS")].iter().filter(|(set, _)| *set).map(|(_, n)| *n).collect();
+            format!("AArch64 feature: {}", join(names))
+        },
+        GnuProperty::Unknown { pr_type, .. } => format!("<unknown type {:#x}>", pr_type)
+    }
+}
+
+fn display_note(elf: &Elf, note: &Note) -> Result<(), ParseError> {
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    print!("  {:<20} 0x{:08x}\t", String::from_utf8_lossy(note.name), note.desc.len());
+    match note.decode(elf.bits(), elf.endianness())? {
+        NoteDescriptor::GnuAbiTag { os, major, minor, patch } => {
+            let os = match os {
+                GnuAbiOs::Linux => "Linux".to_string(),
+                GnuAbiOs::Hurd => "Hurd".to_string(),
+                GnuAbiOs::Solaris => "Solaris".to_string(),
+                GnuAbiOs::FreeBSD => "FreeBSD".to_string(),
+                GnuAbiOs::Unknown(v) => format!("Unknown ({})", v)
+            };
+            println!("NT_GNU_ABI_TAG (ABI version tag)\t    OS: {}, ABI: {}.{}.{}", os, major, minor, patch);
+        },
+        NoteDescriptor::GnuBuildId(id) => println!("NT_GNU_BUILD_ID (unique build ID bitstring)\t    Build ID: {}", hex(id)),
+        NoteDescriptor::GnuGoldVersion(version) => println!("NT_GNU_GOLD_VERSION (gold version)\t    Version: {}", version),
+        NoteDescriptor::GnuProperties(properties) => {
+            let properties: Vec<String> = properties.iter().map(gnu_property).collect();
+            println!("NT_GNU_PROPERTY_TYPE_0\t      Properties: {}", properties.join("\n\t"));
+        },
+        NoteDescriptor::GoBuildId(id) => println!("GO BUILDID\t    Build ID: {}", id),
+        NoteDescriptor::GoPackageList(list) => println!("GO PKGLIST\t    {}", list),
+        NoteDescriptor::GoAbiHash(hash) => println!("GO ABIHASH\t    {}", hash),
+        NoteDescriptor::GoDependencies(deps) => println!("GO DEPS\t    {}", deps),
+        NoteDescriptor::FdoPackageMetadata(json) => println!("FDO_PACKAGING_METADATA\t    Packaging Metadata: {}", json),
+        NoteDescriptor::Unknown => {
+            println!("Unknown note type: (0x{:08x})", note.n_type);
+            let data: Vec<String> = note.desc.iter().map(|b| format!("{:02x}", b)).collect();
+            println!("   description data: {} ", data.join(" "));
+        }
+    }
+    Ok(())
+}
+
+fn display_notes(elf: &Elf) -> Result<(), ParseError> {
+    let mut sections = elf.section_headers().filter(|sh| sh.sh_type == SectionType::SHT_NOTE).peekable();
+    if sections.peek().is_some() {
+        for section in sections {
+            println!("\nDisplaying notes found in: {}", elf.section_name(&section)?);
+            println!("  Owner                Data size \tDescription");
+            for note in NoteIterator::new(elf.section_data(&section)?, section.sh_offset, section.sh_addralign, elf.endianness()) {
+                display_note(elf, &note?)?;
+            }
+        }
+        return Ok(());
+    }
+
+    // Without section headers the notes are found through the segments
+    for segment in elf.program_headers().filter(|ph| ph.r#type == ProgramHeaderType::PT_NOTE) {
+        println!("\nDisplaying notes found at file offset 0x{:08x} with length 0x{:08x}:", segment.offset, segment.filesz);
+        println!("  Owner                Data size \tDescription");
+        for note in NoteIterator::new(elf.segment_data(&segment)?, segment.offset, segment.align, elf.endianness()) {
+            display_note(elf, &note?)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn args() {
+        use super::*;
+
+        let args: Vec<String> = ["-lS", "--dynamic", "-W", "a.out", "b.so"].iter().map(|s| s.to_string()).collect();
+        let (options, files) = parse_args(&args).unwrap().unwrap();
+        assert!(options.program_headers && options.section_headers && options.dynamic && !options.symbols);
+        assert_eq!(files, vec!["a.out", "b.so"]);
+
+        let args: Vec<String> = ["-a", "a.out"].iter().map(|s| s.to_string()).collect();
+        assert!(parse_args(&args).unwrap().unwrap().0.versions);
+        assert!(parse_args(&["-H".to_string()]).unwrap().is_none());
+        assert!(parse_args(&["-x".to_string(), "a.out".to_string()]).is_err());
+        assert!(parse_args(&["a.out".to_string()]).is_err());
+    }
+
+    #[test]
+    fn section_flag_letters() {
+        use super::*;
+
+        assert_eq!(section_flags(SectionFlags::from_bits(0x2 | 0x4), MachineType::x64), "AX");
+        assert_eq!(section_flags(SectionFlags::from_bits(0x42), MachineType::x64), "AI");
+        assert_eq!(section_flags(SectionFlags::from_bits(0x10000002), MachineType::x64), "Al");
+        assert_eq!(section_flags(SectionFlags::from_bits(0x10000002), MachineType::ARM), "Ap");
+    }
+}
@@ -0,0 +1,326 @@
+use super::read_file;
+use elf_parser::elf::*;
+use elf_parser::elf_file::ElfFile;
+use elf_parser::parse_error::ParseError;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: size [option(s)] [file(s)]
+ Displays the sizes of sections inside binary files
+ If no input file(s) are specified, a.out is assumed
+ The options are:
+  -A|-B     --format={sysv|berkeley}  Select output style (default is berkeley)
+            --size-sort               Per section output, largest sections first
+  -t        --totals                  Display the total sizes (Berkeley only)
+  -h        --help                    Display this information";
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum Format {
+    Berkeley,
+    SysV
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    format: Format,
+    size_sort: bool,
+    totals: bool
+}
+
+// `None` when only the help was asked for
+fn parse_args(args: &[String]) -> Result<Option<(Options, Vec<&str>)>, String> {
+    let mut options = Options { format: Format::Berkeley, size_sort: false, totals: false };
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--format=berkeley" => options.format = Format::Berkeley,
+            "--format=sysv" => options.format = Format::SysV,
+            "--size-sort" => {
+                options.format = Format::SysV;
+                options.size_sort = true;
+            },
+            "--totals" => options.totals = true,
+            "--help" => return Ok(None),
+            long if long.starts_with("--") => return Err(format!("unrecognized option '{}'", long)),
+            short if short.starts_with('-') && short.len() > 1 => {
+                // Short options can be grouped, like -At
+                for c in short.chars().skip(1) {
+                    match c {
+                        'A' => options.format = Format::SysV,
+                        'B' => options.format = Format::Berkeley,
+                        't' => options.totals = true,
+                        'h' => return Ok(None),
+                        c => return Err(format!("invalid option -- '{}'", c))
+                    }
+                }
+            },
+            file => files.push(file)
+        }
+    }
+    if files.is_empty() { files.push("a.out") }
+    Ok(Some((options, files)))
+}
+
+// Entry point of the size mode, returns a failure when an option is wrong
+// or a file could not be parsed
+pub fn run(args: &[String]) -> ExitCode {
+    let (options, files) = match parse_args(args) {
+        Ok(Some(parsed)) => parsed,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(message) => {
+            eprintln!("size: {}", message);
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    let mut totals = Totals::default();
+    // Like binutils the header comes with the first file that could be read
+    let mut header = false;
+    for path in &files {
+        let content = match read_file(path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("size: {}: {}", path, e);
+                status = ExitCode::FAILURE;
+                continue;
+            }
+        };
+        let sizes = Elf::parse(&content).and_then(|elf| section_sizes(&elf));
+        match sizes {
+            Ok(sizes) => match options.format {
+                Format::Berkeley => {
+                    if !header {
+                        println!("{}", BERKELEY_HEADER);
+                        header = true;
+                    }
+                    let file_totals = Totals::of(&sizes);
+                    println!("{}", berkeley_line(&file_totals, path));
+                    totals.add(&file_totals);
+                },
+                Format::SysV => print!("{}", sysv_table(&sizes, path, options.size_sort))
+            },
+            Err(e) => {
+                eprintln!("size: {}: {}", path, e);
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    if options.format == Format::Berkeley && options.totals {
+        println!("{}", berkeley_line(&totals, "(TOTALS)"));
+    }
+    status
+}
+
+// Where `size` counts a section
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum SizeClass {
+    Text, // Code and read only data
+    Data,
+    Bss,
+    None // Not loaded
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct SectionSize {
+    name: String,
+    size: u64,
+    addr: u64,
+    class: SizeClass
+}
+
+// Sizes of the sections like binutils sees them, symbol tables, their strings
+// and the relocations applying to other sections are left out. Files without
+// sections use their segments instead. Sums saturate, the sizes come straight
+// from the headers
+fn section_sizes(elf: &Elf) -> Result<Vec<SectionSize>, ParseError> {
+    if elf.section_count() == 0 {
+        return Ok(elf.program_headers().filter(|ph| ph.r#type == ProgramHeaderType::PT_LOAD).flat_map(|segment| {
+            let name = format!("{:?}", segment.r#type).trim_start_matches("PT_").to_string();
+            let class = if segment.flags.contains(&ProgramHeaderFlag::PF_W) { SizeClass::Data } else { SizeClass::Text };
+            let bss = SectionSize { name: name.clone(), size: segment.memsz.saturating_sub(segment.filesz), addr: segment.vaddr.saturating_add(segment.filesz), class: SizeClass::Bss };
+            [SectionSize { name, size: segment.filesz, addr: segment.vaddr, class }, bss]
+        }).filter(|section| section.size != 0).collect());
+    }
+
+    let symtab = elf.section_index_by_type(SectionType::SHT_SYMTAB);
+    let symtab_strings = symtab.and_then(|index| elf.section_header(index)).map(|section| section.sh_link as usize);
+    let mut sizes = Vec::new();
+    for (index, section) in elf.section_headers().enumerate().skip(1) {
+        let hidden = match section.sh_type {
+            SectionType::SHT_SYMTAB | SectionType::SHT_SYMTAB_SHNDX => true,
+            SectionType::SHT_STRTAB => Some(index) == symtab_strings || Some(index) == elf.shstrndx(),
+            SectionType::SHT_REL | SectionType::SHT_RELA => symtab.is_some_and(|symtab| section.sh_link as usize == symtab) && section.sh_info != 0,
+            _ => false
+        };
+        if hidden { continue }
+
+        let class = if !section.sh_flags.contains(SectionFlags::ALLOC) {
+            SizeClass::None
+        } else if section.sh_flags.contains(SectionFlags::EXECINSTR) || !section.sh_flags.contains(SectionFlags::WRITE) {
+            SizeClass::Text
+        } else if section.sh_type != SectionType::SHT_NOBITS {
+            SizeClass::Data
+        } else {
+            SizeClass::Bss
+        };
+        sizes.push(SectionSize { name: elf.section_name(&section)?.to_string(), size: section.sh_size, addr: section.sh_addr, class });
+    }
+    Ok(sizes)
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+struct Totals {
+    text: u64,
+    data: u64,
+    bss: u64
+}
+
+impl Totals {
+    fn of(sizes: &[SectionSize]) -> Self {
+        let mut totals = Totals::default();
+        for section in sizes {
+            match section.class {
+                SizeClass::Text => totals.text = totals.text.saturating_add(section.size),
+                SizeClass::Data => totals.data = totals.data.saturating_add(section.size),
+                SizeClass::Bss => totals.bss = totals.bss.saturating_add(section.size),
+                SizeClass::None => {}
+            }
+        }
+        totals
+    }
+
+    fn add(&mut self, other: &Totals) {
+        self.text = self.text.saturating_add(other.text);
+        self.data = self.data.saturating_add(other.data);
+        self.bss = self.bss.saturating_add(other.bss);
+    }
+
+    fn total(&self) -> u64 {
+        self.text.saturating_add(self.data).saturating_add(self.bss)
+    }
+}
+
+const BERKELEY_HEADER: &str = "   text\t   data\t    bss\t    dec\t    hex\tfilename";
+
+fn berkeley_line(totals: &Totals, name: &str) -> String {
+    format!("{:>7}\t{:>7}\t{:>7}\t{:>7}\t{:>7x}\t{}", totals.text, totals.data, totals.bss, totals.total(), totals.total(), name)
+}
+
+fn sysv_table(sizes: &[SectionSize], name: &str, size_sort: bool) -> String {
+    let mut rows: Vec<&SectionSize> = sizes.iter().collect();
+    // Largest first, the order of the file breaks ties
+    if size_sort { rows.sort_by_key(|section| std::cmp::Reverse(section.size)) }
+
+    let total = sizes.iter().fold(0u64, |total, section| total.saturating_add(section.size));
+    let name_width = rows.iter().map(|section| section.name.len()).fold("section".len(), usize::max);
+    // The total goes in the size column too
+    let size_width = rows.iter().map(|section| section.size.to_string().len()).fold(total.to_string().len().max("size".len()), usize::max);
+    let addr_width = rows.iter().map(|section| section.addr.to_string().len()).fold("addr".len(), usize::max);
+
+    let mut table = format!("{}  :\n", name);
+    table += &format!("{:<name_width$}   {:>size_width$}   {:>addr_width$}\n", "section", "size", "addr");
+    for section in rows {
+        table += &format!("{:<name_width$}   {:>size_width$}   {:>addr_width$}\n", section.name, section.size, section.addr);
+    }
+    table += &format!("{:<name_width$}   {:>size_width$}\n\n\n", "Total", total);
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn args() {
+        use super::*;
+
+        let args: Vec<String> = ["-At", "a.o"].iter().map(|s| s.to_string()).collect();
+        let (options, files) = parse_args(&args).unwrap().unwrap();
+        assert_eq!(options, Options { format: Format::SysV, size_sort: false, totals: true });
+        assert_eq!(files, vec!["a.o"]);
+
+        let args = ["--size-sort".to_string()];
+        let (options, files) = parse_args(&args).unwrap().unwrap();
+        assert!(options.format == Format::SysV && options.size_sort);
+        assert_eq!(files, vec!["a.out"]);
+        assert!(parse_args(&["--format=xml".to_string()]).is_err());
+    }
+
+    #[test]
+    fn binutils_output() {
+        use super::*;
+        use std::fs;
+
+        // The expected output in testdata/size comes from GNU size 2.40, run
+        // with -t and -A on the same files
+        let files = ["testdata/size.o", "testdata/libsize.so.1", "testdata/hello"];
+        let mut berkeley = vec![BERKELEY_HEADER.to_string()];
+        let mut sysv = String::new();
+        let mut totals = Totals::default();
+        for file in files {
+            let content = fs::read(file).unwrap();
+            let elf = Elf::parse(&content).unwrap();
+            let sizes = section_sizes(&elf).unwrap();
+            berkeley.push(berkeley_line(&Totals::of(&sizes), file));
+            totals.add(&Totals::of(&sizes));
+            sysv += &sysv_table(&sizes, file, false);
+        }
+        berkeley.push(berkeley_line(&totals, "(TOTALS)"));
+
+        assert_eq!(fs::read_to_string("testdata/size/berkeley-t.txt").unwrap().lines().collect::<Vec<_>>(), berkeley);
+        assert_eq!(fs::read_to_string("testdata/size/sysv.txt").unwrap(), sysv);
+
+        // Sorting keeps the same rows
+        let content = fs::read("testdata/libsize.so.1").unwrap();
+        let elf = Elf::parse(&content).unwrap();
+        let sizes = section_sizes(&elf).unwrap();
+        let sorted = sysv_table(&sizes, "libsize.so.1", true);
+        let rows: Vec<u64> = sorted.lines().skip(2).take(sizes.len()).map(|line| line.split_whitespace().nth(1).unwrap().parse().unwrap()).collect();
+        assert!(rows.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+
+    #[test]
+    fn overflow() {
+        use super::*;
+        use std::fs;
+
+        let content = fs::read("testdata/hello").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let header = |name: &str| {
+            let index = elf.shtable().iter().position(|sh| elf.section_name(sh) == Ok(name)).unwrap();
+            elf.header().e_shoff as usize + index * elf.header().e_shentsize as usize
+        };
+
+        // Two huge SHT_NOBITS sections, their sum doesn't fit in 64 bits
+        let mut huge = content.clone();
+        for name in [".tbss", ".bss"] {
+            let sh_size = header(name) + 0x20;
+            huge[sh_size..sh_size + 8].copy_from_slice(&(1u64 << 63).to_le_bytes());
+        }
+        let sizes = section_sizes(&Elf::parse(&huge).unwrap()).unwrap();
+        let totals = Totals::of(&sizes);
+        assert_eq!(totals.bss, u64::MAX);
+        assert_eq!(totals.total(), u64::MAX);
+        let mut all = totals;
+        all.add(&totals);
+        assert_eq!(all.bss, u64::MAX);
+        assert!(berkeley_line(&totals, "huge").ends_with(&format!("{}\t{:x}\thuge", u64::MAX, u64::MAX)));
+        let table = sysv_table(&sizes, "huge", true);
+        assert!(table.lines().any(|line| line.starts_with("Total") && line.ends_with(&u64::MAX.to_string())));
+
+        // A segment loaded at the very end of the address space, in a file
+        // without sections
+        let mut high = content.clone();
+        high[0x28..0x30].fill(0); // e_shoff
+        high[0x3C..0x40].fill(0); // e_shnum, e_shstrndx
+        let load = elf.phtable().iter().position(|ph| ph.r#type == ProgramHeaderType::PT_LOAD && ph.memsz > ph.filesz).unwrap();
+        let p_vaddr = elf.header().e_phoff as usize + load * elf.header().e_phentsize as usize + 0x10;
+        high[p_vaddr..p_vaddr + 8].copy_from_slice(&0xffff_ffff_ffff_fff0u64.to_le_bytes());
+        let sizes = section_sizes(&Elf::parse(&high).unwrap()).unwrap();
+        let bss = sizes.iter().find(|section| section.class == SizeClass::Bss).unwrap();
+        assert_eq!(bss.addr, u64::MAX);
+        sysv_table(&sizes, "high", false);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::elf::BitType;
+use crate::endianness::{self, Endianness};
+use crate::parse_error::ParseError;
+
+// Reads fields one after the other from a buffer. Every read is checked,
+// running out of data gives `ParseError::UnexpectedEnd` instead of a panic.
+// The errors add `base`, where the buffer is in the file, to the offsets
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    data: &'a [u8],
+    base: u64,
+    offset: usize,
+    endian: Endianness
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(data: &'a [u8], offset: usize, endian: Endianness) -> Self {
+        Self { data, base: 0, offset, endian }
+    }
+
+    // Same for a buffer found at `base` in the file
+    pub fn with_base(data: &'a [u8], base: u64, offset: usize, endian: Endianness) -> Self {
+        Self { data, base, offset, endian }
+    }
+
+    // Cursor on entry `index` of a table of `entsize` bytes entries starting
+    // at `table` in a buffer found at `base`. The whole entry must be in
+    // `data`, even the bytes past the fields the caller knows about
+    pub fn entry(data: &'a [u8], base: u64, table: u64, index: usize, entsize: usize, endian: Endianness) -> Result<Self, ParseError> {
+        let start = (index as u64).checked_mul(entsize as u64).and_then(|offset| offset.checked_add(table));
+        let left = |start: u64| (data.len() as u64).saturating_sub(start);
+        match start {
+            Some(start) if entsize as u64 <= left(start) => Ok(Self::with_base(data, base, start as usize, endian)),
+            _ => Err(ParseError::UnexpectedEnd {
+                offset: start.map_or(u64::MAX, |start| base.saturating_add(start)),
+                expected: entsize as u64,
+                actual: start.map_or(0, left)
+            })
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.offset)
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        let left = self.remaining();
+        if len > left {
+            return Err(ParseError::UnexpectedEnd { offset: self.base.saturating_add(self.offset as u64), expected: len as u64, actual: left as u64 });
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+
+    pub fn read8(&mut self) -> Result<u8, ParseError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    pub fn read16(&mut self) -> Result<u16, ParseError> {
+        Ok(endianness::read16(&self.array()?, self.endian))
+    }
+
+    pub fn read32(&mut self) -> Result<u32, ParseError> {
+        Ok(endianness::read32(&self.array()?, self.endian))
+    }
+
+    pub fn read64(&mut self) -> Result<u64, ParseError> {
+        Ok(endianness::read64(&self.array()?, self.endian))
+    }
+
+    // Address sized value of the class
+    pub fn read_word(&mut self, bits: BitType) -> Result<u64, ParseError> {
+        match bits {
+            BitType::_32 => self.read32().map(u64::from),
+            BitType::_64 => self.read64()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn reads() {
+        use super::*;
+
+        let data = [0x7F, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+        let mut cursor = Cursor::new(&data, 1, Endianness::BigEndian);
+        assert_eq!(cursor.read16(), Ok(0x0102));
+        assert_eq!(cursor.read32(), Ok(0x03040506));
+        assert_eq!(cursor.offset(), 7);
+        assert_eq!(cursor.read32(), Err(ParseError::UnexpectedEnd { offset: 7, expected: 4, actual: 2 }));
+        assert_eq!(cursor.read16(), Ok(0x0708));
+        assert_eq!(cursor.read8(), Err(ParseError::UnexpectedEnd { offset: 9, expected: 1, actual: 0 }));
+
+        let mut cursor = Cursor::new(&data, 1, Endianness::LittleEndian);
+        assert_eq!(cursor.read64(), Ok(0x0807060504030201));
+        assert_eq!(Cursor::new(&data, usize::MAX, Endianness::LittleEndian).read8().unwrap_err(),
+                   ParseError::UnexpectedEnd { offset: usize::MAX as u64, expected: 1, actual: 0 });
+
+        // Entries are found with the stride of the table, not the size of their fields
+        let mut entry = Cursor::entry(&data, 0, 1, 1, 3, Endianness::LittleEndian).unwrap();
+        assert_eq!(entry.read8(), Ok(0x04));
+        assert!(Cursor::entry(&data, 0, 1, 2, 3, Endianness::LittleEndian).is_err());
+        assert_eq!(Cursor::entry(&data, 0, u64::MAX, 1, 3, Endianness::LittleEndian).unwrap_err(),
+                   ParseError::UnexpectedEnd { offset: u64::MAX, expected: 3, actual: 0 });
+
+        // Offsets in the errors are from the start of the file
+        let mut entry = Cursor::entry(&data, 0x100, 0, 2, 3, Endianness::LittleEndian).unwrap();
+        assert_eq!(entry.bytes(2), Ok(&data[6..8]));
+        assert_eq!(Cursor::entry(&data, 0x100, 0, 3, 3, Endianness::LittleEndian).unwrap_err(),
+                   ParseError::UnexpectedEnd { offset: 0x109, expected: 3, actual: 0 });
+        assert_eq!(Cursor::with_base(&data, 0x100, 8, Endianness::LittleEndian).read16().unwrap_err(),
+                   ParseError::UnexpectedEnd { offset: 0x108, expected: 2, actual: 1 });
+    }
+}
@@ -0,0 +1,239 @@
+use crate::elf_file::ElfFile;
+use crate::endianness::{self, Endianness};
+use crate::mapped::MappedElf;
+use crate::parse_error::ParseError;
+use std::path::{Path, PathBuf};
+use std::str;
+
+// Content of .gnu_debuglink: name of the separate debug file and the CRC32
+// of its whole content
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct DebugLink<'a> {
+    pub filename: &'a str,
+    pub crc: u32
+}
+
+// Content of .gnu_debugaltlink: name of the supplementary (dwz) debug file
+// and its build ID
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct DebugAltLink<'a> {
+    pub filename: &'a str,
+    pub build_id: &'a [u8]
+}
+
+// NUL terminated string at the start of a section found at `offset` in
+// the file, and the bytes after it
+fn split_string(data: &[u8], offset: u64) -> Result<(&str, &[u8]), ParseError> {
+    let end = data.iter().position(|&b| b == 0).ok_or(ParseError::UnterminatedString { offset })?;
+    let s = str::from_utf8(&data[..end]).map_err(|_| ParseError::InvalidString { offset })?;
+    Ok((s, &data[end + 1..]))
+}
+
+impl<'a> DebugLink<'a> {
+    // The filename is NUL terminated and padded to 4 bytes, followed by the
+    // CRC. The section is at `index`, found at `offset` in the file
+    pub fn parse(data: &'a [u8], index: usize, offset: u64, endian: Endianness) -> Result<Self, ParseError> {
+        let (filename, _) = split_string(data, offset)?;
+        let crc_offset = (filename.len() + 1).div_ceil(4) * 4;
+        let crc = data.get(crc_offset..crc_offset + 4).ok_or(ParseError::TooSmallDebugLink { index, offset, expected: crc_offset + 4, actual: data.len() })?;
+        Ok(Self { filename, crc: endianness::read32(&[crc[0], crc[1], crc[2], crc[3]], endian) })
+    }
+}
+
+impl<'a> DebugAltLink<'a> {
+    pub fn parse(data: &'a [u8], offset: u64) -> Result<Self, ParseError> {
+        let (filename, build_id) = split_string(data, offset)?;
+        Ok(Self { filename, build_id })
+    }
+}
+
+// CRC-32 (IEEE 802.3, reflected) as used by .gnu_debuglink
+pub fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut c = i as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 { 0xEDB88320 ^ (c >> 1) } else { c >> 1 };
+                k += 1;
+            }
+            table[i] = c;
+            i += 1;
+        }
+        table
+    };
+
+    !data.iter().fold(!0u32, |crc, &b| TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Finds the separate debug file of a binary the same way gdb does. Every
+// path is looked up under `root` so a whole file system tree can be used
+pub struct DebugFileResolver {
+    root: PathBuf,
+    debug_dirs: Vec<PathBuf>
+}
+
+impl Default for DebugFileResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugFileResolver {
+    pub fn new() -> Self {
+        Self::with_root("/")
+    }
+
+    pub fn with_root<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            debug_dirs: vec![PathBuf::from("/usr/lib/debug")]
+        }
+    }
+
+    // Replaces the global debug directories, /usr/lib/debug by default
+    pub fn debug_dirs<P: AsRef<Path>>(mut self, dirs: &[P]) -> Self {
+        self.debug_dirs = dirs.iter().map(|d| d.as_ref().to_path_buf()).collect();
+        self
+    }
+
+    fn rooted(&self, path: &Path) -> PathBuf {
+        self.root.join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    // Candidates from the build ID: <debug dir>/.build-id/xx/yyyy.debug
+    pub fn build_id_paths(&self, build_id: &[u8]) -> Vec<PathBuf> {
+        if build_id.len() < 2 { return Vec::new() }
+        let file = format!("{}.debug", hex(&build_id[1..]));
+        self.debug_dirs.iter()
+            .map(|dir| self.rooted(&dir.join(".build-id").join(hex(&build_id[..1])).join(&file)))
+            .collect()
+    }
+
+    // Candidates from the debug link: next to the binary, in its .debug
+    // directory and in the global directories mirroring its path
+    pub fn debug_link_paths(&self, binary: &Path, filename: &str) -> Vec<PathBuf> {
+        let dir = binary.parent().unwrap_or(Path::new("/"));
+        let mut paths = vec![
+            self.rooted(&dir.join(filename)),
+            self.rooted(&dir.join(".debug").join(filename))
+        ];
+        for debug_dir in &self.debug_dirs {
+            paths.push(self.rooted(&debug_dir.join(dir.strip_prefix("/").unwrap_or(dir)).join(filename)));
+        }
+        paths
+    }
+
+    // Debug file of `elf`, found at `binary` (a path under the root). Files
+    // found through the build ID must carry the same build ID, files found
+    // through the debug link must match its CRC
+    pub fn resolve<'a, E>(&self, elf: &E, binary: &Path) -> Result<Option<MappedElf>, ParseError>
+    where
+        E: ElfFile<'a> + ?Sized
+    {
+        if let Some(build_id) = elf.build_id()? {
+            for path in self.build_id_paths(build_id) {
+                // Candidates that can't be read are skipped like missing ones
+                if let Ok(debug) = MappedElf::open(&path) {
+                    if debug.elf().build_id() == Ok(Some(build_id)) {
+                        return Ok(Some(debug));
+                    }
+                }
+            }
+        }
+
+        if let Some(link) = elf.debug_link()? {
+            for path in self.debug_link_paths(binary, link.filename) {
+                // The binary itself may carry the name of its debug file
+                if path == self.rooted(binary) { continue }
+                if let Ok(debug) = MappedElf::open(&path) {
+                    if crc32(debug.mmap()) == link.crc {
+                        return Ok(Some(debug));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn crc() {
+        use super::*;
+
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    }
+
+    #[test]
+    fn truncated_link() {
+        use super::*;
+
+        let link = DebugLink::parse(b"prog.debug\0\0\x01\x02\x03\x04", 30, 0x3000, Endianness::LittleEndian);
+        assert_eq!(link, Ok(DebugLink { filename: "prog.debug", crc: 0x04030201 }));
+        assert_eq!(DebugLink::parse(b"prog.debug\0\0\x01\x02", 30, 0x3000, Endianness::LittleEndian),
+                   Err(ParseError::TooSmallDebugLink { index: 30, offset: 0x3000, expected: 16, actual: 14 }));
+    }
+
+    #[test]
+    fn resolve() {
+        use super::*;
+        use crate::elf::{BitType, Elf};
+        use crate::test_util::temp_dir;
+        use std::fs;
+
+        // testdata/debug and testdata/debug32 installed as /bin/debug and /bin/debug32
+        // in the root, next to their debug files
+        let root = temp_dir("resolve");
+        fs::create_dir_all(root.join("bin")).unwrap();
+        for (name, bits) in [("debug", BitType::_64), ("debug32", BitType::_32)] {
+            let debug_file = root.join(format!("bin/{}.debug", name));
+            fs::copy(format!("testdata/{}.debug", name), &debug_file).unwrap();
+            let binary = Path::new("/bin").join(name);
+
+            let content = fs::read(format!("testdata/{}", name)).unwrap();
+            let elf = Elf::parse(&content).unwrap();
+            let build_id = elf.build_id().unwrap().unwrap();
+            let link = elf.debug_link().unwrap().unwrap();
+            assert_eq!(link.filename, format!("{}.debug", name));
+            assert_eq!(link.crc, crc32(&fs::read(&debug_file).unwrap()));
+            assert_eq!(elf.debug_alt_link().unwrap(), None);
+
+            // Found next to the binary through the debug link
+            let resolver = DebugFileResolver::with_root(&root);
+            let debug = resolver.resolve(&elf, &binary).unwrap().unwrap();
+            assert_eq!(debug.elf().bits(), bits);
+            assert_eq!(debug.elf().build_id().unwrap(), Some(build_id));
+
+            // Found through the build ID once moved to the global directory
+            let path = &resolver.build_id_paths(build_id)[0];
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::rename(&debug_file, path).unwrap();
+            assert!(resolver.resolve(&elf, &binary).unwrap().is_some());
+
+            // A build ID candidate with broken notes doesn't stop the search
+            fs::rename(path, &debug_file).unwrap();
+            let mut broken = fs::read(&debug_file).unwrap();
+            let note = Elf::parse(&broken).unwrap().section_by_name(".note.gnu.build-id").unwrap().sh_offset as usize;
+            broken[note..note + 4].copy_from_slice(&u32::MAX.to_le_bytes()); // namesz
+            assert!(Elf::parse(&broken).unwrap().build_id().is_err());
+            fs::write(path, broken).unwrap();
+            assert!(resolver.resolve(&elf, &binary).unwrap().is_some());
+
+            // A debug link candidate with a wrong CRC is rejected
+            let mut modified = fs::read(&debug_file).unwrap();
+            modified.push(0);
+            fs::write(&debug_file, modified).unwrap();
+            assert!(resolver.resolve(&elf, &binary).unwrap().is_none());
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+}
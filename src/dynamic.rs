@@ -0,0 +1,494 @@
+use crate::cursor::Cursor;
+use crate::elf::*;
+use crate::elf_file::ElfFile;
+use crate::endianness::Endianness;
+use crate::parse_error::ParseError;
+use crate::string_table::StringTable;
+use std::fmt;
+
+pub const DYNAMIC32_SIZE: usize = 8;
+pub const DYNAMIC64_SIZE: usize = 16;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum DynamicTag {
+    DT_NULL,
+    DT_NEEDED,
+    DT_PLTRELSZ,
+    DT_PLTGOT,
+    DT_HASH,
+    DT_STRTAB,
+    DT_SYMTAB,
+    DT_RELA,
+    DT_RELASZ,
+    DT_RELAENT,
+    DT_STRSZ,
+    DT_SYMENT,
+    DT_INIT,
+    DT_FINI,
+    DT_SONAME,
+    DT_RPATH,
+    DT_SYMBOLIC,
+    DT_REL,
+    DT_RELSZ,
+    DT_RELENT,
+    DT_PLTREL,
+    DT_DEBUG,
+    DT_TEXTREL,
+    DT_JMPREL,
+    DT_BIND_NOW,
+    DT_INIT_ARRAY,
+    DT_FINI_ARRAY,
+    DT_INIT_ARRAYSZ,
+    DT_FINI_ARRAYSZ,
+    DT_RUNPATH,
+    DT_FLAGS,
+    DT_PREINIT_ARRAY,
+    DT_PREINIT_ARRAYSZ,
+    DT_SYMTAB_SHNDX,
+    DT_RELRSZ,
+    DT_RELR,
+    DT_RELRENT,
+    // GNU specific
+    DT_GNU_HASH,
+    DT_VERSYM,
+    DT_RELACOUNT,
+    DT_RELCOUNT,
+    DT_FLAGS_1,
+    DT_VERDEF,
+    DT_VERDEFNUM,
+    DT_VERNEED,
+    DT_VERNEEDNUM,
+    DT_AUXILIARY,
+    DT_FILTER,
+    // Ranges reserved for other uses, the raw value is kept
+    OsSpecific(u64),
+    ProcessorSpecific(u64),
+    Unknown(u64)
+}
+
+impl DynamicTag {
+    pub const DT_LOOS: u64 = 0x6000000D;
+    pub const DT_HIOS: u64 = 0x6FFFF000;
+    pub const DT_LOPROC: u64 = 0x70000000;
+    pub const DT_HIPROC: u64 = 0x7FFFFFFF;
+
+    // Tags whose value is an offset in the dynamic string table
+    pub fn is_string(&self) -> bool {
+        matches!(self, DynamicTag::DT_NEEDED | DynamicTag::DT_SONAME | DynamicTag::DT_RPATH |
+                       DynamicTag::DT_RUNPATH | DynamicTag::DT_AUXILIARY | DynamicTag::DT_FILTER)
+    }
+
+    pub fn value(&self) -> u64 {
+        match *self {
+            DynamicTag::DT_NULL => 0,
+            DynamicTag::DT_NEEDED => 1,
+            DynamicTag::DT_PLTRELSZ => 2,
+            DynamicTag::DT_PLTGOT => 3,
+            DynamicTag::DT_HASH => 4,
+            DynamicTag::DT_STRTAB => 5,
+            DynamicTag::DT_SYMTAB => 6,
+            DynamicTag::DT_RELA => 7,
+            DynamicTag::DT_RELASZ => 8,
+            DynamicTag::DT_RELAENT => 9,
+            DynamicTag::DT_STRSZ => 10,
+            DynamicTag::DT_SYMENT => 11,
+            DynamicTag::DT_INIT => 12,
+            DynamicTag::DT_FINI => 13,
+            DynamicTag::DT_SONAME => 14,
+            DynamicTag::DT_RPATH => 15,
+            DynamicTag::DT_SYMBOLIC => 16,
+            DynamicTag::DT_REL => 17,
+            DynamicTag::DT_RELSZ => 18,
+            DynamicTag::DT_RELENT => 19,
+            DynamicTag::DT_PLTREL => 20,
+            DynamicTag::DT_DEBUG => 21,
+            DynamicTag::DT_TEXTREL => 22,
+            DynamicTag::DT_JMPREL => 23,
+            DynamicTag::DT_BIND_NOW => 24,
+            DynamicTag::DT_INIT_ARRAY => 25,
+            DynamicTag::DT_FINI_ARRAY => 26,
+            DynamicTag::DT_INIT_ARRAYSZ => 27,
+            DynamicTag::DT_FINI_ARRAYSZ => 28,
+            DynamicTag::DT_RUNPATH => 29,
+            DynamicTag::DT_FLAGS => 30,
+            DynamicTag::DT_PREINIT_ARRAY => 32,
+            DynamicTag::DT_PREINIT_ARRAYSZ => 33,
+            DynamicTag::DT_SYMTAB_SHNDX => 34,
+            DynamicTag::DT_RELRSZ => 35,
+            DynamicTag::DT_RELR => 36,
+            DynamicTag::DT_RELRENT => 37,
+            DynamicTag::DT_GNU_HASH => 0x6FFFFEF5,
+            DynamicTag::DT_VERSYM => 0x6FFFFFF0,
+            DynamicTag::DT_RELACOUNT => 0x6FFFFFF9,
+            DynamicTag::DT_RELCOUNT => 0x6FFFFFFA,
+            DynamicTag::DT_FLAGS_1 => 0x6FFFFFFB,
+            DynamicTag::DT_VERDEF => 0x6FFFFFFC,
+            DynamicTag::DT_VERDEFNUM => 0x6FFFFFFD,
+            DynamicTag::DT_VERNEED => 0x6FFFFFFE,
+            DynamicTag::DT_VERNEEDNUM => 0x6FFFFFFF,
+            DynamicTag::DT_AUXILIARY => 0x7FFFFFFD,
+            DynamicTag::DT_FILTER => 0x7FFFFFFF,
+            DynamicTag::OsSpecific(v) => v,
+            DynamicTag::ProcessorSpecific(v) => v,
+            DynamicTag::Unknown(v) => v
+        }
+    }
+}
+
+pub fn parse_dynamic_tag(v: u64) -> DynamicTag {
+    match v {
+        0 => DynamicTag::DT_NULL,
+        1 => DynamicTag::DT_NEEDED,
+        2 => DynamicTag::DT_PLTRELSZ,
+        3 => DynamicTag::DT_PLTGOT,
+        4 => DynamicTag::DT_HASH,
+        5 => DynamicTag::DT_STRTAB,
+        6 => DynamicTag::DT_SYMTAB,
+        7 => DynamicTag::DT_RELA,
+        8 => DynamicTag::DT_RELASZ,
+        9 => DynamicTag::DT_RELAENT,
+        10 => DynamicTag::DT_STRSZ,
+        11 => DynamicTag::DT_SYMENT,
+        12 => DynamicTag::DT_INIT,
+        13 => DynamicTag::DT_FINI,
+        14 => DynamicTag::DT_SONAME,
+        15 => DynamicTag::DT_RPATH,
+        16 => DynamicTag::DT_SYMBOLIC,
+        17 => DynamicTag::DT_REL,
+        18 => DynamicTag::DT_RELSZ,
+        19 => DynamicTag::DT_RELENT,
+        20 => DynamicTag::DT_PLTREL,
+        21 => DynamicTag::DT_DEBUG,
+        22 => DynamicTag::DT_TEXTREL,
+        23 => DynamicTag::DT_JMPREL,
+        24 => DynamicTag::DT_BIND_NOW,
+        25 => DynamicTag::DT_INIT_ARRAY,
+        26 => DynamicTag::DT_FINI_ARRAY,
+        27 => DynamicTag::DT_INIT_ARRAYSZ,
+        28 => DynamicTag::DT_FINI_ARRAYSZ,
+        29 => DynamicTag::DT_RUNPATH,
+        30 => DynamicTag::DT_FLAGS,
+        32 => DynamicTag::DT_PREINIT_ARRAY,
+        33 => DynamicTag::DT_PREINIT_ARRAYSZ,
+        34 => DynamicTag::DT_SYMTAB_SHNDX,
+        35 => DynamicTag::DT_RELRSZ,
+        36 => DynamicTag::DT_RELR,
+        37 => DynamicTag::DT_RELRENT,
+        0x6FFFFEF5 => DynamicTag::DT_GNU_HASH,
+        0x6FFFFFF0 => DynamicTag::DT_VERSYM,
+        0x6FFFFFF9 => DynamicTag::DT_RELACOUNT,
+        0x6FFFFFFA => DynamicTag::DT_RELCOUNT,
+        0x6FFFFFFB => DynamicTag::DT_FLAGS_1,
+        0x6FFFFFFC => DynamicTag::DT_VERDEF,
+        0x6FFFFFFD => DynamicTag::DT_VERDEFNUM,
+        0x6FFFFFFE => DynamicTag::DT_VERNEED,
+        0x6FFFFFFF => DynamicTag::DT_VERNEEDNUM,
+        0x7FFFFFFD => DynamicTag::DT_AUXILIARY,
+        0x7FFFFFFF => DynamicTag::DT_FILTER,
+        DynamicTag::DT_LOOS..=DynamicTag::DT_HIOS => DynamicTag::OsSpecific(v),
+        DynamicTag::DT_LOPROC..=DynamicTag::DT_HIPROC => DynamicTag::ProcessorSpecific(v),
+        _ => DynamicTag::Unknown(v)
+    }
+}
+
+// Value of DT_FLAGS, bits without a name are kept as is
+#[derive(Eq, PartialEq, Clone, Copy, Default, Hash)]
+pub struct DynamicFlags(u64);
+
+impl DynamicFlags {
+    pub const ORIGIN: DynamicFlags = DynamicFlags(0x1);
+    pub const SYMBOLIC: DynamicFlags = DynamicFlags(0x2);
+    pub const TEXTREL: DynamicFlags = DynamicFlags(0x4);
+    pub const BIND_NOW: DynamicFlags = DynamicFlags(0x8);
+    pub const STATIC_TLS: DynamicFlags = DynamicFlags(0x10);
+
+    const NAMES: [(DynamicFlags, &'static str); 5] = [
+        (Self::ORIGIN, "ORIGIN"),
+        (Self::SYMBOLIC, "SYMBOLIC"),
+        (Self::TEXTREL, "TEXTREL"),
+        (Self::BIND_NOW, "BIND_NOW"),
+        (Self::STATIC_TLS, "STATIC_TLS")
+    ];
+
+    pub const fn from_bits(bits: u64) -> Self {
+        DynamicFlags(bits)
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn contains(&self, other: DynamicFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        Self::NAMES.iter().filter(|(flag, _)| self.contains(*flag)).map(|(_, name)| *name)
+    }
+}
+
+impl fmt::Debug for DynamicFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_flags(f, self.0, self.names(), Self::NAMES.iter().fold(0, |acc, (flag, _)| acc | flag.0))
+    }
+}
+
+// Value of DT_FLAGS_1, bits without a name are kept as is
+#[derive(Eq, PartialEq, Clone, Copy, Default, Hash)]
+pub struct DynamicFlags1(u64);
+
+impl DynamicFlags1 {
+    pub const NOW: DynamicFlags1 = DynamicFlags1(0x1);
+    pub const GLOBAL: DynamicFlags1 = DynamicFlags1(0x2);
+    pub const GROUP: DynamicFlags1 = DynamicFlags1(0x4);
+    pub const NODELETE: DynamicFlags1 = DynamicFlags1(0x8);
+    pub const LOADFLTR: DynamicFlags1 = DynamicFlags1(0x10);
+    pub const INITFIRST: DynamicFlags1 = DynamicFlags1(0x20);
+    pub const NOOPEN: DynamicFlags1 = DynamicFlags1(0x40);
+    pub const ORIGIN: DynamicFlags1 = DynamicFlags1(0x80);
+    pub const DIRECT: DynamicFlags1 = DynamicFlags1(0x100);
+    pub const INTERPOSE: DynamicFlags1 = DynamicFlags1(0x400);
+    pub const NODEFLIB: DynamicFlags1 = DynamicFlags1(0x800);
+    pub const NODUMP: DynamicFlags1 = DynamicFlags1(0x1000);
+    pub const CONFALT: DynamicFlags1 = DynamicFlags1(0x2000);
+    pub const ENDFILTEE: DynamicFlags1 = DynamicFlags1(0x4000);
+    pub const DISPRELDNE: DynamicFlags1 = DynamicFlags1(0x8000);
+    pub const DISPRELPND: DynamicFlags1 = DynamicFlags1(0x10000);
+    pub const NODIRECT: DynamicFlags1 = DynamicFlags1(0x20000);
+    pub const PIE: DynamicFlags1 = DynamicFlags1(0x8000000);
+
+    const NAMES: [(DynamicFlags1, &'static str); 18] = [
+        (Self::NOW, "NOW"),
+        (Self::GLOBAL, "GLOBAL"),
+        (Self::GROUP, "GROUP"),
+        (Self::NODELETE, "NODELETE"),
+        (Self::LOADFLTR, "LOADFLTR"),
+        (Self::INITFIRST, "INITFIRST"),
+        (Self::NOOPEN, "NOOPEN"),
+        (Self::ORIGIN, "ORIGIN"),
+        (Self::DIRECT, "DIRECT"),
+        (Self::INTERPOSE, "INTERPOSE"),
+        (Self::NODEFLIB, "NODEFLIB"),
+        (Self::NODUMP, "NODUMP"),
+        (Self::CONFALT, "CONFALT"),
+        (Self::ENDFILTEE, "ENDFILTEE"),
+        (Self::DISPRELDNE, "DISPRELDNE"),
+        (Self::DISPRELPND, "DISPRELPND"),
+        (Self::NODIRECT, "NODIRECT"),
+        (Self::PIE, "PIE")
+    ];
+
+    pub const fn from_bits(bits: u64) -> Self {
+        DynamicFlags1(bits)
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn contains(&self, other: DynamicFlags1) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        Self::NAMES.iter().filter(|(flag, _)| self.contains(*flag)).map(|(_, name)| *name)
+    }
+}
+
+impl fmt::Debug for DynamicFlags1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_flags(f, self.0, self.names(), Self::NAMES.iter().fold(0, |acc, (flag, _)| acc | flag.0))
+    }
+}
+
+// Prints the set flag names and the unknown bits in raw form
+fn write_flags<'n>(f: &mut fmt::Formatter<'_>, bits: u64, names: impl Iterator<Item = &'n str>, known: u64) -> fmt::Result {
+    let mut first = true;
+    for name in names {
+        if !first { f.write_str(" | ")? }
+        f.write_str(name)?;
+        first = false;
+    }
+    let rest = bits & !known;
+    if rest != 0 || first {
+        if !first { f.write_str(" | ")? }
+        write!(f, "{:#x}", rest)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DynamicEntry<'a> {
+    pub tag: DynamicTag,
+    pub value: u64,
+    // Resolved value of string tags like DT_NEEDED. A bad string only
+    // fails its own entry
+    pub string: Option<Result<&'a str, ParseError>>
+}
+
+// Content of the dynamic section, up to DT_NULL
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Dynamic<'a> {
+    entries: Vec<DynamicEntry<'a>>,
+    strtab: Option<StringTable<'a>>
+}
+
+impl<'a> Dynamic<'a> {
+    // Reads the PT_DYNAMIC segment, or the SHT_DYNAMIC section when there
+    // is no program header, so it also works without section headers
+    pub fn parse<E: ElfFile<'a> + ?Sized>(elf: &E) -> Result<Option<Self>, ParseError> {
+        let data = match elf.program_headers().find(|ph| ph.r#type == ProgramHeaderType::PT_DYNAMIC) {
+            Some(segment) => elf.segment_data(&segment)?,
+            None => match elf.section_headers().find(|sh| sh.sh_type == SectionType::SHT_DYNAMIC) {
+                Some(section) => elf.section_data(&section)?,
+                None => return Ok(None)
+            }
+        };
+        let raw = parse_dynamic_entries(data, elf.bits(), elf.endianness());
+
+        // String table is given as an address, find where it lives in the file
+        let strtab_addr = raw.iter().find(|(tag, _)| *tag == DynamicTag::DT_STRTAB).map(|(_, v)| *v);
+        let strsz = raw.iter().find(|(tag, _)| *tag == DynamicTag::DT_STRSZ).map(|(_, v)| *v);
+        let strtab = strtab_addr.map(|addr| -> Result<StringTable<'a>, ParseError> {
+            let start = elf.vaddr_to_offset(addr)?;
+            let start = usize::try_from(start).map_err(|_| ParseError::UnmappedAddress(addr))?;
+            let data = elf.data().get(start..).ok_or(ParseError::UnmappedAddress(addr))?;
+            let size = strsz.and_then(|s| usize::try_from(s).ok()).unwrap_or(data.len()).min(data.len());
+            Ok(StringTable::new(&data[..size], start as u64))
+        });
+
+        let entries = raw.into_iter().map(|(tag, value)| {
+            let string = match &strtab {
+                Some(strtab) if tag.is_string() => Some(strtab.clone().and_then(|strtab| strtab.get(value as usize))),
+                _ => None
+            };
+            DynamicEntry { tag, value, string }
+        }).collect();
+
+        Ok(Some(Self { entries, strtab: strtab.and_then(Result::ok) }))
+    }
+
+    pub fn entries(&self) -> &[DynamicEntry<'a>] {
+        &self.entries
+    }
+
+    pub fn strtab(&self) -> Option<StringTable<'a>> {
+        self.strtab
+    }
+
+    // Value of the first entry with the given tag
+    pub fn get(&self, tag: DynamicTag) -> Option<u64> {
+        self.entries.iter().find(|e| e.tag == tag).map(|e| e.value)
+    }
+
+    // Broken strings are left out, the entry holds the error
+    fn string(&self, tag: DynamicTag) -> Option<&'a str> {
+        self.entries.iter().find(|e| e.tag == tag).and_then(|e| e.string.clone()?.ok())
+    }
+
+    // Libraries this file depends on, in order
+    pub fn needed(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.entries.iter().filter(|e| e.tag == DynamicTag::DT_NEEDED).filter_map(|e| e.string.clone()?.ok())
+    }
+
+    pub fn soname(&self) -> Option<&'a str> {
+        self.string(DynamicTag::DT_SONAME)
+    }
+
+    pub fn rpath(&self) -> Option<&'a str> {
+        self.string(DynamicTag::DT_RPATH)
+    }
+
+    pub fn runpath(&self) -> Option<&'a str> {
+        self.string(DynamicTag::DT_RUNPATH)
+    }
+
+    pub fn flags(&self) -> Option<DynamicFlags> {
+        self.get(DynamicTag::DT_FLAGS).map(DynamicFlags::from_bits)
+    }
+
+    pub fn flags_1(&self) -> Option<DynamicFlags1> {
+        self.get(DynamicTag::DT_FLAGS_1).map(DynamicFlags1::from_bits)
+    }
+}
+
+// Reads (tag, value) pairs until DT_NULL or the end of the data
+fn parse_dynamic_entries(f: &[u8], bits: BitType, endian: Endianness) -> Vec<(DynamicTag, u64)> {
+    let mut c = Cursor::new(f, 0, endian);
+    let mut entries = Vec::new();
+    // A cut off last entry is dropped
+    while let (Ok(tag), Ok(value)) = (c.read_word(bits), c.read_word(bits)) {
+        let tag = parse_dynamic_tag(tag);
+        if tag == DynamicTag::DT_NULL { break }
+        entries.push((tag, value));
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn tags() {
+        use super::*;
+
+        assert_eq!(parse_dynamic_tag(0x6FFFFEF5), DynamicTag::DT_GNU_HASH);
+        assert_eq!(parse_dynamic_tag(0x6000000D), DynamicTag::OsSpecific(0x6000000D));
+        assert_eq!(parse_dynamic_tag(0x6FFFF000), DynamicTag::OsSpecific(0x6FFFF000));
+        // Past DT_HIOS only the GNU tags have a meaning
+        assert_eq!(parse_dynamic_tag(0x6FFFF001), DynamicTag::Unknown(0x6FFFF001));
+        assert_eq!(parse_dynamic_tag(0x6FFFFFF1), DynamicTag::Unknown(0x6FFFFFF1));
+        assert_eq!(parse_dynamic_tag(0x70000001), DynamicTag::ProcessorSpecific(0x70000001));
+    }
+
+    #[test]
+    fn dynamic64() {
+        use super::*;
+        use std::fs;
+
+        let mut content = fs::read("testdata/libdynamic64.so.1").unwrap();
+
+        {
+            let elf = Elf64::parse(&content).unwrap();
+            let dynamic = elf.dynamic().unwrap().unwrap();
+            assert_eq!(dynamic.soname(), Some("libdynamic64.so.1"));
+            assert_eq!(dynamic.runpath(), Some("/opt/dynamic64"));
+            assert!(dynamic.needed().any(|lib| lib.starts_with("libc.so")));
+            assert!(dynamic.flags().unwrap().contains(DynamicFlags::BIND_NOW));
+            assert!(dynamic.flags_1().unwrap().contains(DynamicFlags1::NOW));
+            assert!(dynamic.get(DynamicTag::DT_GNU_HASH).is_some());
+        }
+
+        // Works the same once the section headers are gone
+        content[40..48].copy_from_slice(&0u64.to_le_bytes()); // e_shoff
+        content[60..64].copy_from_slice(&[0; 4]); // e_shnum, e_shstrndx
+        let stripped = Elf64::parse(&content).unwrap();
+        assert!(stripped.shtable().is_empty());
+        assert_eq!(stripped.dynamic().unwrap().unwrap().soname(), Some("libdynamic64.so.1"));
+
+        // A string past the end of the table only breaks its own entry
+        let segment = stripped.phtable().iter().find(|ph| ph.r#type == ProgramHeaderType::PT_DYNAMIC).unwrap().offset as usize;
+        let soname = (segment..).step_by(DYNAMIC64_SIZE).find(|&i| content[i] == 14).unwrap(); // DT_SONAME
+        content[soname + 8..soname + 16].copy_from_slice(&0xFFFFFFu64.to_le_bytes());
+        let broken = Elf64::parse(&content).unwrap();
+        let dynamic = broken.dynamic().unwrap().unwrap();
+        assert_eq!(dynamic.soname(), None);
+        assert!(matches!(dynamic.entries().iter().find(|e| e.tag == DynamicTag::DT_SONAME).unwrap().string,
+                         Some(Err(ParseError::StringOutOfBounds { offset: 0xFFFFFF, .. }))));
+        assert_eq!(dynamic.runpath(), Some("/opt/dynamic64"));
+        assert!(dynamic.needed().any(|lib| lib.starts_with("libc.so")));
+    }
+
+    #[test]
+    fn dynamic32() {
+        use super::*;
+        use std::fs;
+
+        let content = fs::read("testdata/libdynamic32.so.1").unwrap();
+        let elf = Elf::parse(&content).unwrap();
+        let dynamic = elf.dynamic().unwrap().unwrap();
+        assert_eq!(dynamic.soname(), Some("libdynamic32.so"));
+        assert_eq!(dynamic.needed().count(), 0);
+        assert!(dynamic.get(DynamicTag::DT_SYMTAB).is_some());
+    }
+}
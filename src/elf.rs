@@ -1,8 +1,23 @@
+use crate::cursor::Cursor;
 use crate::endianness::Endianness;
+use crate::parse_error::ParseError;
+use std::fmt;
+use std::ops::{BitAnd, BitOr};
 
 pub const ELF_MAGIC_NUM: &[u8] = &[0x7F, 0x45, 0x4C, 0x46];
 pub const ELF32_HEADER_SIZE: usize = 52;
 pub const ELF64_HEADER_SIZE: usize = 64;
+pub const PROGRAM_HEADER32_SIZE: usize = 32;
+pub const PROGRAM_HEADER64_SIZE: usize = 56;
+pub const SECTION_HEADER32_SIZE: usize = 40;
+pub const SECTION_HEADER64_SIZE: usize = 64;
+
+// Special section indexes
+pub const SHN_UNDEF: u16 = 0;
+pub const SHN_LORESERVE: u16 = 0xFF00;
+pub const SHN_ABS: u16 = 0xFFF1;
+pub const SHN_COMMON: u16 = 0xFFF2;
+pub const SHN_XINDEX: u16 = 0xFFFF;
 pub const EI_NIDENT: usize = 16;
 
 // Custom types
@@ -36,44 +51,543 @@ pub enum ABI {
     OpenBSD,
     ARM_EABI,
     ARM,
-    Standalone
+    Standalone,
+    Unknown(u8)
 }
 
-#[derive(Debug, Eq, PartialEq)]
+impl ABI {
+    pub fn value(&self) -> u8 {
+        match *self {
+            ABI::UnixSystemV => 0,
+            ABI::HP_UX => 1,
+            ABI::NetBSD => 2,
+            ABI::Linux => 3,
+            ABI::SunSolaris => 6,
+            ABI::IBM_AIX => 7,
+            ABI::SGI_Irix => 8,
+            ABI::FreeBSD => 9,
+            ABI::CompaqTRU64 => 10,
+            ABI::NovellModesto => 11,
+            ABI::OpenBSD => 12,
+            ABI::ARM_EABI => 64,
+            ABI::ARM => 97,
+            ABI::Standalone => 255,
+            ABI::Unknown(v) => v
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 #[allow(non_camel_case_types)]
 pub enum FileType {
     ET_NONE,
     ET_REL,
     ET_EXEC,
     ET_DYN,
-    ET_CORE
+    ET_CORE,
+    // Ranges reserved for other uses, the raw value is kept
+    OsSpecific(u16),
+    ProcessorSpecific(u16),
+    Unknown(u16)
 }
 
-#[derive(Debug, Eq, PartialEq)]
+impl FileType {
+    pub const ET_LOOS: u16 = 0xFE00;
+    pub const ET_HIOS: u16 = 0xFEFF;
+    pub const ET_LOPROC: u16 = 0xFF00;
+    pub const ET_HIPROC: u16 = 0xFFFF;
+
+    pub fn value(&self) -> u16 {
+        match *self {
+            FileType::ET_NONE => 0,
+            FileType::ET_REL => 1,
+            FileType::ET_EXEC => 2,
+            FileType::ET_DYN => 3,
+            FileType::ET_CORE => 4,
+            FileType::OsSpecific(v) => v,
+            FileType::ProcessorSpecific(v) => v,
+            FileType::Unknown(v) => v
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 #[allow(non_camel_case_types)]
 pub enum MachineType {
     None,
+    AT_T_WE32100,
     SPARC,
     Intel_80386,
     Motorola_68000,
+    Motorola_88000,
+    Intel_MCU,
     Intel_i860,
     MIPS_I,
+    IBM_S370,
+    MIPS_RS3_LE,
+    PA_RISC,
+    Fujitsu_VPP500,
+    SPARC32Plus,
     Intel_i960,
     PowerPC,
+    PowerPC64,
+    IBM_S390,
+    IBM_SPU,
+    NEC_V800,
+    Fujitsu_FR20,
+    TRW_RH32,
+    Motorola_RCE,
     ARM,
+    Alpha,
+    SuperH,
+    SPARCV9,
+    Siemens_TriCore,
+    ARC,
+    H8_300,
+    H8_300H,
+    H8S,
+    H8_500,
     Intel_IA64,
+    MIPS_X,
+    Motorola_ColdFire,
+    Motorola_68HC12,
+    Fujitsu_MMA,
+    Siemens_PCP,
+    Sony_NCPU,
+    Denso_NDR1,
+    Motorola_StarCore,
+    Toyota_ME16,
+    ST100,
+    TinyJ,
     x64,
-    RISC_V
+    Sony_PDSP,
+    PDP10,
+    PDP11,
+    Siemens_FX66,
+    ST9Plus,
+    ST7,
+    Motorola_68HC16,
+    Motorola_68HC11,
+    Motorola_68HC08,
+    Motorola_68HC05,
+    SGI_SVx,
+    ST19,
+    VAX,
+    Axis_CRIS,
+    Infineon_Javelin,
+    Element14_FirePath,
+    LSI_ZSP,
+    MMIX,
+    HUANY,
+    SiTera_Prism,
+    AVR,
+    Fujitsu_FR30,
+    Mitsubishi_D10V,
+    Mitsubishi_D30V,
+    NEC_V850,
+    Mitsubishi_M32R,
+    Matsushita_MN10300,
+    Matsushita_MN10200,
+    PicoJava,
+    OpenRISC,
+    ARC_Compact,
+    Xtensa,
+    VideoCore,
+    TMM_GPP,
+    NS32K,
+    TPC,
+    SNP1K,
+    ST200,
+    IP2K,
+    MAX,
+    CompactRISC,
+    Fujitsu_F2MC16,
+    MSP430,
+    Blackfin,
+    Seiko_S1C33,
+    Sharp_SEP,
+    ArcaNet,
+    UniCore,
+    eXcess,
+    Icera_DXP,
+    Altera_Nios2,
+    CRX,
+    Motorola_XGATE,
+    Infineon_C166,
+    Renesas_M16C,
+    DSPIC30F,
+    Freescale_CE,
+    Renesas_M32C,
+    Altium_TSK3000,
+    Freescale_RS08,
+    ADI_SHARC,
+    ECOG2,
+    Sunplus_Score7,
+    NJR_DSP24,
+    VideoCore3,
+    LatticeMico32,
+    Seiko_C17,
+    TI_C6000,
+    TI_C2000,
+    TI_C5500,
+    TI_ARP32,
+    TI_PRU,
+    ST_MMDSP_Plus,
+    Cypress_M8C,
+    Renesas_R32C,
+    NXP_TriMedia,
+    Hexagon,
+    Intel_8051,
+    ST_STxP7x,
+    Andes_NDS32,
+    ECOG1X,
+    MAXQ30,
+    NJR_XIMO16,
+    M2000_MANIK,
+    Cray_NV2,
+    Renesas_RX,
+    Imagination_Meta,
+    MCST_Elbrus,
+    ECOG16,
+    CR16,
+    Freescale_ETPU,
+    Infineon_SLE9X,
+    Intel_L10M,
+    Intel_K10M,
+    AArch64,
+    Atmel_AVR32,
+    ST_STM8,
+    Tilera_TILE64,
+    Tilera_TILEPro,
+    MicroBlaze,
+    CUDA,
+    Tilera_TILEGx,
+    CloudShield,
+    CoreA_1st,
+    CoreA_2nd,
+    ARC_Compact2,
+    Open8,
+    Renesas_RL78,
+    VideoCore5,
+    Renesas_78K0R,
+    Freescale_56800EX,
+    Beyond_BA1,
+    Beyond_BA2,
+    XMOS_xCORE,
+    Microchip_PIC,
+    Intel_GT,
+    KM211_KM32,
+    KM211_KMX32,
+    KM211_KMX16,
+    KM211_KMX8,
+    KM211_KVARC,
+    Paneve_CDP,
+    Cognitive_COGE,
+    Bluechip_CoolEngine,
+    Nanoradio_NORC,
+    CSR_Kalimba,
+    Z80,
+    Visium,
+    FTDI_FT32,
+    Moxie,
+    AMDGPU,
+    RISC_V,
+    Lanai,
+    CEVA,
+    CEVA_X2,
+    BPF,
+    Graphcore_IPU,
+    Imagination_IMG1,
+    Netronome_NFP,
+    NEC_VE,
+    CSKY,
+    ARC_Compact3_64,
+    MOS_6502,
+    ARC_Compact3,
+    Kalray_KVX,
+    WDC_65816,
+    LoongArch,
+    ChipON_KF32,
+    LAPIS_U16_U8Core,
+    Tachyum,
+    NXP_56800EF,
+    Unknown(u16)
 }
 
-#[derive(Debug, Eq, PartialEq)]
+// Registered e_machine values with the names readelf prints for them
+const MACHINE_TYPES: [(MachineType, u16, &str); 197] = [
+    (MachineType::None, 0, "None"),
+    (MachineType::AT_T_WE32100, 1, "WE32100"),
+    (MachineType::SPARC, 2, "Sparc"),
+    (MachineType::Intel_80386, 3, "Intel 80386"),
+    (MachineType::Motorola_68000, 4, "MC68000"),
+    (MachineType::Motorola_88000, 5, "MC88000"),
+    (MachineType::Intel_MCU, 6, "Intel MCU"),
+    (MachineType::Intel_i860, 7, "Intel 80860"),
+    (MachineType::MIPS_I, 8, "MIPS R3000"),
+    (MachineType::IBM_S370, 9, "IBM System/370"),
+    (MachineType::MIPS_RS3_LE, 10, "MIPS R4000 big-endian"),
+    (MachineType::PA_RISC, 15, "HPPA"),
+    (MachineType::Fujitsu_VPP500, 17, "Fujitsu VPP500"),
+    (MachineType::SPARC32Plus, 18, "Sparc v8+"),
+    (MachineType::Intel_i960, 19, "Intel 80960"),
+    (MachineType::PowerPC, 20, "PowerPC"),
+    (MachineType::PowerPC64, 21, "PowerPC64"),
+    (MachineType::IBM_S390, 22, "IBM S/390"),
+    (MachineType::IBM_SPU, 23, "SPU"),
+    (MachineType::NEC_V800, 36, "Renesas V850 (using RH850 ABI)"),
+    (MachineType::Fujitsu_FR20, 37, "Fujitsu FR20"),
+    (MachineType::TRW_RH32, 38, "TRW RH32"),
+    (MachineType::Motorola_RCE, 39, "MCORE"),
+    (MachineType::ARM, 40, "ARM"),
+    (MachineType::Alpha, 41, "Digital Alpha (old)"),
+    (MachineType::SuperH, 42, "Renesas / SuperH SH"),
+    (MachineType::SPARCV9, 43, "Sparc v9"),
+    (MachineType::Siemens_TriCore, 44, "Siemens Tricore"),
+    (MachineType::ARC, 45, "ARC"),
+    (MachineType::H8_300, 46, "Renesas H8/300"),
+    (MachineType::H8_300H, 47, "Renesas H8/300H"),
+    (MachineType::H8S, 48, "Renesas H8S"),
+    (MachineType::H8_500, 49, "Renesas H8/500"),
+    (MachineType::Intel_IA64, 50, "Intel IA-64"),
+    (MachineType::MIPS_X, 51, "Stanford MIPS-X"),
+    (MachineType::Motorola_ColdFire, 52, "Motorola Coldfire"),
+    (MachineType::Motorola_68HC12, 53, "Motorola MC68HC12 Microcontroller"),
+    (MachineType::Fujitsu_MMA, 54, "Fujitsu Multimedia Accelerator"),
+    (MachineType::Siemens_PCP, 55, "Siemens PCP"),
+    (MachineType::Sony_NCPU, 56, "Sony nCPU embedded RISC processor"),
+    (MachineType::Denso_NDR1, 57, "Denso NDR1 microprocesspr"),
+    (MachineType::Motorola_StarCore, 58, "Motorola Star*Core processor"),
+    (MachineType::Toyota_ME16, 59, "Toyota ME16 processor"),
+    (MachineType::ST100, 60, "STMicroelectronics ST100 processor"),
+    (MachineType::TinyJ, 61, "Advanced Logic Corp. TinyJ embedded processor"),
+    (MachineType::x64, 62, "Advanced Micro Devices X86-64"),
+    (MachineType::Sony_PDSP, 63, "Sony DSP processor"),
+    (MachineType::PDP10, 64, "Digital Equipment Corp. PDP-10"),
+    (MachineType::PDP11, 65, "Digital Equipment Corp. PDP-11"),
+    (MachineType::Siemens_FX66, 66, "Siemens FX66 microcontroller"),
+    (MachineType::ST9Plus, 67, "STMicroelectronics ST9+ 8/16 bit microcontroller"),
+    (MachineType::ST7, 68, "STMicroelectronics ST7 8-bit microcontroller"),
+    (MachineType::Motorola_68HC16, 69, "Motorola MC68HC16 Microcontroller"),
+    (MachineType::Motorola_68HC11, 70, "Motorola MC68HC11 Microcontroller"),
+    (MachineType::Motorola_68HC08, 71, "Motorola MC68HC08 Microcontroller"),
+    (MachineType::Motorola_68HC05, 72, "Motorola MC68HC05 Microcontroller"),
+    (MachineType::SGI_SVx, 73, "Silicon Graphics SVx"),
+    (MachineType::ST19, 74, "STMicroelectronics ST19 8-bit microcontroller"),
+    (MachineType::VAX, 75, "Digital VAX"),
+    (MachineType::Axis_CRIS, 76, "Axis Communications 32-bit embedded processor"),
+    (MachineType::Infineon_Javelin, 77, "Infineon Technologies 32-bit embedded cpu"),
+    (MachineType::Element14_FirePath, 78, "Element 14 64-bit DSP processor"),
+    (MachineType::LSI_ZSP, 79, "LSI Logic's 16-bit DSP processor"),
+    (MachineType::MMIX, 80, "Donald Knuth's educational 64-bit processor"),
+    (MachineType::HUANY, 81, "Harvard Universitys's machine-independent object format"),
+    (MachineType::SiTera_Prism, 82, "Vitesse Prism"),
+    (MachineType::AVR, 83, "Atmel AVR 8-bit microcontroller"),
+    (MachineType::Fujitsu_FR30, 84, "Fujitsu FR30"),
+    (MachineType::Mitsubishi_D10V, 85, "d10v"),
+    (MachineType::Mitsubishi_D30V, 86, "d30v"),
+    (MachineType::NEC_V850, 87, "Renesas V850"),
+    (MachineType::Mitsubishi_M32R, 88, "Renesas M32R (formerly Mitsubishi M32r)"),
+    (MachineType::Matsushita_MN10300, 89, "mn10300"),
+    (MachineType::Matsushita_MN10200, 90, "mn10200"),
+    (MachineType::PicoJava, 91, "picoJava"),
+    (MachineType::OpenRISC, 92, "OpenRISC 1000"),
+    (MachineType::ARC_Compact, 93, "ARCompact"),
+    (MachineType::Xtensa, 94, "Tensilica Xtensa Processor"),
+    (MachineType::VideoCore, 95, "Alphamosaic VideoCore processor"),
+    (MachineType::TMM_GPP, 96, "Thompson Multimedia General Purpose Processor"),
+    (MachineType::NS32K, 97, "National Semiconductor 32000 series"),
+    (MachineType::TPC, 98, "Tenor Network TPC processor"),
+    (MachineType::SNP1K, 99, "Trebia SNP 1000 processor"),
+    (MachineType::ST200, 100, "STMicroelectronics ST200 microcontroller"),
+    (MachineType::IP2K, 101, "Ubicom IP2xxx 8-bit microcontrollers"),
+    (MachineType::MAX, 102, "MAX Processor"),
+    (MachineType::CompactRISC, 103, "National Semiconductor CompactRISC"),
+    (MachineType::Fujitsu_F2MC16, 104, "Fujitsu F2MC16"),
+    (MachineType::MSP430, 105, "Texas Instruments msp430 microcontroller"),
+    (MachineType::Blackfin, 106, "Analog Devices Blackfin"),
+    (MachineType::Seiko_S1C33, 107, "S1C33 Family of Seiko Epson processors"),
+    (MachineType::Sharp_SEP, 108, "Sharp embedded microprocessor"),
+    (MachineType::ArcaNet, 109, "Arca RISC microprocessor"),
+    (MachineType::UniCore, 110, "Unicore"),
+    (MachineType::eXcess, 111, "eXcess 16/32/64-bit configurable embedded CPU"),
+    (MachineType::Icera_DXP, 112, "Icera Semiconductor Inc. Deep Execution Processor"),
+    (MachineType::Altera_Nios2, 113, "Altera Nios II"),
+    (MachineType::CRX, 114, "National Semiconductor CRX microprocessor"),
+    (MachineType::Motorola_XGATE, 115, "Motorola XGATE embedded processor"),
+    (MachineType::Infineon_C166, 116, "Infineon Technologies xc16x"),
+    (MachineType::Renesas_M16C, 117, "Renesas M16C series microprocessors"),
+    (MachineType::DSPIC30F, 118, "Microchip Technology dsPIC30F Digital Signal Controller"),
+    (MachineType::Freescale_CE, 119, "Freescale Communication Engine RISC core"),
+    (MachineType::Renesas_M32C, 120, "Renesas M32c"),
+    (MachineType::Altium_TSK3000, 131, "Altium TSK3000 core"),
+    (MachineType::Freescale_RS08, 132, "Freescale RS08 embedded processor"),
+    (MachineType::ADI_SHARC, 133, "Analog Devices SHARC family of 32-bit DSP processors"),
+    (MachineType::ECOG2, 134, "Cyan Technology eCOG2 microprocessor"),
+    (MachineType::Sunplus_Score7, 135, "SUNPLUS S+Core"),
+    (MachineType::NJR_DSP24, 136, "New Japan Radio (NJR) 24-bit DSP Processor"),
+    (MachineType::VideoCore3, 137, "Broadcom VideoCore III processor"),
+    (MachineType::LatticeMico32, 138, "Lattice Mico32"),
+    (MachineType::Seiko_C17, 139, "Seiko Epson C17 family"),
+    (MachineType::TI_C6000, 140, "Texas Instruments TMS320C6000 DSP family"),
+    (MachineType::TI_C2000, 141, "Texas Instruments TMS320C2000 DSP family"),
+    (MachineType::TI_C5500, 142, "Texas Instruments TMS320C55x DSP family"),
+    (MachineType::TI_ARP32, 143, "Texas Instruments Application Specific RISC Processor, 32bit fetch"),
+    (MachineType::TI_PRU, 144, "TI PRU I/O processor"),
+    (MachineType::ST_MMDSP_Plus, 160, "STMicroelectronics 64bit VLIW Data Signal Processor"),
+    (MachineType::Cypress_M8C, 161, "Cypress M8C microprocessor"),
+    (MachineType::Renesas_R32C, 162, "Renesas R32C series microprocessors"),
+    (MachineType::NXP_TriMedia, 163, "NXP Semiconductors TriMedia architecture family"),
+    (MachineType::Hexagon, 164, "QUALCOMM DSP6 Processor"),
+    (MachineType::Intel_8051, 165, "Intel 8051 and variants"),
+    (MachineType::ST_STxP7x, 166, "STMicroelectronics STxP7x family"),
+    (MachineType::Andes_NDS32, 167, "Andes Technology compact code size embedded RISC processor family"),
+    (MachineType::ECOG1X, 168, "Cyan Technology eCOG1X family"),
+    (MachineType::MAXQ30, 169, "Dallas Semiconductor MAXQ30 Core microcontrollers"),
+    (MachineType::NJR_XIMO16, 170, "New Japan Radio (NJR) 16-bit DSP Processor"),
+    (MachineType::M2000_MANIK, 171, "M2000 Reconfigurable RISC Microprocessor"),
+    (MachineType::Cray_NV2, 172, "Cray Inc. NV2 vector architecture"),
+    (MachineType::Renesas_RX, 173, "Renesas RX"),
+    (MachineType::Imagination_Meta, 174, "Imagination Technologies Meta processor architecture"),
+    (MachineType::MCST_Elbrus, 175, "MCST Elbrus general purpose hardware architecture"),
+    (MachineType::ECOG16, 176, "Cyan Technology eCOG16 family"),
+    (MachineType::CR16, 177, "National Semiconductor's CR16"),
+    (MachineType::Freescale_ETPU, 178, "Freescale Extended Time Processing Unit"),
+    (MachineType::Infineon_SLE9X, 179, "Infineon Technologies SLE9X core"),
+    (MachineType::Intel_L10M, 180, "Intel L1OM"),
+    (MachineType::Intel_K10M, 181, "Intel K1OM"),
+    (MachineType::AArch64, 183, "AArch64"),
+    (MachineType::Atmel_AVR32, 185, "Atmel Corporation 32-bit microprocessor"),
+    (MachineType::ST_STM8, 186, "STMicroeletronics STM8 8-bit microcontroller"),
+    (MachineType::Tilera_TILE64, 187, "Tilera TILE64 multicore architecture family"),
+    (MachineType::Tilera_TILEPro, 188, "Tilera TILEPro multicore architecture family"),
+    (MachineType::MicroBlaze, 189, "Xilinx MicroBlaze"),
+    (MachineType::CUDA, 190, "NVIDIA CUDA architecture"),
+    (MachineType::Tilera_TILEGx, 191, "Tilera TILE-Gx multicore architecture family"),
+    (MachineType::CloudShield, 192, "CloudShield architecture family"),
+    (MachineType::CoreA_1st, 193, "KIPO-KAIST Core-A 1st generation processor family"),
+    (MachineType::CoreA_2nd, 194, "KIPO-KAIST Core-A 2nd generation processor family"),
+    (MachineType::ARC_Compact2, 195, "ARCv2"),
+    (MachineType::Open8, 196, "Open8 8-bit RISC soft processor core"),
+    (MachineType::Renesas_RL78, 197, "Renesas RL78"),
+    (MachineType::VideoCore5, 198, "Broadcom VideoCore V processor"),
+    (MachineType::Renesas_78K0R, 199, "Renesas 78K0R"),
+    (MachineType::Freescale_56800EX, 200, "Freescale 56800EX Digital Signal Controller (DSC)"),
+    (MachineType::Beyond_BA1, 201, "Beyond BA1 CPU architecture"),
+    (MachineType::Beyond_BA2, 202, "Beyond BA2 CPU architecture"),
+    (MachineType::XMOS_xCORE, 203, "XMOS xCORE processor family"),
+    (MachineType::Microchip_PIC, 204, "Microchip 8-bit PIC(r) family"),
+    (MachineType::Intel_GT, 205, "Intel Graphics Technology"),
+    (MachineType::KM211_KM32, 210, "KM211 KM32 32-bit processor"),
+    (MachineType::KM211_KMX32, 211, "KM211 KMX32 32-bit processor"),
+    (MachineType::KM211_KMX16, 212, "KM211 KMX16 16-bit processor"),
+    (MachineType::KM211_KMX8, 213, "KM211 KMX8 8-bit processor"),
+    (MachineType::KM211_KVARC, 214, "KM211 KVARC processor"),
+    (MachineType::Paneve_CDP, 215, "Paneve CDP architecture family"),
+    (MachineType::Cognitive_COGE, 216, "Cognitive Smart Memory Processor"),
+    (MachineType::Bluechip_CoolEngine, 217, "Bluechip Systems CoolEngine"),
+    (MachineType::Nanoradio_NORC, 218, "Nanoradio Optimized RISC"),
+    (MachineType::CSR_Kalimba, 219, "CSR Kalimba architecture family"),
+    (MachineType::Z80, 220, "Zilog Z80"),
+    (MachineType::Visium, 221, "CDS VISIUMcore processor"),
+    (MachineType::FTDI_FT32, 222, "FTDI Chip FT32"),
+    (MachineType::Moxie, 223, "Moxie"),
+    (MachineType::AMDGPU, 224, "AMD GPU"),
+    (MachineType::RISC_V, 243, "RISC-V"),
+    (MachineType::Lanai, 244, "Lanai 32-bit processor"),
+    (MachineType::CEVA, 245, "CEVA Processor Architecture Family"),
+    (MachineType::CEVA_X2, 246, "CEVA X2 Processor Family"),
+    (MachineType::BPF, 247, "Linux BPF"),
+    (MachineType::Graphcore_IPU, 248, "Graphcore Intelligent Processing Unit"),
+    (MachineType::Imagination_IMG1, 249, "Imagination Technologies"),
+    (MachineType::Netronome_NFP, 250, "Netronome Flow Processor"),
+    (MachineType::NEC_VE, 251, "NEC Vector Engine"),
+    (MachineType::CSKY, 252, "C-SKY"),
+    (MachineType::ARC_Compact3_64, 253, "Synopsys ARCv2.3 64-bit"),
+    (MachineType::MOS_6502, 254, "MOS Technology MCS 6502 processor"),
+    (MachineType::ARC_Compact3, 255, "Synopsys ARCv2.3 32-bit"),
+    (MachineType::Kalray_KVX, 256, "Kalray VLIW core of the MPPA processor family"),
+    (MachineType::WDC_65816, 257, "WDC 65816/65C816"),
+    (MachineType::LoongArch, 258, "LoongArch"),
+    (MachineType::ChipON_KF32, 259, "ChipON KungFu32"),
+    (MachineType::LAPIS_U16_U8Core, 260, "LAPIS nX-U16/U8"),
+    (MachineType::Tachyum, 261, "Tachyum"),
+    (MachineType::NXP_56800EF, 262, "NXP 56800EF Digital Signal Controller (DSC)"),
+];
+
+impl MachineType {
+    pub fn from_value(v: u16) -> Self {
+        MACHINE_TYPES.iter()
+            .find(|(_, value, _)| *value == v)
+            .map_or(MachineType::Unknown(v), |(machine, _, _)| *machine)
+    }
+
+    pub fn value(&self) -> u16 {
+        match *self {
+            MachineType::Unknown(v) => v,
+            machine => MACHINE_TYPES.iter().find(|(m, _, _)| *m == machine).map_or(0, |(_, value, _)| *value)
+        }
+    }
+
+    // Human readable name, `None` for unregistered values
+    pub fn name(&self) -> Option<&'static str> {
+        MACHINE_TYPES.iter().find(|(m, _, _)| m == self).map(|(_, _, name)| *name)
+    }
+
+    // Size of a pointer in bits, `None` when the class of the file decides
+    // (MIPS, RISC-V, s390...) or the machine is not known well enough
+    pub fn pointer_width(&self) -> Option<u32> {
+        use MachineType::*;
+        match *self {
+            x64 | Intel_IA64 | AArch64 | PowerPC64 | SPARCV9 | Alpha | MMIX | BPF | AMDGPU | CUDA | NEC_VE
+                | Kalray_KVX | Intel_L10M | Intel_K10M | Tilera_TILEGx | ARC_Compact3_64 => Some(64),
+            Intel_80386 | Intel_MCU | ARM | SPARC | SPARC32Plus | PowerPC | Motorola_68000 | Motorola_88000
+                | MIPS_RS3_LE | SuperH | Xtensa | Hexagon | MicroBlaze | OpenRISC | Altera_Nios2 | Blackfin | CSKY
+                | ARC | ARC_Compact | ARC_Compact2 | ARC_Compact3 | Intel_i860 | Intel_i960 | VAX | Axis_CRIS
+                | Mitsubishi_M32R | NEC_V850 | Tilera_TILE64 | Tilera_TILEPro | Atmel_AVR32 | Lanai | Moxie
+                | FTDI_FT32 | Andes_NDS32 | Renesas_RX | Motorola_ColdFire => Some(32),
+            AVR | MSP430 | Z80 | MOS_6502 | Intel_8051 | PDP11 | H8_300 | Motorola_68HC05 | Motorola_68HC08
+                | Motorola_68HC11 | Motorola_68HC12 | Motorola_68HC16 => Some(16),
+            // The glob import shadows `None` with MachineType::None
+            _ => Option::None
+        }
+    }
+
+    // Byte order the machine normally uses, `None` for machines where both
+    // are common (MIPS, PowerPC64, SuperH...) or that are not known well enough
+    pub fn default_endianness(&self) -> Option<Endianness> {
+        use MachineType::*;
+        match *self {
+            x64 | Intel_80386 | Intel_MCU | Intel_IA64 | Alpha | ARM | AArch64 | RISC_V | LoongArch | AVR | MSP430
+                | Hexagon | BPF | AMDGPU | CUDA | Altera_Nios2 | Blackfin | CSKY | ARC | ARC_Compact | ARC_Compact2
+                | ARC_Compact3 | ARC_Compact3_64 | VAX | Z80 | MOS_6502 | PDP11 | MIPS_RS3_LE | Intel_L10M
+                | Intel_K10M | Tilera_TILE64 | Tilera_TILEPro | Tilera_TILEGx | NEC_VE | Kalray_KVX | Axis_CRIS
+                | Andes_NDS32 | Intel_8051 => Some(Endianness::LittleEndian),
+            SPARC | SPARC32Plus | SPARCV9 | Motorola_68000 | Motorola_88000 | Motorola_ColdFire | IBM_S370 | IBM_S390
+                | PA_RISC | PowerPC | OpenRISC | Lanai | Atmel_AVR32 | Mitsubishi_M32R => Some(Endianness::BigEndian),
+            _ => Option::None
+        }
+    }
+}
+
+impl fmt::Display for MachineType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "<unknown>: {:#x}", self.value())
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 #[allow(non_camel_case_types)]
 pub enum HeaderVersion {
     None,
-    Current
+    Current,
+    Unknown(u32)
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 #[allow(non_camel_case_types)]
 pub enum ProgramHeaderType {
     PT_NULL,
@@ -88,11 +602,72 @@ pub enum ProgramHeaderType {
     GNU_PROPERTY,
     GNU_EH_FRAME,
     GNU_STACK,
-    GNU_RELRO
-    
+    GNU_RELRO,
+    GNU_SFRAME,
+    // OpenBSD specific
+    OPENBSD_MUTABLE,
+    OPENBSD_RANDOMIZE,
+    OPENBSD_WXNEEDED,
+    OPENBSD_NOBTCFI,
+    OPENBSD_SYSCALLS,
+    OPENBSD_BOOTDATA,
+    // Ranges reserved for other uses, the raw value is kept
+    OsSpecific(u32),
+    ProcessorSpecific(u32),
+    Unknown(u32)
 }
 
-#[derive(Debug, Eq, PartialEq)]
+impl ProgramHeaderType {
+    pub const PT_LOOS: u32 = 0x60000000;
+    pub const PT_HIOS: u32 = 0x6FFFFFFF;
+    pub const PT_LOPROC: u32 = 0x70000000;
+    pub const PT_HIPROC: u32 = 0x7FFFFFFF;
+    // Memory binding segments of GNU ld
+    pub const PT_GNU_MBIND_LO: u32 = 0x6474E555;
+    pub const PT_GNU_MBIND_HI: u32 = 0x6474F554;
+    // Processor specific values, their meaning depends on the machine
+    pub const PT_MIPS_REGINFO: u32 = 0x70000000;
+    pub const PT_MIPS_RTPROC: u32 = 0x70000001;
+    pub const PT_MIPS_OPTIONS: u32 = 0x70000002;
+    pub const PT_MIPS_ABIFLAGS: u32 = 0x70000003;
+    pub const PT_ARM_EXIDX: u32 = 0x70000001;
+    pub const PT_AARCH64_MEMTAG_MTE: u32 = 0x70000002;
+    pub const PT_RISCV_ATTRIBUTES: u32 = 0x70000003;
+
+    pub fn value(&self) -> u32 {
+        match *self {
+            ProgramHeaderType::PT_NULL => 0,
+            ProgramHeaderType::PT_LOAD => 1,
+            ProgramHeaderType::PT_DYNAMIC => 2,
+            ProgramHeaderType::PT_INTERP => 3,
+            ProgramHeaderType::PT_NOTE => 4,
+            ProgramHeaderType::PT_SHLIB => 5,
+            ProgramHeaderType::PT_PHDR => 6,
+            ProgramHeaderType::PT_TLS => 7,
+            ProgramHeaderType::GNU_EH_FRAME => 0x6474E550,
+            ProgramHeaderType::GNU_STACK => 0x6474E551,
+            ProgramHeaderType::GNU_RELRO => 0x6474E552,
+            ProgramHeaderType::GNU_PROPERTY => 0x6474E553,
+            ProgramHeaderType::GNU_SFRAME => 0x6474E554,
+            ProgramHeaderType::OPENBSD_MUTABLE => 0x65A3DBE5,
+            ProgramHeaderType::OPENBSD_RANDOMIZE => 0x65A3DBE6,
+            ProgramHeaderType::OPENBSD_WXNEEDED => 0x65A3DBE7,
+            ProgramHeaderType::OPENBSD_NOBTCFI => 0x65A3DBE8,
+            ProgramHeaderType::OPENBSD_SYSCALLS => 0x65A3DBE9,
+            ProgramHeaderType::OPENBSD_BOOTDATA => 0x65A41BE6,
+            ProgramHeaderType::OsSpecific(v) => v,
+            ProgramHeaderType::ProcessorSpecific(v) => v,
+            ProgramHeaderType::Unknown(v) => v
+        }
+    }
+
+    // Whether this is one of the PT_GNU_MBIND segments
+    pub fn is_gnu_mbind(&self) -> bool {
+        (Self::PT_GNU_MBIND_LO..=Self::PT_GNU_MBIND_HI).contains(&self.value())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 #[allow(non_camel_case_types)]
 pub enum ProgramHeaderFlag {
     PF_X,
@@ -100,6 +675,179 @@ pub enum ProgramHeaderFlag {
     PF_R
 }
 
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum SectionType {
+    SHT_NULL,
+    SHT_PROGBITS,
+    SHT_SYMTAB,
+    SHT_STRTAB,
+    SHT_RELA,
+    SHT_HASH,
+    SHT_DYNAMIC,
+    SHT_NOTE,
+    SHT_NOBITS,
+    SHT_REL,
+    SHT_SHLIB,
+    SHT_DYNSYM,
+    SHT_INIT_ARRAY,
+    SHT_FINI_ARRAY,
+    SHT_PREINIT_ARRAY,
+    SHT_GROUP,
+    SHT_SYMTAB_SHNDX,
+    SHT_RELR,
+    // GNU specific
+    SHT_GNU_ATTRIBUTES,
+    SHT_GNU_HASH,
+    SHT_GNU_LIBLIST,
+    SHT_GNU_verdef,
+    SHT_GNU_verneed,
+    SHT_GNU_versym,
+    // Ranges reserved for other uses, the raw value is kept
+    OsSpecific(u32),
+    ProcessorSpecific(u32),
+    UserSpecific(u32),
+    Unknown(u32)
+}
+
+impl SectionType {
+    pub const SHT_LOOS: u32 = 0x60000000;
+    pub const SHT_HIOS: u32 = 0x6FFFFFFF;
+    pub const SHT_LOPROC: u32 = 0x70000000;
+    pub const SHT_HIPROC: u32 = 0x7FFFFFFF;
+    pub const SHT_LOUSER: u32 = 0x80000000;
+    pub const SHT_HIUSER: u32 = 0xFFFFFFFF;
+
+    pub fn value(&self) -> u32 {
+        match *self {
+            SectionType::SHT_NULL => 0,
+            SectionType::SHT_PROGBITS => 1,
+            SectionType::SHT_SYMTAB => 2,
+            SectionType::SHT_STRTAB => 3,
+            SectionType::SHT_RELA => 4,
+            SectionType::SHT_HASH => 5,
+            SectionType::SHT_DYNAMIC => 6,
+            SectionType::SHT_NOTE => 7,
+            SectionType::SHT_NOBITS => 8,
+            SectionType::SHT_REL => 9,
+            SectionType::SHT_SHLIB => 10,
+            SectionType::SHT_DYNSYM => 11,
+            SectionType::SHT_INIT_ARRAY => 14,
+            SectionType::SHT_FINI_ARRAY => 15,
+            SectionType::SHT_PREINIT_ARRAY => 16,
+            SectionType::SHT_GROUP => 17,
+            SectionType::SHT_SYMTAB_SHNDX => 18,
+            SectionType::SHT_RELR => 19,
+            SectionType::SHT_GNU_ATTRIBUTES => 0x6FFFFFF5,
+            SectionType::SHT_GNU_HASH => 0x6FFFFFF6,
+            SectionType::SHT_GNU_LIBLIST => 0x6FFFFFF7,
+            SectionType::SHT_GNU_verdef => 0x6FFFFFFD,
+            SectionType::SHT_GNU_verneed => 0x6FFFFFFE,
+            SectionType::SHT_GNU_versym => 0x6FFFFFFF,
+            SectionType::OsSpecific(v) => v,
+            SectionType::ProcessorSpecific(v) => v,
+            SectionType::UserSpecific(v) => v,
+            SectionType::Unknown(v) => v
+        }
+    }
+}
+
+// Section attribute bits, bits without a name are kept as is
+#[derive(Eq, PartialEq, Clone, Copy, Default, Hash)]
+pub struct SectionFlags(u64);
+
+impl SectionFlags {
+    pub const WRITE: SectionFlags = SectionFlags(0x1);
+    pub const ALLOC: SectionFlags = SectionFlags(0x2);
+    pub const EXECINSTR: SectionFlags = SectionFlags(0x4);
+    pub const MERGE: SectionFlags = SectionFlags(0x10);
+    pub const STRINGS: SectionFlags = SectionFlags(0x20);
+    pub const INFO_LINK: SectionFlags = SectionFlags(0x40);
+    pub const LINK_ORDER: SectionFlags = SectionFlags(0x80);
+    pub const OS_NONCONFORMING: SectionFlags = SectionFlags(0x100);
+    pub const GROUP: SectionFlags = SectionFlags(0x200);
+    pub const TLS: SectionFlags = SectionFlags(0x400);
+    pub const COMPRESSED: SectionFlags = SectionFlags(0x800);
+    pub const GNU_RETAIN: SectionFlags = SectionFlags(0x200000);
+    pub const MASKOS: SectionFlags = SectionFlags(0x0FF00000);
+    pub const MASKPROC: SectionFlags = SectionFlags(0xF0000000);
+
+    const NAMES: [(SectionFlags, &'static str); 12] = [
+        (Self::WRITE, "WRITE"),
+        (Self::ALLOC, "ALLOC"),
+        (Self::EXECINSTR, "EXECINSTR"),
+        (Self::MERGE, "MERGE"),
+        (Self::STRINGS, "STRINGS"),
+        (Self::INFO_LINK, "INFO_LINK"),
+        (Self::LINK_ORDER, "LINK_ORDER"),
+        (Self::OS_NONCONFORMING, "OS_NONCONFORMING"),
+        (Self::GROUP, "GROUP"),
+        (Self::TLS, "TLS"),
+        (Self::COMPRESSED, "COMPRESSED"),
+        (Self::GNU_RETAIN, "GNU_RETAIN")
+    ];
+
+    pub const fn empty() -> Self {
+        SectionFlags(0)
+    }
+
+    pub const fn from_bits(bits: u64) -> Self {
+        SectionFlags(bits)
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(&self, other: SectionFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn intersects(&self, other: SectionFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for SectionFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        SectionFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for SectionFlags {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        SectionFlags(self.0 & rhs.0)
+    }
+}
+
+impl fmt::Debug for SectionFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.0;
+        let mut first = true;
+        for (flag, name) in Self::NAMES {
+            if !self.contains(flag) { continue }
+            if !first { f.write_str(" | ")? }
+            f.write_str(name)?;
+            rest &= !flag.0;
+            first = false;
+        }
+        // Print unknown bits in raw form
+        if rest != 0 || first {
+            if !first { f.write_str(" | ")? }
+            write!(f, "{:#x}", rest)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub struct ElfIdent {
     pub e_bits: BitType,
@@ -109,15 +857,15 @@ pub struct ElfIdent {
     pub e_abi_version: u8
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct ElfHeader64 {
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ElfHeader32 {
     pub e_ident: ElfIdent,
     pub e_type: FileType,
     pub e_machine: MachineType,
     pub e_version: HeaderVersion,
-    pub e_entry: Address64,
-    pub e_phoff: Offset64,
-    pub e_shoff: Offset64,
+    pub e_entry: Address32,
+    pub e_phoff: Offset32,
+    pub e_shoff: Offset32,
     pub e_flags: Word,
     pub e_ehsize: HalfWord,
     pub e_phentsize: HalfWord,
@@ -127,15 +875,43 @@ pub struct ElfHeader64 {
     pub e_shstrndx: HalfWord
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct ElfHeader32 {
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ProgramHeader32 {
+    pub r#type: ProgramHeaderType,
+    pub offset: Offset32,
+    pub vaddr: Address32,
+    pub paddr: Address32,
+    pub filesz: Word,
+    pub memsz: Word,
+    pub flags: Vec<ProgramHeaderFlag>,
+    pub align: Word
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SectionHeader32 {
+    pub sh_name: Word,
+    pub sh_type: SectionType,
+    pub sh_flags: SectionFlags,
+    pub sh_addr: Address32,
+    pub sh_offset: Offset32,
+    pub sh_size: Word,
+    pub sh_link: Word,
+    pub sh_info: Word,
+    pub sh_addralign: Word,
+    pub sh_entsize: Word
+}
+
+// Class independent versions of the headers, they are the ELF64 ones
+// with ELF32 addresses, offsets and sizes widened to 64 bits
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ElfHeader {
     pub e_ident: ElfIdent,
     pub e_type: FileType,
     pub e_machine: MachineType,
     pub e_version: HeaderVersion,
-    pub e_entry: Address32,
-    pub e_phoff: Offset32,
-    pub e_shoff: Offset32,
+    pub e_entry: Address64,
+    pub e_phoff: Offset64,
+    pub e_shoff: Offset64,
     pub e_flags: Word,
     pub e_ehsize: HalfWord,
     pub e_phentsize: HalfWord,
@@ -145,8 +921,8 @@ pub struct ElfHeader32 {
     pub e_shstrndx: HalfWord
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct ProgramHeader64 {
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ProgramHeader {
     pub r#type: ProgramHeaderType,
     pub flags: Vec<ProgramHeaderFlag>,
     pub offset: Offset64,
@@ -157,98 +933,165 @@ pub struct ProgramHeader64 {
     pub align: XWord
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct ProgramHeader32 {
-    pub r#type: ProgramHeaderType,
-    pub offset: Offset32,
-    pub vaddr: Address32,
-    pub paddr: Address32,
-    pub filesz: Word,
-    pub memsz: Word,
-    pub flags: Vec<ProgramHeaderFlag>,
-    pub align: Word
-}
-
-#[derive(Debug, Eq, PartialEq)]
-pub struct SectionHeader64 {
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SectionHeader {
     pub sh_name: Word,
-    pub sh_type: Word,
-    pub sh_flags: Word,
+    pub sh_type: SectionType,
+    pub sh_flags: SectionFlags,
     pub sh_addr: Address64,
     pub sh_offset: Offset64,
-    pub sh_size: Word,
+    pub sh_size: XWord,
     pub sh_link: Word,
     pub sh_info: Word,
-    pub sh_addralign: Word,
-    pub sh_entsize: Word
+    pub sh_addralign: XWord,
+    pub sh_entsize: XWord
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct SectionHeader32 {
-    pub sh_name: Word,
-    pub sh_type: Word,
-    pub sh_flags: Word,
-    pub sh_addr: Address32,
-    pub sh_offset: Offset32,
-    pub sh_size: Word,
-    pub sh_link: Word,
-    pub sh_info: Word,
-    pub sh_addralign: Word,
-    pub sh_entsize: Word
+pub type ElfHeader64 = ElfHeader;
+pub type ProgramHeader64 = ProgramHeader;
+pub type SectionHeader64 = SectionHeader;
+
+impl From<&ElfHeader32> for ElfHeader {
+    fn from(h: &ElfHeader32) -> Self {
+        Self {
+            e_ident: h.e_ident,
+            e_type: h.e_type,
+            e_machine: h.e_machine,
+            e_version: h.e_version,
+            e_entry: h.e_entry.into(),
+            e_phoff: h.e_phoff.into(),
+            e_shoff: h.e_shoff.into(),
+            e_flags: h.e_flags,
+            e_ehsize: h.e_ehsize,
+            e_phentsize: h.e_phentsize,
+            e_phnum: h.e_phnum,
+            e_shentsize: h.e_shentsize,
+            e_shnum: h.e_shnum,
+            e_shstrndx: h.e_shstrndx
+        }
+    }
+}
+
+impl From<&ProgramHeader32> for ProgramHeader {
+    fn from(ph: &ProgramHeader32) -> Self {
+        Self {
+            r#type: ph.r#type,
+            flags: ph.flags.clone(),
+            offset: ph.offset.into(),
+            vaddr: ph.vaddr.into(),
+            paddr: ph.paddr.into(),
+            filesz: ph.filesz.into(),
+            memsz: ph.memsz.into(),
+            align: ph.align.into()
+        }
+    }
+}
+
+impl From<&SectionHeader32> for SectionHeader {
+    fn from(sh: &SectionHeader32) -> Self {
+        Self {
+            sh_name: sh.sh_name,
+            sh_type: sh.sh_type,
+            sh_flags: sh.sh_flags,
+            sh_addr: sh.sh_addr.into(),
+            sh_offset: sh.sh_offset.into(),
+            sh_size: sh.sh_size.into(),
+            sh_link: sh.sh_link,
+            sh_info: sh.sh_info,
+            sh_addralign: sh.sh_addralign.into(),
+            sh_entsize: sh.sh_entsize.into()
+        }
+    }
+}
+
+// What tells the two classes apart, everything else is written once
+// for both in `ElfN` and the parser
+pub trait Class {
+    type Header: fmt::Debug + Eq + Clone;
+    type ProgramHeader: fmt::Debug + Eq + Clone;
+    type SectionHeader: fmt::Debug + Eq + Clone;
+    const BITS: BitType;
+    const HEADER_SIZE: usize;
+    const PROGRAM_HEADER_SIZE: usize;
+    const SECTION_HEADER_SIZE: usize;
+
+    fn widen_header(header: &Self::Header) -> ElfHeader;
+    fn widen_program_header(header: &Self::ProgramHeader) -> ProgramHeader;
+    fn widen_section_header(header: &Self::SectionHeader) -> SectionHeader;
+    // Class sized fields were read as class sized words, they always fit
+    fn narrow_header(header: ElfHeader) -> Self::Header;
+    fn parse_program_header(c: &mut Cursor) -> Result<Self::ProgramHeader, ParseError>;
+    fn parse_section_header(c: &mut Cursor) -> Result<Self::SectionHeader, ParseError>;
 }
 
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Class32;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Class64;
+
 #[derive(Debug, Eq, PartialEq)]
-pub struct Elf64 {
-    header: ElfHeader64,
-    phtable: Vec<ProgramHeader64>,
-    sections_data: Vec<u8>,
-    shtable: Vec<SectionHeader64>
+pub struct ElfN<'a, C: Class> {
+    pub(crate) header: C::Header,
+    pub(crate) phtable: Vec<C::ProgramHeader>,
+    pub(crate) data: &'a [u8], // Whole file, sections and segments borrow from it
+    pub(crate) shtable: Vec<C::SectionHeader>
 }
 
+pub type Elf32<'a> = ElfN<'a, Class32>;
+pub type Elf64<'a> = ElfN<'a, Class64>;
+
+// Either class of file, returned by `Elf::parse`
 #[derive(Debug, Eq, PartialEq)]
-pub struct Elf32 {
-    header: ElfHeader32,
-    phtable: Vec<ProgramHeader32>,
-    sections_data: Vec<u8>,
-    shtable: Vec<SectionHeader32>
-}
-
-impl Elf32 {
-    fn new(header: ElfHeader32,
-           phtable: Vec<ProgramHeader32>,
-           sections_data: Vec<u8>,
-           shtable: Vec<SectionHeader32>) -> Self {
+pub enum Elf<'a> {
+    Elf32(Elf32<'a>),
+    Elf64(Elf64<'a>)
+}
+
+// `size` bytes at `offset` in the file, or else the number of bytes left from `offset`
+pub(crate) fn file_range(data: &[u8], offset: u64, size: u64) -> Result<&[u8], u64> {
+    let left = (data.len() as u64).saturating_sub(offset);
+    if size > left { return Err(left) }
+    if size == 0 { return Ok(&[]) } // The offset of an empty range may be anything
+    Ok(&data[offset as usize..(offset + size) as usize])
+}
+
+impl<'a, C: Class> ElfN<'a, C> {
+    pub(crate) fn new(header: C::Header,
+           phtable: Vec<C::ProgramHeader>,
+           data: &'a [u8],
+           shtable: Vec<C::SectionHeader>) -> Self {
         Self {
             header,
             phtable,
-            sections_data,
+            data,
             shtable
         }
     }
 
-    fn get_entry_point(&self) -> u32 {
-        self.header.e_entry
+    pub fn header(&self) -> &C::Header {
+        &self.header
+    }
+
+    pub fn phtable(&self) -> &[C::ProgramHeader] {
+        &self.phtable
+    }
+
+    pub fn shtable(&self) -> &[C::SectionHeader] {
+        &self.shtable
     }
 
     // ...
 }
 
-impl Elf64 {
-    fn new(header: ElfHeader64,
-           phtable: Vec<ProgramHeader64>,
-           sections_data: Vec<u8>,
-           shtable: Vec<SectionHeader64>) -> Self {
-        Self {
-            header,
-            phtable,
-            sections_data,
-            shtable
-        }
+impl Elf32<'_> {
+    pub fn get_entry_point(&self) -> u32 {
+        self.header.e_entry
     }
+}
 
-    fn get_entry_point(&self) -> u64 {
+impl Elf64<'_> {
+    pub fn get_entry_point(&self) -> u64 {
         self.header.e_entry
     }
-
-    // ...
 }
@@ -0,0 +1,542 @@
+use crate::debug_link::{DebugAltLink, DebugLink};
+use crate::dynamic::{Dynamic, DynamicTag};
+use crate::elf::*;
+use crate::endianness::Endianness;
+use crate::hash::{hash_table_data, GnuHashTable, SysvHashTable};
+use crate::machine_flags::MachineFlags;
+use crate::note::{Note, NoteIterator, NT_GNU_BUILD_ID};
+use crate::parse_error::ParseError;
+use crate::relocation::RelocationTable;
+use crate::segment_map::{is_tbss_special, section_in_segment};
+use crate::string_table::StringTable;
+use crate::symbol::{Symbol, SymbolTable};
+use crate::version::{SymbolVersions, VersionedSymbol};
+use std::borrow::Cow;
+
+// Class independent access to a parsed file, implemented by `Elf32`, `Elf64`
+// and `Elf`. Headers are handed out widened to 64 bits so the same code
+// works on both classes
+pub trait ElfFile<'a> {
+    // Whole file
+    fn data(&self) -> &'a [u8];
+    fn ident(&self) -> ElfIdent;
+    fn elf_header(&self) -> ElfHeader;
+    fn section_count(&self) -> usize;
+    fn section_header(&self, index: usize) -> Option<SectionHeader>;
+    fn segment_count(&self) -> usize;
+    fn program_header(&self, index: usize) -> Option<ProgramHeader>;
+
+    fn bits(&self) -> BitType {
+        self.ident().e_bits
+    }
+
+    fn endianness(&self) -> Endianness {
+        self.ident().e_endianness
+    }
+
+    fn machine(&self) -> MachineType {
+        self.elf_header().e_machine
+    }
+
+    // e_flags decoded for the machine
+    fn machine_flags(&self) -> MachineFlags {
+        MachineFlags::decode(self.machine(), self.bits(), self.elf_header().e_flags)
+    }
+
+    fn file_type(&self) -> FileType {
+        self.elf_header().e_type
+    }
+
+    fn entry(&self) -> u64 {
+        self.elf_header().e_entry
+    }
+
+    fn section_headers(&self) -> impl Iterator<Item = SectionHeader> {
+        (0..self.section_count()).filter_map(|i| self.section_header(i))
+    }
+
+    fn program_headers(&self) -> impl Iterator<Item = ProgramHeader> {
+        (0..self.segment_count()).filter_map(|i| self.program_header(i))
+    }
+
+    // Content of a section in the file, empty for SHT_NOBITS
+    fn section_data(&self, section: &SectionHeader) -> Result<&'a [u8], ParseError> {
+        if section.sh_type == SectionType::SHT_NOBITS { return Ok(&[]) }
+        let (offset, size) = (section.sh_offset, section.sh_size);
+        file_range(self.data(), offset, size).map_err(|actual| ParseError::SectionOutOfBounds { offset, expected: size, actual })
+    }
+
+    // Bytes of a segment present in the file, the zero-filled part is not included
+    fn segment_data(&self, segment: &ProgramHeader) -> Result<&'a [u8], ParseError> {
+        let (offset, size) = (segment.offset, segment.filesz);
+        file_range(self.data(), offset, size).map_err(|actual| ParseError::SegmentOutOfBounds { offset, expected: size, actual })
+    }
+
+    // File offset of a virtual address, found through the PT_LOAD segments.
+    // Addresses in the zero-filled tail of a segment have no offset
+    fn vaddr_to_offset(&self, addr: u64) -> Result<u64, ParseError> {
+        let segment = self.load_segment_at(addr).ok_or(ParseError::UnmappedAddress(addr))?;
+        let delta = addr - segment.vaddr;
+        if delta >= segment.filesz { return Err(ParseError::ZeroFilledAddress(addr)) }
+        segment.offset.checked_add(delta).ok_or(ParseError::UnmappedAddress(addr))
+    }
+
+    // Virtual address a file offset is loaded at
+    fn offset_to_vaddr(&self, offset: u64) -> Result<u64, ParseError> {
+        self.program_headers()
+            .filter(|ph| ph.r#type == ProgramHeaderType::PT_LOAD)
+            .find(|ph| offset >= ph.offset && offset - ph.offset < ph.filesz)
+            .and_then(|ph| ph.vaddr.checked_add(offset - ph.offset))
+            .ok_or(ParseError::UnmappedOffset(offset))
+    }
+
+    // PT_LOAD segment whose memory image holds `addr`
+    fn load_segment_at(&self, addr: u64) -> Option<ProgramHeader> {
+        self.program_headers()
+            .filter(|ph| ph.r#type == ProgramHeaderType::PT_LOAD)
+            .find(|ph| addr >= ph.vaddr && addr - ph.vaddr < ph.memsz)
+    }
+
+    // `len` bytes of the memory image at `addr`, which must stay inside one
+    // segment. Only borrows from the file when no zero-filled byte is included
+    fn read_at_vaddr(&self, addr: u64, len: usize) -> Result<Cow<'a, [u8]>, ParseError> {
+        let segment = self.load_segment_at(addr).ok_or(ParseError::UnmappedAddress(addr))?;
+        let start = addr - segment.vaddr;
+        let end = start.checked_add(len as u64).ok_or(ParseError::UnmappedAddress(addr))?;
+        if end > segment.memsz { return Err(ParseError::UnmappedAddress(addr)) }
+
+        let file = self.segment_data(&segment)?;
+        let file_len = file.len() as u64;
+        let bytes = &file[start.min(file_len) as usize..end.min(file_len) as usize];
+        if end <= file_len { return Ok(Cow::Borrowed(bytes)) }
+        let mut bytes = bytes.to_vec();
+        bytes.resize(len, 0);
+        Ok(Cow::Owned(bytes))
+    }
+
+    // Indexes of the sections inside each program header, the same way
+    // readelf prints its section to segment mapping
+    fn section_to_segment_mapping(&self) -> Vec<Vec<usize>> {
+        self.program_headers().map(|segment| {
+            (1..self.section_count())
+                .filter(|&i| self.section_header(i).is_some_and(|section| {
+                    !is_tbss_special(&section, &segment) && section_in_segment(&section, &segment, true, true)
+                }))
+                .collect()
+        }).collect()
+    }
+
+    // Index of the section names string table, if any.
+    // With extended numbering the real index lives in the first section
+    fn shstrndx(&self) -> Option<usize> {
+        let index = match self.elf_header().e_shstrndx {
+            SHN_UNDEF => return None,
+            SHN_XINDEX => self.section_header(0)?.sh_link as usize,
+            i => i as usize
+        };
+        if index < self.section_count() { Some(index) } else { None }
+    }
+
+    // String table holding the section names
+    fn shstrtab(&self) -> Result<Option<StringTable<'a>>, ParseError> {
+        match self.shstrndx().and_then(|i| self.section_header(i)) {
+            Some(section) => Ok(Some(StringTable::new(self.section_data(&section)?, section.sh_offset))),
+            None => Ok(None)
+        }
+    }
+
+    // Name of a section, empty when the file has no section names
+    fn section_name(&self, section: &SectionHeader) -> Result<&'a str, ParseError> {
+        match self.shstrtab()? {
+            Some(strtab) => strtab.get(section.sh_name as usize),
+            None => Ok("")
+        }
+    }
+
+    // Every section header along with its name
+    fn sections(&self) -> impl Iterator<Item = Result<(&'a str, SectionHeader), ParseError>> {
+        let strtab = self.shstrtab();
+        self.section_headers().map(move |section| {
+            let name = match strtab.clone()? {
+                Some(strtab) => strtab.get(section.sh_name as usize)?,
+                None => ""
+            };
+            Ok((name, section))
+        })
+    }
+
+    // First section with the given name, sections with broken names are skipped
+    fn section_by_name(&self, name: &str) -> Option<SectionHeader> {
+        self.sections()
+            .filter_map(Result::ok)
+            .find(|(section_name, _)| *section_name == name)
+            .map(|(_, section)| section)
+    }
+
+    // Index of the first section with the given name
+    fn section_index_by_name(&self, name: &str) -> Option<usize> {
+        self.sections().position(|section| matches!(section, Ok((section_name, _)) if section_name == name))
+    }
+
+    // Index of the first section of the given type
+    fn section_index_by_type(&self, r#type: SectionType) -> Option<usize> {
+        self.section_headers().position(|section| section.sh_type == r#type)
+    }
+
+    // Static symbols from .symtab
+    fn symbol_table(&self) -> Result<Option<SymbolTable<'a>>, ParseError> {
+        self.section_index_by_type(SectionType::SHT_SYMTAB)
+            .map(|i| SymbolTable::parse(self, i))
+            .transpose()
+    }
+
+    // Dynamic symbols from .dynsym, or from DT_SYMTAB once the section
+    // headers are stripped
+    fn dynamic_symbol_table(&self) -> Result<Option<SymbolTable<'a>>, ParseError> {
+        match self.section_index_by_type(SectionType::SHT_DYNSYM) {
+            Some(i) => SymbolTable::parse(self, i).map(Some),
+            None => SymbolTable::from_dynamic(self)
+        }
+    }
+
+    // Iterators over the symbol tables, a broken table is reported once
+    fn symbols(&self) -> impl Iterator<Item = Result<Symbol<'a>, ParseError>> {
+        symbols_of(self.symbol_table())
+    }
+
+    fn dynamic_symbols(&self) -> impl Iterator<Item = Result<Symbol<'a>, ParseError>> {
+        symbols_of(self.dynamic_symbol_table())
+    }
+
+    // Entries of the dynamic section
+    fn dynamic(&self) -> Result<Option<Dynamic<'a>>, ParseError> {
+        Dynamic::parse(self)
+    }
+
+    // DT_GNU_HASH table covering .dynsym
+    fn gnu_hash_table(&self) -> Result<Option<GnuHashTable<'a>>, ParseError> {
+        let (Some((data, offset)), Some(symbols)) = (hash_table_data(self, DynamicTag::DT_GNU_HASH, SectionType::SHT_GNU_HASH)?,
+                                                     self.dynamic_symbol_table()?) else { return Ok(None) };
+        GnuHashTable::parse(data, offset, symbols.len(), self.bits(), self.endianness()).map(Some)
+    }
+
+    // DT_HASH table covering .dynsym
+    fn sysv_hash_table(&self) -> Result<Option<SysvHashTable<'a>>, ParseError> {
+        let (Some((data, offset)), Some(symbols)) = (hash_table_data(self, DynamicTag::DT_HASH, SectionType::SHT_HASH)?,
+                                                     self.dynamic_symbol_table()?) else { return Ok(None) };
+        SysvHashTable::parse(data, offset, symbols.len(), self.endianness()).map(Some)
+    }
+
+    // Defined dynamic symbol named `name`, found through the hash tables
+    // like the dynamic loader does, DT_GNU_HASH first then DT_HASH
+    fn lookup_dynamic_symbol(&self, name: &str) -> Result<Option<Symbol<'a>>, ParseError> {
+        let Some(symbols) = self.dynamic_symbol_table()? else { return Ok(None) };
+        let versions = self.symbol_versions()?;
+        let found = match self.gnu_hash_table()? {
+            Some(table) => table.lookup(name, &symbols, versions.as_ref())?,
+            None => match self.sysv_hash_table()? {
+                Some(table) => table.lookup(name, &symbols, versions.as_ref())?,
+                None => return Err(ParseError::MissingHashTable)
+            }
+        };
+        Ok(found.map(|(_, symbol)| symbol))
+    }
+
+    // GNU symbol versioning of the dynamic symbols
+    fn symbol_versions(&self) -> Result<Option<SymbolVersions<'a>>, ParseError> {
+        SymbolVersions::parse(self)
+    }
+
+    // Dynamic symbols with the version .gnu.version assigns them
+    fn versioned_dynamic_symbols(&self) -> Result<Vec<VersionedSymbol<'a>>, ParseError> {
+        let versions = self.symbol_versions()?;
+        self.dynamic_symbols().enumerate().map(|(i, symbol)| {
+            let version = match &versions {
+                Some(versions) => versions.version_of(i)?,
+                None => None
+            };
+            Ok(VersionedSymbol { symbol: symbol?, version })
+        }).collect()
+    }
+
+    // Every SHT_REL, SHT_RELA and SHT_RELR section
+    fn relocation_tables(&self) -> Result<Vec<RelocationTable<'a>>, ParseError> {
+        let relocation_types = [SectionType::SHT_REL, SectionType::SHT_RELA, SectionType::SHT_RELR];
+        self.section_headers()
+            .enumerate()
+            .filter(|(_, section)| relocation_types.contains(&section.sh_type))
+            .map(|(i, _)| RelocationTable::parse(self, i))
+            .collect()
+    }
+
+    // Notes of every PT_NOTE segment
+    fn segment_notes(&self) -> Result<Vec<Note<'a>>, ParseError> {
+        let mut notes = Vec::new();
+        for segment in self.program_headers().filter(|ph| ph.r#type == ProgramHeaderType::PT_NOTE) {
+            for note in NoteIterator::new(self.segment_data(&segment)?, segment.offset, segment.align, self.endianness()) {
+                notes.push(note?);
+            }
+        }
+        Ok(notes)
+    }
+
+    // Notes of every SHT_NOTE section
+    fn section_notes(&self) -> Result<Vec<Note<'a>>, ParseError> {
+        let mut notes = Vec::new();
+        for section in self.section_headers().filter(|sh| sh.sh_type == SectionType::SHT_NOTE) {
+            for note in NoteIterator::new(self.section_data(&section)?, section.sh_offset, section.sh_addralign, self.endianness()) {
+                notes.push(note?);
+            }
+        }
+        Ok(notes)
+    }
+
+    // Descriptor of the NT_GNU_BUILD_ID note, looked up in the segments
+    // first so it is still found once the section headers are stripped
+    fn build_id(&self) -> Result<Option<&'a [u8]>, ParseError> {
+        let is_build_id = |note: &Note| note.name == b"GNU" && note.n_type == NT_GNU_BUILD_ID;
+        if let Some(note) = self.segment_notes()?.iter().find(|n| is_build_id(n)) {
+            return Ok(Some(note.desc));
+        }
+        Ok(self.section_notes()?.iter().find(|n| is_build_id(n)).map(|note| note.desc))
+    }
+
+    fn debug_link(&self) -> Result<Option<DebugLink<'a>>, ParseError> {
+        let Some(index) = self.section_index_by_name(".gnu_debuglink") else { return Ok(None) };
+        let section = self.section_header(index).ok_or(ParseError::InvalidSectionIndex(index))?;
+        Ok(Some(DebugLink::parse(self.section_data(&section)?, index, section.sh_offset, self.endianness())?))
+    }
+
+    fn debug_alt_link(&self) -> Result<Option<DebugAltLink<'a>>, ParseError> {
+        match self.section_by_name(".gnu_debugaltlink") {
+            Some(section) => Ok(Some(DebugAltLink::parse(self.section_data(&section)?, section.sh_offset)?)),
+            None => Ok(None)
+        }
+    }
+}
+
+fn symbols_of(table: Result<Option<SymbolTable<'_>>, ParseError>) -> impl Iterator<Item = Result<Symbol<'_>, ParseError>> {
+    let (table, error) = match table {
+        Ok(table) => (table, None),
+        Err(e) => (None, Some(Err(e)))
+    };
+    error.into_iter().chain(table.into_iter().flat_map(|table| table.iter()))
+}
+
+impl<'a, C: Class> ElfFile<'a> for ElfN<'a, C> {
+    fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn ident(&self) -> ElfIdent {
+        self.elf_header().e_ident
+    }
+
+    fn elf_header(&self) -> ElfHeader {
+        C::widen_header(&self.header)
+    }
+
+    fn section_count(&self) -> usize {
+        self.shtable.len()
+    }
+
+    fn section_header(&self, index: usize) -> Option<SectionHeader> {
+        self.shtable.get(index).map(C::widen_section_header)
+    }
+
+    fn segment_count(&self) -> usize {
+        self.phtable.len()
+    }
+
+    fn program_header(&self, index: usize) -> Option<ProgramHeader> {
+        self.phtable.get(index).map(C::widen_program_header)
+    }
+}
+
+impl<'a> ElfFile<'a> for Elf<'a> {
+    fn data(&self) -> &'a [u8] {
+        match self {
+            Elf::Elf32(elf) => elf.data(),
+            Elf::Elf64(elf) => elf.data()
+        }
+    }
+
+    fn ident(&self) -> ElfIdent {
+        match self {
+            Elf::Elf32(elf) => elf.ident(),
+            Elf::Elf64(elf) => elf.ident()
+        }
+    }
+
+    fn elf_header(&self) -> ElfHeader {
+        match self {
+            Elf::Elf32(elf) => elf.elf_header(),
+            Elf::Elf64(elf) => elf.elf_header()
+        }
+    }
+
+    fn section_count(&self) -> usize {
+        match self {
+            Elf::Elf32(elf) => elf.section_count(),
+            Elf::Elf64(elf) => elf.section_count()
+        }
+    }
+
+    fn section_header(&self, index: usize) -> Option<SectionHeader> {
+        match self {
+            Elf::Elf32(elf) => elf.section_header(index),
+            Elf::Elf64(elf) => elf.section_header(index)
+        }
+    }
+
+    fn segment_count(&self) -> usize {
+        match self {
+            Elf::Elf32(elf) => elf.segment_count(),
+            Elf::Elf64(elf) => elf.segment_count()
+        }
+    }
+
+    fn program_header(&self, index: usize) -> Option<ProgramHeader> {
+        match self {
+            Elf::Elf32(elf) => elf.program_header(index),
+            Elf::Elf64(elf) => elf.program_header(index)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn unified() {
+        use super::*;
+        use std::fs;
+
+        let content = fs::read("testdata/hello").unwrap();
+        let elf64 = Elf64::parse(&content).unwrap();
+        let elf = Elf::parse(&content).unwrap();
+        assert!(matches!(elf, Elf::Elf64(_)));
+        assert_eq!(elf.bits(), BitType::_64);
+        assert_eq!(elf.entry(), elf64.get_entry_point());
+        assert_eq!(elf.section_count(), elf64.shtable().len());
+        assert_eq!(elf.program_headers().count(), elf64.phtable().len());
+
+        let text = elf.section_by_name(".text").unwrap();
+        assert_eq!(text, elf64.section_by_name(".text").unwrap());
+        assert_eq!(elf.section_data(&text).unwrap().len(), text.sh_size as usize);
+
+        assert_eq!(Elf::parse(&content[..8]), Err(ParseError::TooSmallIdent { expected: EI_NIDENT, actual: 8 }));
+        assert_eq!(Elf::parse(b"#!/bin/sh\nexit 0\n"), Err(ParseError::NotELF { magic: *b"#!/b" }));
+    }
+
+    #[test]
+    fn unified32() {
+        use super::*;
+        use std::fs;
+
+        let content = fs::read("testdata/static32").unwrap();
+
+        let elf32 = Elf32::parse(&content).unwrap();
+        let elf = Elf::parse(&content).unwrap();
+        assert!(matches!(elf, Elf::Elf32(_)));
+        assert_eq!(elf.machine(), MachineType::Intel_80386);
+        assert_eq!(elf.entry(), elf32.get_entry_point() as u64);
+        assert_eq!(elf.program_headers().count(), elf32.phtable().len());
+        let data = elf.section_by_name(".data").unwrap();
+        assert_eq!(elf.section_data(&data).unwrap(), &1u32.to_le_bytes());
+    }
+
+    #[test]
+    fn addresses() {
+        use super::*;
+        use std::fs;
+
+        // Initialized data followed by a zero-filled array in the same segment
+        let content = fs::read("testdata/addresses").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let symbol = |name: &str| elf.symbols().map(Result::unwrap).find(|s| s.name == name).unwrap();
+        let (value, buffer) = (symbol("value"), symbol("buffer"));
+
+        let offset = elf.vaddr_to_offset(value.value).unwrap();
+        assert_eq!(&content[offset as usize..offset as usize + 4], &0x12345678u32.to_le_bytes());
+        assert_eq!(elf.offset_to_vaddr(offset), Ok(value.value));
+        assert!(matches!(elf.read_at_vaddr(value.value, 4).unwrap(), Cow::Borrowed(b) if b == 0x12345678u32.to_le_bytes()));
+
+        assert_eq!(elf.vaddr_to_offset(buffer.value), Err(ParseError::ZeroFilledAddress(buffer.value)));
+        assert_eq!(elf.read_at_vaddr(buffer.value, 4096).unwrap().as_ref(), &[0; 4096][..]);
+
+        // Reads spanning the end of the file part are completed with zeros
+        let data_end = value.value + 4;
+        let padded = elf.read_at_vaddr(value.value, (buffer.value + 16 - value.value) as usize).unwrap();
+        assert!(matches!(padded, Cow::Owned(_)));
+        assert!(padded[(data_end - value.value) as usize..].iter().all(|&b| b == 0));
+
+        assert_eq!(elf.vaddr_to_offset(u64::MAX), Err(ParseError::UnmappedAddress(u64::MAX)));
+        assert_eq!(elf.read_at_vaddr(buffer.value, 1 << 20), Err(ParseError::UnmappedAddress(buffer.value)));
+        assert_eq!(elf.offset_to_vaddr(content.len() as u64), Err(ParseError::UnmappedOffset(content.len() as u64)));
+    }
+
+    #[test]
+    fn malformed() {
+        use super::*;
+        use std::fs;
+
+        // Runs every reader over a damaged file, errors are fine, panics are not
+        fn walk(content: &[u8]) {
+            let Ok(elf) = Elf::parse(content) else { return };
+            let _ = (elf.machine_flags().to_string(), elf.section_to_segment_mapping());
+            for section in elf.section_headers() {
+                let _ = (elf.section_data(&section), elf.section_name(&section));
+            }
+            for segment in elf.program_headers() {
+                let _ = (elf.segment_data(&segment), elf.offset_to_vaddr(segment.offset));
+                let _ = elf.read_at_vaddr(segment.vaddr, 64);
+            }
+            let _ = (elf.sections().count(), elf.symbols().count(), elf.dynamic_symbols().count());
+            if let Ok(Some(dynamic)) = elf.dynamic() {
+                let _ = (dynamic.needed().count(), dynamic.soname(), dynamic.flags_1());
+            }
+            let _ = (elf.gnu_hash_table(), elf.sysv_hash_table(), elf.lookup_dynamic_symbol("malloc"));
+            let _ = elf.versioned_dynamic_symbols();
+            for table in elf.relocation_tables().into_iter().flatten() {
+                let _ = table.iter().count();
+            }
+            let _ = (elf.segment_notes(), elf.section_notes(), elf.build_id());
+            let _ = (elf.debug_link(), elf.debug_alt_link());
+        }
+
+        let content = fs::read("testdata/hello").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let header = elf.header();
+        let mut tables = vec![0..ELF64_HEADER_SIZE,
+                              header.e_phoff as usize..header.e_phoff as usize + header.e_phnum as usize * PROGRAM_HEADER64_SIZE,
+                              header.e_shoff as usize..header.e_shoff as usize + header.e_shnum as usize * SECTION_HEADER64_SIZE];
+        tables.extend(elf.shtable().iter()
+            .filter(|sh| sh.sh_type != SectionType::SHT_NOBITS && sh.sh_size > 0)
+            .map(|sh| sh.sh_offset as usize..(sh.sh_offset + sh.sh_size) as usize));
+
+        // Cut off at every header and table boundary and a few places in between
+        for end in (0..content.len()).step_by(content.len() / 64).chain(tables.iter().flat_map(|t| [t.start, t.end])) {
+            walk(&content[..end.min(content.len())]);
+        }
+
+        // Overwrite header fields with values near the limits, a xorshift
+        // keeps the runs the same every time
+        let mut state = 0x2545F4914F6CDD1Du64;
+        let mut next = || { state ^= state << 13; state ^= state >> 7; state ^= state << 17; state };
+        for _ in 0..2000 {
+            let mut damaged = content.clone();
+            for _ in 0..4 {
+                let table = &tables[next() as usize % tables.len()];
+                let offset = table.start + next() as usize % (table.end - table.start);
+                let value = match next() % 4 {
+                    0 => [0xFF; 8],
+                    1 => [0; 8],
+                    _ => next().to_le_bytes()
+                };
+                let end = (offset + 1 + next() as usize % 8).min(damaged.len());
+                damaged[offset..end].copy_from_slice(&value[..end - offset]);
+            }
+            walk(&damaged);
+        }
+    }
+}
@@ -1,35 +1,34 @@
 use crate::elf::*;
+use crate::cursor::Cursor;
 use crate::parse_error::ParseError;
-use crate::endianness::{self, Endianness};
-use std::cell::Cell;
+use crate::endianness::Endianness;
 
 pub fn parse_ident(ident: &[u8]) -> Result<ElfIdent, ParseError> {
-    if ident.len() < EI_NIDENT { return Err(ParseError::TooSmallIdent) } // Validate size
+    if ident.len() < EI_NIDENT { return Err(ParseError::TooSmallIdent { expected: EI_NIDENT, actual: ident.len() }) } // Validate size
 
     let magic = &ident[0..4]; // Get magic number
-    if magic != ELF_MAGIC_NUM { return Err(ParseError::NotELF) } // Ensure file is elf
-    
-    // Read a byte
-    let idx = Cell::new(4);
-    let read = || -> u8 { let temp = idx.get(); let v = ident[temp]; idx.set(temp + 1); v };
+    if magic != ELF_MAGIC_NUM { return Err(ParseError::NotELF { magic: [magic[0], magic[1], magic[2], magic[3]] }) } // Ensure file is elf
     
+    // Single bytes, the byte order doesn't matter
+    let mut c = Cursor::new(ident, 4, Endianness::LittleEndian);
+
     // 64 bits or 32
-    let bits = match read() {
+    let bits = match c.read8()? {
         1 => BitType::_32,
         2 => BitType::_64,
-        _ => return Err(ParseError::UnsupportedMode)
+        v => return Err(ParseError::UnsupportedMode(v))
     };
 
-    let endianness = match read() {
+    let endianness = match c.read8()? {
         1 => Endianness::LittleEndian,
         2 => Endianness::BigEndian,
-        _ => return Err(ParseError::UnsupportedEndianness)
+        v => return Err(ParseError::UnsupportedEndianness(v))
     };
 
-    let header_format_version = read();
+    let header_format_version = c.read8()?;
 
     // Application binary interface
-    let abi = match read() {
+    let abi = match c.read8()? {
         0 => ABI::UnixSystemV,
         1 => ABI::HP_UX,
         2 => ABI::NetBSD,
@@ -44,10 +43,10 @@ pub fn parse_ident(ident: &[u8]) -> Result<ElfIdent, ParseError> {
         64 => ABI::ARM_EABI,
         97 => ABI::ARM,
         255 => ABI::Standalone,
-        _ => return Err(ParseError::UnsupportedABI)
+        v => ABI::Unknown(v)
     };
 
-    let abi_ver = read();
+    let abi_ver = c.read8()?;
 
     Ok(ElfIdent {
         e_bits: bits,
@@ -58,203 +57,384 @@ pub fn parse_ident(ident: &[u8]) -> Result<ElfIdent, ParseError> {
     })
 }
 
-impl Elf64 {
-    pub fn parse_header(h: &[u8]) -> Result<ElfHeader64, ParseError> {
-        if h.len() < ELF64_HEADER_SIZE { return Err(ParseError::TooSmallHeader) } // Validate size
-        let ident = &h[..EI_NIDENT]; // Ident part
-        let ident = parse_ident(ident)?; // Parse ident
-        let endian = ident.e_endianness; // Get endian type
+// Index, offset and number of bytes left of the first entry of a header
+// table that does not fit in a file of `file_len` bytes
+fn cut_off_entry(file_len: u64, offset: u64, entsize: usize, count: usize) -> Option<(usize, u64, u64)> {
+    let left = file_len.saturating_sub(offset);
+    let fitting = left / entsize as u64;
+    if fitting >= count as u64 { return None }
+    Some((fitting as usize, offset + fitting * entsize as u64, left - fitting * entsize as u64))
+}
 
-        // Closures for reading values
-        let idx = Cell::new(EI_NIDENT);
-        let r16 = || -> u16 { let temp = idx.get(); let v = endianness::read16(&[h[temp], h[temp + 1]], endian); idx.set(temp + 2); v };
-        let r32 = || -> u32 { let temp = idx.get(); let v = endianness::read32(&[h[temp], h[temp + 1], h[temp + 2], h[temp + 3]], endian); idx.set(temp + 4); v };
-        let r64 = || -> u64 { let temp = idx.get(); let v = endianness::read64(&[h[temp],     h[temp + 1], h[temp + 2], h[temp + 3],
-                                                           h[temp + 4], h[temp + 5], h[temp + 6], h[temp + 7]], endian); idx.set(temp + 8); v };
-        let filetype = match r16() {
-            0 => FileType::ET_NONE,
-            1 => FileType::ET_NONE,
-            2 => FileType::ET_EXEC,
-            3 => FileType::ET_DYN,
-            4 => FileType::ET_CORE,
-            _ => return Err(ParseError::UnsupportedFileType)
-        };
-
-        let machine = match r16() {
-            0 => MachineType::None,
-            2 => MachineType::SPARC,
-            3 => MachineType::Intel_80386,
-            4 => MachineType::Motorola_68000,
-            7 => MachineType::Intel_i860,
-            8 => MachineType::MIPS_I,
-            19 => MachineType::Intel_i960,
-            20 => MachineType::PowerPC,
-            40 => MachineType::ARM,
-            50 => MachineType::Intel_IA64,
-            62 => MachineType::x64,
-            243 => MachineType::RISC_V,
-            _ => return Err(ParseError::UnsupportedMachineType)
-        };
-        
-        let version = match r32() {
-            0 => HeaderVersion::None,
-            1 => HeaderVersion::Current,
-            _ => return Err(ParseError::UnsupportedVersion)
-        };
-        
-        Ok(ElfHeader64 {
-            e_ident: ident,
-            e_type: filetype,
-            e_machine: machine,
-            e_version: version,
-            e_entry: r64(),
-            e_phoff: r64(),
-            e_shoff: r64(),
-            e_flags: r32(),
-            e_ehsize: r16(),
-            e_phentsize: r16(),
-            e_phnum: r16(),
-            e_shentsize: r16(),
-            e_shnum: r16(),
-            e_shstrndx: r16(),
+pub(crate) fn check_program_header_table(file_len: u64, offset: u64, entsize: usize, count: usize) -> Result<(), ParseError> {
+    match cut_off_entry(file_len, offset, entsize, count) {
+        Some((index, offset, actual)) => Err(ParseError::TooSmallProgramHeaderTable { index, offset, expected: entsize as u64, actual }),
+        None => Ok(())
+    }
+}
+
+pub(crate) fn check_section_header_table(file_len: u64, offset: u64, entsize: usize, count: usize) -> Result<(), ParseError> {
+    match cut_off_entry(file_len, offset, entsize, count) {
+        Some((index, offset, actual)) => Err(ParseError::TooSmallSectionHeaderTable { index, offset, expected: entsize as u64, actual }),
+        None => Ok(())
+    }
+}
+
+// Names the header a read going past the end of its table was for
+pub(crate) fn program_header_error(index: usize) -> impl Fn(ParseError) -> ParseError {
+    move |e| match e {
+        ParseError::UnexpectedEnd { offset, expected, actual } => ParseError::TooSmallProgramHeaderTable { index, offset, expected, actual },
+        e => e
+    }
+}
+
+pub(crate) fn section_header_error(index: usize) -> impl Fn(ParseError) -> ParseError {
+    move |e| match e {
+        ParseError::UnexpectedEnd { offset, expected, actual } => ParseError::TooSmallSectionHeaderTable { index, offset, expected, actual },
+        e => e
+    }
+}
+
+pub fn parse_file_type(v: u16) -> FileType {
+    match v {
+        0 => FileType::ET_NONE,
+        1 => FileType::ET_REL,
+        2 => FileType::ET_EXEC,
+        3 => FileType::ET_DYN,
+        4 => FileType::ET_CORE,
+        FileType::ET_LOOS..=FileType::ET_HIOS => FileType::OsSpecific(v),
+        FileType::ET_LOPROC..=FileType::ET_HIPROC => FileType::ProcessorSpecific(v),
+        _ => FileType::Unknown(v)
+    }
+}
+
+pub fn parse_machine_type(v: u16) -> MachineType {
+    MachineType::from_value(v)
+}
+
+pub fn parse_header_version(v: u32) -> HeaderVersion {
+    match v {
+        0 => HeaderVersion::None,
+        1 => HeaderVersion::Current,
+        _ => HeaderVersion::Unknown(v)
+    }
+}
+
+pub fn parse_program_header_type(v: u32) -> ProgramHeaderType {
+    match v {
+        0 => ProgramHeaderType::PT_NULL,
+        1 => ProgramHeaderType::PT_LOAD,
+        2 => ProgramHeaderType::PT_DYNAMIC,
+        3 => ProgramHeaderType::PT_INTERP,
+        4 => ProgramHeaderType::PT_NOTE,
+        5 => ProgramHeaderType::PT_SHLIB,
+        6 => ProgramHeaderType::PT_PHDR,
+        7 => ProgramHeaderType::PT_TLS,
+        0x6474E553 => ProgramHeaderType::GNU_PROPERTY,
+        0x6474E550 => ProgramHeaderType::GNU_EH_FRAME,
+        0x6474E551 => ProgramHeaderType::GNU_STACK,
+        0x6474E552 => ProgramHeaderType::GNU_RELRO,
+        0x6474E554 => ProgramHeaderType::GNU_SFRAME,
+        0x65A3DBE5 => ProgramHeaderType::OPENBSD_MUTABLE,
+        0x65A3DBE6 => ProgramHeaderType::OPENBSD_RANDOMIZE,
+        0x65A3DBE7 => ProgramHeaderType::OPENBSD_WXNEEDED,
+        0x65A3DBE8 => ProgramHeaderType::OPENBSD_NOBTCFI,
+        0x65A3DBE9 => ProgramHeaderType::OPENBSD_SYSCALLS,
+        0x65A41BE6 => ProgramHeaderType::OPENBSD_BOOTDATA,
+        ProgramHeaderType::PT_LOOS..=ProgramHeaderType::PT_HIOS => ProgramHeaderType::OsSpecific(v),
+        ProgramHeaderType::PT_LOPROC..=ProgramHeaderType::PT_HIPROC => ProgramHeaderType::ProcessorSpecific(v),
+        _ => ProgramHeaderType::Unknown(v)
+    }
+}
+
+pub fn parse_program_header_flags(num: u32) -> Vec<ProgramHeaderFlag> {
+    let mut flags: Vec<ProgramHeaderFlag> = Vec::new();
+    if num & 1 == 1 { flags.push(ProgramHeaderFlag::PF_X); }
+    if ((num >> 1) & 1) == 1 { flags.push(ProgramHeaderFlag::PF_W); }
+    if ((num >> 2) & 1) == 1 { flags.push(ProgramHeaderFlag::PF_R); }
+    flags
+}
+
+pub fn parse_section_type(v: u32) -> SectionType {
+    match v {
+        0 => SectionType::SHT_NULL,
+        1 => SectionType::SHT_PROGBITS,
+        2 => SectionType::SHT_SYMTAB,
+        3 => SectionType::SHT_STRTAB,
+        4 => SectionType::SHT_RELA,
+        5 => SectionType::SHT_HASH,
+        6 => SectionType::SHT_DYNAMIC,
+        7 => SectionType::SHT_NOTE,
+        8 => SectionType::SHT_NOBITS,
+        9 => SectionType::SHT_REL,
+        10 => SectionType::SHT_SHLIB,
+        11 => SectionType::SHT_DYNSYM,
+        14 => SectionType::SHT_INIT_ARRAY,
+        15 => SectionType::SHT_FINI_ARRAY,
+        16 => SectionType::SHT_PREINIT_ARRAY,
+        17 => SectionType::SHT_GROUP,
+        18 => SectionType::SHT_SYMTAB_SHNDX,
+        19 => SectionType::SHT_RELR,
+        0x6FFFFFF5 => SectionType::SHT_GNU_ATTRIBUTES,
+        0x6FFFFFF6 => SectionType::SHT_GNU_HASH,
+        0x6FFFFFF7 => SectionType::SHT_GNU_LIBLIST,
+        0x6FFFFFFD => SectionType::SHT_GNU_verdef,
+        0x6FFFFFFE => SectionType::SHT_GNU_verneed,
+        0x6FFFFFFF => SectionType::SHT_GNU_versym,
+        SectionType::SHT_LOOS..=SectionType::SHT_HIOS => SectionType::OsSpecific(v),
+        SectionType::SHT_LOPROC..=SectionType::SHT_HIPROC => SectionType::ProcessorSpecific(v),
+        SectionType::SHT_LOUSER..=SectionType::SHT_HIUSER => SectionType::UserSpecific(v),
+        _ => SectionType::Unknown(v)
+    }
+}
+
+impl Class for Class64 {
+    type Header = ElfHeader64;
+    type ProgramHeader = ProgramHeader64;
+    type SectionHeader = SectionHeader64;
+    const BITS: BitType = BitType::_64;
+    const HEADER_SIZE: usize = ELF64_HEADER_SIZE;
+    const PROGRAM_HEADER_SIZE: usize = PROGRAM_HEADER64_SIZE;
+    const SECTION_HEADER_SIZE: usize = SECTION_HEADER64_SIZE;
+
+    // The class independent headers are the ELF64 ones
+    fn widen_header(header: &ElfHeader64) -> ElfHeader {
+        header.clone()
+    }
+
+    fn widen_program_header(header: &ProgramHeader64) -> ProgramHeader {
+        header.clone()
+    }
+
+    fn widen_section_header(header: &SectionHeader64) -> SectionHeader {
+        header.clone()
+    }
+
+    fn narrow_header(header: ElfHeader) -> ElfHeader64 {
+        header
+    }
+
+    // Reads one program header where the cursor is
+    fn parse_program_header(c: &mut Cursor) -> Result<ProgramHeader64, ParseError> {
+        let phtype = parse_program_header_type(c.read32()?);
+        let flags = parse_program_header_flags(c.read32()?);
+
+        Ok(ProgramHeader64 {
+            r#type: phtype,
+            flags,
+            offset: c.read64()?,
+            vaddr: c.read64()?,
+            paddr: c.read64()?,
+            filesz: c.read64()?,
+            memsz: c.read64()?,
+            align: c.read64()?
         })
     }
-    
-    // TODO: Error handling
-    pub fn parse(f: &[u8]) -> Result<Self, ParseError> {
-        let header = Self::parse_header(f).unwrap();
-        let endian = header.e_ident.e_endianness; // Get endianness
-        let mut phtable: Vec<ProgramHeader64> = Vec::with_capacity(header.e_phnum as usize); // To store program headers
-        
-        // Closures for reading values
-        let idx = Cell::new(header.e_phoff as usize);
-        let r16 = || -> u16 { let temp = idx.get(); let v = endianness::read16(&[f[temp], f[temp + 1]], endian); idx.set(temp + 2); v };
-        let r32 = || -> u32 { let temp = idx.get(); let v = endianness::read32(&[f[temp], f[temp + 1], f[temp + 2], f[temp + 3]], endian); idx.set(temp + 4); v };
-        let r64 = || -> u64 { let temp = idx.get(); let v = endianness::read64(&[f[temp],     f[temp + 1], f[temp + 2], f[temp + 3],
-                                                                                 f[temp + 4], f[temp + 5], f[temp + 6], f[temp + 7]], endian); idx.set(temp + 8); v };
-        // Read each program header
-        for _ in 0..header.e_phnum {
-            let v = r32();
-            println!("{:#02x}", v);
-            let phtype = match v {
-                0 => { ProgramHeaderType::PT_NULL },
-                1 => { ProgramHeaderType::PT_LOAD },
-                2 => { ProgramHeaderType::PT_DYNAMIC },
-                3 => { ProgramHeaderType::PT_INTERP },
-                4 => { ProgramHeaderType::PT_NOTE },
-                5 => { ProgramHeaderType::PT_SHLIB },
-                6 => { ProgramHeaderType::PT_PHDR },
-                7 => { ProgramHeaderType::PT_TLS },
-                0x6474E553 => { ProgramHeaderType::GNU_PROPERTY },
-                0x6474E550 => { ProgramHeaderType::GNU_EH_FRAME },
-                0x6474E551 => { ProgramHeaderType::GNU_STACK },
-                0x6474E552 => { ProgramHeaderType::GNU_RELRO },
-                _ => { return Err(ParseError::UnsupportedProgramHeaderType) }
-            };
-
-            let mut flags: Vec<ProgramHeaderFlag> = Vec::new();
-            {
-                let num = r32();
-                if num & 1 == 1 { flags.push(ProgramHeaderFlag::PF_X); }
-                if ((num >> 1) & 1) == 1 { flags.push(ProgramHeaderFlag::PF_W); }
-                if ((num >> 2) & 1) == 1 { flags.push(ProgramHeaderFlag::PF_R); }
-            }
-
-            phtable.push(ProgramHeader64 {
-                r#type: phtype,
-                flags,
-                offset: r64(),
-                vaddr: r64(),
-                paddr: r64(),
-                filesz: r64(),
-                memsz: r64(),
-                align: r64()
-            });
+
+    // Reads one section header where the cursor is
+    fn parse_section_header(c: &mut Cursor) -> Result<SectionHeader64, ParseError> {
+        Ok(SectionHeader64 {
+            sh_name: c.read32()?,
+            sh_type: parse_section_type(c.read32()?),
+            sh_flags: SectionFlags::from_bits(c.read64()?),
+            sh_addr: c.read64()?,
+            sh_offset: c.read64()?,
+            sh_size: c.read64()?,
+            sh_link: c.read32()?,
+            sh_info: c.read32()?,
+            sh_addralign: c.read64()?,
+            sh_entsize: c.read64()?
+        })
+    }
+}
+
+impl Class for Class32 {
+    type Header = ElfHeader32;
+    type ProgramHeader = ProgramHeader32;
+    type SectionHeader = SectionHeader32;
+    const BITS: BitType = BitType::_32;
+    const HEADER_SIZE: usize = ELF32_HEADER_SIZE;
+    const PROGRAM_HEADER_SIZE: usize = PROGRAM_HEADER32_SIZE;
+    const SECTION_HEADER_SIZE: usize = SECTION_HEADER32_SIZE;
+
+    fn widen_header(header: &ElfHeader32) -> ElfHeader {
+        header.into()
+    }
+
+    fn widen_program_header(header: &ProgramHeader32) -> ProgramHeader {
+        header.into()
+    }
+
+    fn widen_section_header(header: &SectionHeader32) -> SectionHeader {
+        header.into()
+    }
+
+    fn narrow_header(header: ElfHeader) -> ElfHeader32 {
+        ElfHeader32 {
+            e_ident: header.e_ident,
+            e_type: header.e_type,
+            e_machine: header.e_machine,
+            e_version: header.e_version,
+            e_entry: header.e_entry as u32,
+            e_phoff: header.e_phoff as u32,
+            e_shoff: header.e_shoff as u32,
+            e_flags: header.e_flags,
+            e_ehsize: header.e_ehsize,
+            e_phentsize: header.e_phentsize,
+            e_phnum: header.e_phnum,
+            e_shentsize: header.e_shentsize,
+            e_shnum: header.e_shnum,
+            e_shstrndx: header.e_shstrndx
         }
-        println!("{:#?}", phtable);
+    }
+
+    // Reads one program header where the cursor is
+    fn parse_program_header(c: &mut Cursor) -> Result<ProgramHeader32, ParseError> {
+        let phtype = parse_program_header_type(c.read32()?);
+
+        // Flags come after the sizes in 32 bits headers
+        let (offset, vaddr, paddr, filesz, memsz) = (c.read32()?, c.read32()?, c.read32()?, c.read32()?, c.read32()?);
+        let flags = parse_program_header_flags(c.read32()?);
 
-        todo!();
+        Ok(ProgramHeader32 {
+            r#type: phtype,
+            offset,
+            vaddr,
+            paddr,
+            filesz,
+            memsz,
+            flags,
+            align: c.read32()?
+        })
+    }
+
+    // Reads one section header where the cursor is
+    fn parse_section_header(c: &mut Cursor) -> Result<SectionHeader32, ParseError> {
+        Ok(SectionHeader32 {
+            sh_name: c.read32()?,
+            sh_type: parse_section_type(c.read32()?),
+            sh_flags: SectionFlags::from_bits(c.read32()? as u64),
+            sh_addr: c.read32()?,
+            sh_offset: c.read32()?,
+            sh_size: c.read32()?,
+            sh_link: c.read32()?,
+            sh_info: c.read32()?,
+            sh_addralign: c.read32()?,
+            sh_entsize: c.read32()?
+        })
     }
 }
 
-impl Elf32 {
-    pub fn parse_header(h: &[u8]) -> Result<ElfHeader32, ParseError> {
-        if h.len() < ELF32_HEADER_SIZE { return Err(ParseError::TooSmallHeader) } // Validate size
+// Number of entries in the section header table.
+// With extended numbering the real count lives in the first entry
+pub(crate) fn section_count<C: Class>(header: &C::Header, first: &C::SectionHeader) -> usize {
+    match C::widen_header(header).e_shnum {
+        0 => usize::try_from(C::widen_section_header(first).sh_size).unwrap_or(usize::MAX),
+        n => n as usize
+    }
+}
+
+impl<'a, C: Class> ElfN<'a, C> {
+    pub fn parse_header(h: &[u8]) -> Result<C::Header, ParseError> {
+        if h.len() < C::HEADER_SIZE { return Err(ParseError::TooSmallHeader { expected: C::HEADER_SIZE, actual: h.len() }) } // Validate size
         let ident = &h[..EI_NIDENT]; // Ident part
         let ident = parse_ident(ident)?; // Parse ident
         let endian = ident.e_endianness; // Get endian type
-        
-        let idx = Cell::new(EI_NIDENT);
-        let r16 = || -> u16 { let temp = idx.get(); let v = endianness::read16(&[h[temp], h[temp + 1]], endian); idx.set(temp + 2); v };
-        let r32 = || -> u32 { let temp = idx.get(); let v = endianness::read32(&[h[temp], h[temp + 1], h[temp + 2], h[temp + 3]], endian); idx.set(temp + 4); v };
-        let r64 = || -> u64 { let temp = idx.get(); let v = endianness::read64(&[h[temp],     h[temp + 1], h[temp + 2], h[temp + 3],
-                                                           h[temp + 4], h[temp + 5], h[temp + 6], h[temp + 7]], endian); idx.set(temp + 8); v };
-        
-        let filetype = match r16() {
-            0 => FileType::ET_NONE,
-            1 => FileType::ET_NONE,
-            2 => FileType::ET_EXEC,
-            3 => FileType::ET_DYN,
-            4 => FileType::ET_CORE,
-            _ => return Err(ParseError::UnsupportedFileType)
-        };
-
-        let machine = match r16() {
-            0 => MachineType::None,
-            2 => MachineType::SPARC,
-            3 => MachineType::Intel_80386,
-            4 => MachineType::Motorola_68000,
-            7 => MachineType::Intel_i860,
-            8 => MachineType::MIPS_I,
-            19 => MachineType::Intel_i960,
-            20 => MachineType::PowerPC,
-            40 => MachineType::ARM,
-            50 => MachineType::Intel_IA64,
-            62 => MachineType::x64,
-            243 => MachineType::RISC_V,
-            _ => return Err(ParseError::UnsupportedMachineType)
-        };
-
-        let version = match r32() {
-            0 => HeaderVersion::None,
-            1 => HeaderVersion::Current,
-            _ => return Err(ParseError::UnsupportedVersion)
-        };
-
-        Ok(ElfHeader32 {
+
+        let mut c = Cursor::new(h, EI_NIDENT, endian);
+        let filetype = parse_file_type(c.read16()?);
+
+        let machine = parse_machine_type(c.read16()?);
+
+        let version = parse_header_version(c.read32()?);
+
+        // Entry point and table offsets are words of the class
+        Ok(C::narrow_header(ElfHeader {
             e_ident: ident,
             e_type: filetype,
             e_machine: machine,
             e_version: version,
-            e_entry: r32(),
-            e_phoff: r32(),
-            e_shoff: r32(),
-            e_flags: r32(),
-            e_ehsize: r16(),
-            e_phentsize: r16(),
-            e_phnum: r16(),
-            e_shentsize: r16(),
-            e_shnum: r16(),
-            e_shstrndx: r16()
-        })
+            e_entry: c.read_word(C::BITS)?,
+            e_phoff: c.read_word(C::BITS)?,
+            e_shoff: c.read_word(C::BITS)?,
+            e_flags: c.read32()?,
+            e_ehsize: c.read16()?,
+            e_phentsize: c.read16()?,
+            e_phnum: c.read16()?,
+            e_shentsize: c.read16()?,
+            e_shnum: c.read16()?,
+            e_shstrndx: c.read16()?
+        }))
     }
-    
-    pub fn parse(f: &[u8]) -> Result<Self, ParseError> {
-        // Get all the program headers
-        let header = Self::parse_header(f).unwrap();
-        let endian = header.e_ident.e_endianness; // Get endianness
-        
-        {
-            let start_i = header.e_phoff as usize;
-            let phtsize = header.e_phentsize as usize * header.e_phnum as usize;
-            let end_i = start_i + phtsize;
-            // Loop through each program header
-            for i in (start_i..end_i).skip(phtsize) {
-                endianness::read16(&[f[i], f[i + 1]], endian);
-            }
+
+    pub fn parse_shtable(f: &[u8], header: &C::Header) -> Result<Vec<C::SectionHeader>, ParseError> {
+        let wide = C::widen_header(header);
+        if wide.e_shoff == 0 { return Ok(Vec::new()) } // No section header table
+        let endian = wide.e_ident.e_endianness; // Get endianness
+        let entsize = wide.e_shentsize as usize;
+        let shoff = wide.e_shoff;
+
+        // The first entry must be readable before anything else
+        if entsize < C::SECTION_HEADER_SIZE { return Err(ParseError::TooSmallSectionHeader { expected: C::SECTION_HEADER_SIZE, actual: entsize }) }
+        check_section_header_table(f.len() as u64, shoff, entsize, 1)?;
+        let entry = |i: usize| Cursor::entry(f, 0, shoff, i, entsize, endian)
+            .and_then(|mut c| C::parse_section_header(&mut c))
+            .map_err(section_header_error(i));
+        let first = entry(0)?;
+
+        let shnum = section_count::<C>(header, &first);
+        if shnum == 0 { return Ok(Vec::new()) }
+        check_section_header_table(f.len() as u64, shoff, entsize, shnum)?;
+
+        // Read each section header
+        let mut shtable: Vec<C::SectionHeader> = Vec::with_capacity(shnum);
+        shtable.push(first);
+        for i in 1..shnum {
+            shtable.push(entry(i)?);
+        }
+
+        Ok(shtable)
+    }
+
+    pub fn parse_phtable(f: &[u8], header: &C::Header) -> Result<Vec<C::ProgramHeader>, ParseError> {
+        let wide = C::widen_header(header);
+        let endian = wide.e_ident.e_endianness; // Get endianness
+        let entsize = wide.e_phentsize as usize;
+        let phoff = wide.e_phoff;
+        let phnum = wide.e_phnum as usize;
+        let mut phtable: Vec<C::ProgramHeader> = Vec::with_capacity(phnum); // To store program headers
+
+        // Make sure the program header table fits in the file
+        if phnum > 0 {
+            if entsize < C::PROGRAM_HEADER_SIZE { return Err(ParseError::TooSmallProgramHeader { expected: C::PROGRAM_HEADER_SIZE, actual: entsize }) }
+            check_program_header_table(f.len() as u64, phoff, entsize, phnum)?;
+        }
+
+        // Read each program header
+        for i in 0..phnum {
+            phtable.push(Cursor::entry(f, 0, phoff, i, entsize, endian)
+                .and_then(|mut c| C::parse_program_header(&mut c))
+                .map_err(program_header_error(i))?);
         }
 
-        todo!();
+        Ok(phtable)
+    }
+
+    pub fn parse(f: &'a [u8]) -> Result<Self, ParseError> {
+        let header = Self::parse_header(f)?;
+        let phtable = Self::parse_phtable(f, &header)?;
+        let shtable = Self::parse_shtable(f, &header)?;
+        Ok(Self::new(header, phtable, f, shtable))
+    }
+}
+
+impl<'a> Elf<'a> {
+    // Parses either class of file depending on the ident
+    pub fn parse(f: &'a [u8]) -> Result<Self, ParseError> {
+        match parse_ident(f)?.e_bits {
+            BitType::_32 => Ok(Elf::Elf32(Elf32::parse(f)?)),
+            BitType::_64 => Ok(Elf::Elf64(Elf64::parse(f)?))
+        }
     }
 }
 
@@ -293,7 +473,7 @@ mod tests {
         // Create dummy file and compile it
         // to compare header
         if !Path::new("temp").exists() { fs::create_dir("temp").unwrap(); }
-        if let Err(_) = fs::File::open("temp/main32.c") {
+        if fs::File::open("temp/main32.c").is_err() {
             let mut f = fs::File::create("temp/main32.c").unwrap();
             f.write_all(b"int main(int argc, char** argv) { return 0; }").unwrap();
         }
@@ -365,4 +545,160 @@ mod tests {
             e_shstrndx: 27
         }); 
     }
+
+    #[test]
+    fn parse64() {
+        use super::*;
+        use std::fs;
+
+        let ls_content = fs::read("/bin/ls").unwrap();
+        let elf = Elf64::parse(&ls_content).unwrap();
+        assert_eq!(elf.phtable().len(), elf.header().e_phnum as usize);
+        assert!(elf.phtable().iter().any(|ph| ph.r#type == ProgramHeaderType::PT_LOAD));
+
+        // Cutting the file in the middle of the program headers must not panic
+        let truncated = &ls_content[..ELF64_HEADER_SIZE + 10];
+        assert_eq!(Elf64::parse(truncated), Err(ParseError::TooSmallProgramHeaderTable { index: 0, offset: 64, expected: 56, actual: 10 }));
+        assert_eq!(Elf64::parse(truncated).unwrap_err().to_string(), "program header #0 at offset 0x40 needs 56 bytes, 10 left in the file");
+    }
+
+    #[test]
+    fn parse_shtable64() {
+        use super::*;
+        use crate::elf_file::ElfFile;
+        use std::fs;
+
+        let original = fs::read("/bin/ls").unwrap();
+        let elf = Elf64::parse(&original).unwrap();
+        let shnum = elf.header().e_shnum;
+        let shstrndx = elf.header().e_shstrndx;
+        assert_eq!(elf.shtable().len(), shnum as usize);
+        assert_eq!(elf.shstrndx(), Some(shstrndx as usize));
+
+        // Move the counts into the first section header like extended numbering does
+        let shoff = elf.header().e_shoff as usize;
+        let mut content = original.clone();
+        content[60..62].copy_from_slice(&0u16.to_le_bytes());
+        content[62..64].copy_from_slice(&SHN_XINDEX.to_le_bytes());
+        content[shoff + 32..shoff + 40].copy_from_slice(&(shnum as u64).to_le_bytes());
+        content[shoff + 40..shoff + 44].copy_from_slice(&(shstrndx as u32).to_le_bytes());
+        let extended = Elf64::parse(&content).unwrap();
+        assert_eq!(extended.shtable().len(), shnum as usize);
+        assert_eq!(extended.shstrndx(), Some(shstrndx as usize));
+        assert_eq!(extended.shtable()[1..], elf.shtable()[1..]);
+    }
+
+    #[test]
+    fn parse_section_types() {
+        use super::*;
+
+        assert_eq!(parse_section_type(6), SectionType::SHT_DYNAMIC);
+        assert_eq!(parse_section_type(0x6FFFFFF6), SectionType::SHT_GNU_HASH);
+        assert_eq!(parse_section_type(0x6FFF4C00), SectionType::OsSpecific(0x6FFF4C00));
+        assert_eq!(parse_section_type(0x70000001), SectionType::ProcessorSpecific(0x70000001));
+        assert_eq!(parse_section_type(42), SectionType::Unknown(42));
+        assert_eq!(parse_section_type(42).value(), 42);
+
+        // Unknown bits must survive
+        let flags = SectionFlags::from_bits(0x1000_0006);
+        assert!(flags.contains(SectionFlags::ALLOC | SectionFlags::EXECINSTR));
+        assert!(!flags.contains(SectionFlags::WRITE));
+        assert_eq!(flags.bits(), 0x1000_0006);
+        assert_eq!(format!("{:?}", flags), "ALLOC | EXECINSTR | 0x10000000");
+    }
+
+    #[test]
+    fn unknown_values() {
+        use super::*;
+        use std::fs;
+
+        assert_eq!(parse_file_type(0xFE01), FileType::OsSpecific(0xFE01));
+        assert_eq!(parse_file_type(0xFF10), FileType::ProcessorSpecific(0xFF10));
+        assert_eq!(parse_file_type(9).value(), 9);
+        assert_eq!(parse_machine_type(0x1234), MachineType::Unknown(0x1234));
+        assert_eq!(parse_header_version(2), HeaderVersion::Unknown(2));
+        assert_eq!(parse_program_header_type(0x6474E554), ProgramHeaderType::GNU_SFRAME);
+        assert_eq!(parse_program_header_type(0x65A3DBE6), ProgramHeaderType::OPENBSD_RANDOMIZE);
+        assert_eq!(parse_program_header_type(ProgramHeaderType::PT_ARM_EXIDX), ProgramHeaderType::ProcessorSpecific(0x70000001));
+        assert!(parse_program_header_type(0x6474E600).is_gnu_mbind());
+        assert_eq!(parse_program_header_type(0x6474E600), ProgramHeaderType::OsSpecific(0x6474E600));
+        assert_eq!(parse_program_header_type(8), ProgramHeaderType::Unknown(8));
+
+        // A file full of values nobody knows about still parses
+        let mut content = fs::read("testdata/hello").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let phoff = elf.header().e_phoff as usize;
+        content[7] = 0x42;
+        content[16..18].copy_from_slice(&0xFE10u16.to_le_bytes());
+        content[18..20].copy_from_slice(&0x1234u16.to_le_bytes());
+        content[phoff..phoff + 4].copy_from_slice(&ProgramHeaderType::PT_RISCV_ATTRIBUTES.to_le_bytes());
+        let elf = Elf64::parse(&content).unwrap();
+        assert_eq!(elf.header().e_ident.e_abi, ABI::Unknown(0x42));
+        assert_eq!(elf.header().e_type, FileType::OsSpecific(0xFE10));
+        assert_eq!(elf.header().e_machine, MachineType::Unknown(0x1234));
+        assert_eq!(elf.phtable()[0].r#type.value(), ProgramHeaderType::PT_RISCV_ATTRIBUTES);
+    }
+
+    #[test]
+    fn machine_types() {
+        use super::*;
+
+        // Every value maps back to itself
+        for v in 0..=u16::MAX {
+            assert_eq!(parse_machine_type(v).value(), v);
+        }
+        assert_eq!(parse_machine_type(183), MachineType::AArch64);
+        assert_eq!(parse_machine_type(258), MachineType::LoongArch);
+        assert_eq!(parse_machine_type(247), MachineType::BPF);
+        assert_eq!(parse_machine_type(22).to_string(), "IBM S/390");
+        assert_eq!(MachineType::x64.to_string(), "Advanced Micro Devices X86-64");
+        assert_eq!(MachineType::Unknown(0x1234).to_string(), "<unknown>: 0x1234");
+        assert_eq!(MachineType::Unknown(0x1234).name(), None);
+
+        assert_eq!(MachineType::AArch64.pointer_width(), Some(64));
+        assert_eq!(MachineType::Intel_80386.pointer_width(), Some(32));
+        assert_eq!(MachineType::AVR.pointer_width(), Some(16));
+        assert_eq!(MachineType::RISC_V.pointer_width(), None);
+        assert_eq!(MachineType::IBM_S390.default_endianness(), Some(Endianness::BigEndian));
+        assert_eq!(MachineType::LoongArch.default_endianness(), Some(Endianness::LittleEndian));
+        assert_eq!(MachineType::MIPS_I.default_endianness(), None);
+    }
+
+    #[test]
+    fn section_names() {
+        use super::*;
+        use crate::elf_file::ElfFile;
+        use std::fs;
+
+        let ls_content = fs::read("/bin/ls").unwrap();
+        let elf = Elf64::parse(&ls_content).unwrap();
+        let names: Vec<&str> = elf.sections().map(|s| s.unwrap().0).collect();
+        assert_eq!(names.len(), elf.shtable().len());
+        assert_eq!(names[0], "");
+        assert!(names.contains(&".shstrtab"));
+
+        let text = elf.section_by_name(".text").unwrap();
+        assert_eq!(text.sh_type, SectionType::SHT_PROGBITS);
+        assert!(text.sh_flags.contains(SectionFlags::ALLOC | SectionFlags::EXECINSTR));
+        assert_eq!(elf.section_data(&text).unwrap().len(), text.sh_size as usize);
+        assert!(elf.section_by_name(".does_not_exist").is_none());
+    }
+
+    #[test]
+    fn borrowed_data() {
+        use super::*;
+        use crate::elf_file::ElfFile;
+        use std::fs;
+
+        let ls_content = fs::read("/bin/ls").unwrap();
+        let elf = Elf64::parse(&ls_content).unwrap();
+        assert_eq!(elf.data().as_ptr(), ls_content.as_ptr());
+
+        // Sections and segments point into the input buffer
+        let text = elf.section_by_name(".text").unwrap();
+        let text_data = elf.section_data(&text).unwrap();
+        assert_eq!(text_data.as_ptr(), ls_content[text.sh_offset as usize..].as_ptr());
+        let load = elf.phtable().iter().find(|ph| ph.r#type == ProgramHeaderType::PT_LOAD).unwrap();
+        assert_eq!(elf.segment_data(load).unwrap().len(), load.filesz as usize);
+    }
 }
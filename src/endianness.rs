@@ -7,7 +7,7 @@ pub enum Endianness {
 
 #[allow(non_camel_case_types)]
 pub fn read16(b: &[u8; 2], endian: Endianness) -> u16 {
-    return match endian {
+    match endian {
         Endianness::LittleEndian => (b[1] as u16) << 8 | b[0] as u16,
         Endianness::BigEndian => (b[0] as u16) << 8 | b[1] as u16
     }
@@ -15,7 +15,7 @@ pub fn read16(b: &[u8; 2], endian: Endianness) -> u16 {
 
 #[allow(non_camel_case_types)]
 pub fn read32(b: &[u8; 4], endian: Endianness) -> u32 {
-    return match endian {
+    match endian {
         Endianness::LittleEndian => {
             (b[3] as u32) << 24 |
             (b[2] as u32) << 16 |
@@ -33,7 +33,7 @@ pub fn read32(b: &[u8; 4], endian: Endianness) -> u32 {
 
 #[allow(non_camel_case_types)]
 pub fn read64(b: &[u8; 8], endian: Endianness) -> u64 {
-    return match endian {
+    match endian {
         Endianness::LittleEndian => {
             (b[7] as u64) << 56 |
             (b[6] as u64) << 48 |
@@ -0,0 +1,421 @@
+use crate::cursor::Cursor;
+use crate::dynamic::DynamicTag;
+use crate::elf::*;
+use crate::elf_file::ElfFile;
+use crate::endianness::Endianness;
+use crate::parse_error::ParseError;
+use crate::symbol::{Symbol, SymbolSection, SymbolTable, SymbolType};
+use crate::version::{SymbolVersions, VERSYM_HIDDEN};
+
+// Hash function of DT_GNU_HASH tables
+pub fn gnu_hash(name: &[u8]) -> u32 {
+    name.iter().fold(5381u32, |h, &c| h.wrapping_mul(33).wrapping_add(c as u32))
+}
+
+// Hash function of DT_HASH tables
+pub fn sysv_hash(name: &[u8]) -> u32 {
+    name.iter().fold(0u32, |h, &c| {
+        let h = (h << 4).wrapping_add(c as u32);
+        let g = h & 0xF0000000;
+        (h ^ (g >> 24)) & !g
+    })
+}
+
+// Word `index` of a part of the table found at `base` in the file
+fn word(data: &[u8], base: u64, index: usize, endian: Endianness) -> Result<u32, ParseError> {
+    Cursor::entry(data, base, 0, index, 4, endian)?.read32()
+}
+
+// Error for the word at `offset` in a table found at `base` in the file
+fn invalid(base: u64, offset: usize) -> ParseError {
+    ParseError::InvalidHashTable { offset: base.saturating_add(offset as u64) }
+}
+
+// Symbols the dynamic loader considers when resolving a name
+fn is_candidate(symbol: &Symbol) -> bool {
+    if symbol.section == SymbolSection::SHN_UNDEF { return false }
+    if symbol.value == 0 && symbol.r#type != SymbolType::STT_TLS { return false }
+    matches!(symbol.r#type, SymbolType::STT_NOTYPE | SymbolType::STT_OBJECT | SymbolType::STT_FUNC |
+                            SymbolType::STT_COMMON | SymbolType::STT_TLS | SymbolType::STT_GNU_IFUNC)
+}
+
+// Hidden versions are only bound by an explicit version, the loader skips
+// them when resolving a bare name
+fn is_visible(index: usize, versions: Option<&SymbolVersions>) -> Result<bool, ParseError> {
+    match versions {
+        Some(versions) => Ok(versions.versym(index)? & VERSYM_HIDDEN == 0),
+        None => Ok(true)
+    }
+}
+
+// DT_GNU_HASH table: nbuckets, symoffset, bloom_size and bloom_shift words,
+// the bloom filter made of class sized words, the buckets and one hash per
+// symbol from symoffset, the lowest bit marking the end of a chain
+#[derive(Debug, Clone)]
+pub struct GnuHashTable<'a> {
+    offset: u64, // Where the table is in the file
+    symoffset: usize,
+    bloom_shift: u32,
+    bloom: &'a [u8],
+    buckets: &'a [u8],
+    chains: &'a [u8],
+    bits: BitType,
+    endian: Endianness
+}
+
+impl<'a> GnuHashTable<'a> {
+    // `data` may extend past the table, `symbol_count` is the number of
+    // dynamic symbols the table covers
+    pub fn parse(data: &'a [u8], offset: u64, symbol_count: usize, bits: BitType, endian: Endianness) -> Result<Self, ParseError> {
+        let nbuckets = word(data, offset, 0, endian)? as usize;
+        let symoffset = word(data, offset, 1, endian)? as usize;
+        let bloom_size = word(data, offset, 2, endian)? as usize;
+        let bloom_shift = word(data, offset, 3, endian)?;
+        let bloom_word = match bits {
+            BitType::_32 => 4,
+            BitType::_64 => 8
+        };
+
+        // The loader masks with bloom_size - 1 and reduces modulo nbuckets,
+        // the second bloom hash shifts a 32 bits hash
+        if nbuckets == 0 { return Err(invalid(offset, 0)) }
+        if symoffset > symbol_count { return Err(invalid(offset, 4)) }
+        if !bloom_size.is_power_of_two() { return Err(invalid(offset, 8)) }
+        if bloom_shift >= 32 { return Err(invalid(offset, 12)) }
+        let slice = |start: usize, len: Option<usize>| -> Result<&'a [u8], ParseError> {
+            let end = len.and_then(|len| start.checked_add(len)).ok_or(invalid(offset, start))?;
+            data.get(start..end).ok_or(invalid(offset, start))
+        };
+        let bloom = slice(16, bloom_size.checked_mul(bloom_word))?;
+        let buckets = slice(16 + bloom.len(), nbuckets.checked_mul(4))?;
+        let chains = slice(16 + bloom.len() + buckets.len(), (symbol_count - symoffset).checked_mul(4))?;
+
+        let table = Self { offset, symoffset, bloom_shift, bloom, buckets, chains, bits, endian };
+
+        // Buckets point inside the hashed symbols and every chain they start ends
+        for bucket in 0..nbuckets {
+            let start = table.bucket(bucket)? as usize;
+            if start == 0 { continue }
+            if start < symoffset || start >= symbol_count {
+                return Err(invalid(table.offset, table.buckets_offset() + bucket * 4));
+            }
+            let mut i = start;
+            while table.chain(i)? & 1 == 0 {
+                i += 1;
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn symbol_offset(&self) -> usize {
+        self.symoffset
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len() / 4
+    }
+
+    fn bloom_word(&self, index: usize) -> Result<u64, ParseError> {
+        let size = match self.bits {
+            BitType::_32 => 4,
+            BitType::_64 => 8
+        };
+        Cursor::entry(self.bloom, self.offset + 16, 0, index, size, self.endian)?.read_word(self.bits)
+    }
+
+    // Offsets of the buckets and chains in the table
+    fn buckets_offset(&self) -> usize {
+        16 + self.bloom.len()
+    }
+
+    fn chains_offset(&self) -> usize {
+        self.buckets_offset() + self.buckets.len()
+    }
+
+    fn bucket(&self, index: usize) -> Result<u32, ParseError> {
+        word(self.buckets, self.offset + self.buckets_offset() as u64, index, self.endian)
+    }
+
+    // Hash stored for the symbol at `index`
+    fn chain(&self, index: usize) -> Result<u32, ParseError> {
+        word(self.chains, self.offset + self.chains_offset() as u64, index - self.symoffset, self.endian)
+    }
+
+    // Whether the bloom filter lets `hash` through
+    pub fn may_contain(&self, hash: u32) -> Result<bool, ParseError> {
+        let bits = match self.bits {
+            BitType::_32 => 32,
+            BitType::_64 => 64
+        };
+        let bloom_size = self.bloom.len() / (bits / 8) as usize;
+        let word = self.bloom_word((hash / bits) as usize & (bloom_size - 1))?;
+        let mask = 1u64 << (hash % bits) | 1u64 << ((hash >> self.bloom_shift) % bits);
+        Ok(word & mask == mask)
+    }
+
+    pub fn lookup(&self, name: &str, symbols: &SymbolTable<'a>, versions: Option<&SymbolVersions>) -> Result<Option<(usize, Symbol<'a>)>, ParseError> {
+        let hash = gnu_hash(name.as_bytes());
+        if !self.may_contain(hash)? { return Ok(None) }
+
+        let mut i = self.bucket(hash as usize % self.bucket_count())? as usize;
+        if i == 0 { return Ok(None) }
+        loop {
+            let chain = self.chain(i)?;
+            if chain | 1 == hash | 1 {
+                let symbol = symbols.get(i)?;
+                if symbol.name == name && is_candidate(&symbol) && is_visible(i, versions)? { return Ok(Some((i, symbol))) }
+            }
+            if chain & 1 != 0 { return Ok(None) }
+            i += 1;
+        }
+    }
+
+    // Checks every hashed symbol is in the right bucket with the right
+    // hash and is let through by the bloom filter
+    pub fn verify(&self, symbols: &SymbolTable<'a>) -> Result<(), ParseError> {
+        for i in self.symoffset..symbols.len() {
+            let hash = gnu_hash(symbols.get(i)?.name.as_bytes());
+            let invalid = invalid(self.offset, self.chains_offset() + (i - self.symoffset) * 4);
+            if self.chain(i)? | 1 != hash | 1 || !self.may_contain(hash)? { return Err(invalid) }
+            let start = self.bucket(hash as usize % self.bucket_count())? as usize;
+            if start == 0 || start > i { return Err(invalid) }
+            if (start..i).any(|j| self.chain(j).is_ok_and(|chain| chain & 1 != 0)) { return Err(invalid) }
+        }
+        Ok(())
+    }
+}
+
+// DT_HASH table: nbucket and nchain words followed by the buckets and one
+// chain entry per symbol, chains end on STN_UNDEF
+#[derive(Debug, Clone)]
+pub struct SysvHashTable<'a> {
+    offset: u64,
+    buckets: &'a [u8],
+    chains: &'a [u8],
+    endian: Endianness
+}
+
+impl<'a> SysvHashTable<'a> {
+    pub fn parse(data: &'a [u8], offset: u64, symbol_count: usize, endian: Endianness) -> Result<Self, ParseError> {
+        let nbucket = word(data, offset, 0, endian)? as usize;
+        let nchain = word(data, offset, 1, endian)? as usize;
+        // nchain is the number of symbols
+        if nbucket == 0 { return Err(invalid(offset, 0)) }
+        if nchain != symbol_count { return Err(invalid(offset, 4)) }
+
+        let buckets_end = nbucket.checked_mul(4).and_then(|len| len.checked_add(8)).ok_or(invalid(offset, 8))?;
+        let chains_end = nchain.checked_mul(4).and_then(|len| len.checked_add(buckets_end))
+            .ok_or(invalid(offset, buckets_end))?;
+        let table = Self {
+            buckets: data.get(8..buckets_end).ok_or(invalid(offset, 8))?,
+            chains: data.get(buckets_end..chains_end).ok_or(invalid(offset, buckets_end))?,
+            offset,
+            endian
+        };
+
+        // Every link stays inside the symbol table
+        for i in 0..nbucket {
+            if table.bucket(i)? as usize >= nchain { return Err(invalid(offset, 8 + i * 4)) }
+        }
+        for i in 0..nchain {
+            if table.chain(i)? as usize >= nchain { return Err(invalid(table.offset, table.chains_offset() + i * 4)) }
+        }
+        Ok(table)
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len() / 4
+    }
+
+    // Offset of the chains in the table, the buckets start at 8
+    fn chains_offset(&self) -> usize {
+        8 + self.buckets.len()
+    }
+
+    fn bucket(&self, index: usize) -> Result<u32, ParseError> {
+        word(self.buckets, self.offset + 8, index, self.endian)
+    }
+
+    fn chain(&self, index: usize) -> Result<u32, ParseError> {
+        word(self.chains, self.offset + self.chains_offset() as u64, index, self.endian)
+    }
+
+    pub fn lookup(&self, name: &str, symbols: &SymbolTable<'a>, versions: Option<&SymbolVersions>) -> Result<Option<(usize, Symbol<'a>)>, ParseError> {
+        let hash = sysv_hash(name.as_bytes());
+        let mut i = self.bucket(hash as usize % self.bucket_count())? as usize;
+        // A chain visits each symbol at most once, more steps mean a cycle
+        for _ in 0..=self.chains.len() / 4 {
+            if i == 0 { return Ok(None) }
+            let symbol = symbols.get(i)?;
+            if symbol.name == name && is_candidate(&symbol) && is_visible(i, versions)? { return Ok(Some((i, symbol))) }
+            i = self.chain(i)? as usize;
+        }
+        Err(invalid(self.offset, self.chains_offset() + i * 4))
+    }
+
+    // Checks every symbol is reachable from the bucket of its hash
+    pub fn verify(&self, symbols: &SymbolTable<'a>) -> Result<(), ParseError> {
+        let nchain = self.chains.len() / 4;
+        let mut seen = vec![false; nchain];
+        for bucket in 0..self.bucket_count() {
+            let mut i = self.bucket(bucket)? as usize;
+            while i != 0 {
+                let invalid = invalid(self.offset, self.chains_offset() + i * 4);
+                if seen[i] { return Err(invalid) }
+                seen[i] = true;
+                if sysv_hash(symbols.get(i)?.name.as_bytes()) as usize % self.bucket_count() != bucket { return Err(invalid) }
+                i = self.chain(i)? as usize;
+            }
+        }
+        // Symbols no bucket leads to
+        match seen.iter().skip(1).position(|&s| !s) {
+            Some(i) => Err(invalid(self.offset, self.chains_offset() + (i + 1) * 4)),
+            None => Ok(())
+        }
+    }
+}
+
+// Content and file offset of the hash table given by `tag`, from its
+// dynamic entry or else from the section of type `section_type`
+pub(crate) fn hash_table_data<'a, E: ElfFile<'a> + ?Sized>(elf: &E, tag: DynamicTag, section_type: SectionType)
+    -> Result<Option<(&'a [u8], u64)>, ParseError>
+{
+    if let Some(addr) = elf.dynamic()?.and_then(|dynamic| dynamic.get(tag)) {
+        let offset = elf.vaddr_to_offset(addr)?;
+        let start = usize::try_from(offset).map_err(|_| ParseError::UnmappedAddress(addr))?;
+        return elf.data().get(start..).map(|data| Some((data, offset))).ok_or(ParseError::UnmappedAddress(addr));
+    }
+    match elf.section_index_by_type(section_type).and_then(|i| elf.section_header(i)) {
+        Some(section) => Ok(Some((elf.section_data(&section)?, section.sh_offset))),
+        None => Ok(None)
+    }
+}
+
+// Number of dynamic symbols, which without section headers only the hash
+// tables give: nchain of DT_HASH, or the end of the last DT_GNU_HASH chain
+pub(crate) fn dynamic_symbol_count<'a, E: ElfFile<'a> + ?Sized>(elf: &E) -> Result<Option<usize>, ParseError> {
+    let endian = elf.endianness();
+    if let Some((data, offset)) = hash_table_data(elf, DynamicTag::DT_HASH, SectionType::SHT_HASH)? {
+        return Ok(Some(word(data, offset, 1, endian)? as usize));
+    }
+    let Some((data, offset)) = hash_table_data(elf, DynamicTag::DT_GNU_HASH, SectionType::SHT_GNU_HASH)? else { return Ok(None) };
+    let nbuckets = word(data, offset, 0, endian)? as usize;
+    let symoffset = word(data, offset, 1, endian)? as usize;
+    let bloom_word = match elf.bits() {
+        BitType::_32 => 4,
+        BitType::_64 => 8
+    };
+    let buckets_offset = (word(data, offset, 2, endian)? as usize).saturating_mul(bloom_word).saturating_add(16);
+    let buckets = data.get(buckets_offset..).ok_or(invalid(offset, buckets_offset))?;
+
+    // Chains are laid out in bucket order, the last one starts at the highest bucket
+    let mut last = 0;
+    for bucket in 0..nbuckets {
+        last = last.max(word(buckets, offset.saturating_add(buckets_offset as u64), bucket, endian)? as usize);
+    }
+    if last < symoffset { return Ok(Some(symoffset)) }
+    let chains_offset = buckets_offset.saturating_add(nbuckets.saturating_mul(4));
+    let chains = data.get(chains_offset..).ok_or(invalid(offset, chains_offset))?;
+    while word(chains, offset.saturating_add(chains_offset as u64), last - symoffset, endian)? & 1 == 0 {
+        last += 1;
+    }
+    Ok(Some(last + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn hashes() {
+        use super::*;
+
+        assert_eq!(gnu_hash(b""), 0x00001505);
+        assert_eq!(gnu_hash(b"printf"), 0x156B2BB8);
+        assert_eq!(gnu_hash(b"exit"), 0x7C967E3F);
+        assert_eq!(sysv_hash(b""), 0);
+        assert_eq!(sysv_hash(b"printf"), 0x077905A6);
+        assert_eq!(sysv_hash(b"exit"), 0x0006CF04);
+    }
+
+    #[test]
+    fn lookup() {
+        use super::*;
+        use std::fs;
+
+        // Both tables side by side
+        let content = fs::read("testdata/libhash.so.1").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let symbols = elf.dynamic_symbol_table().unwrap().unwrap();
+
+        let gnu = elf.gnu_hash_table().unwrap().unwrap();
+        let sysv = elf.sysv_hash_table().unwrap().unwrap();
+        gnu.verify(&symbols).unwrap();
+        sysv.verify(&symbols).unwrap();
+
+        for i in [0, 57, 199] {
+            let name = format!("export_{}", i);
+            let (index, symbol) = gnu.lookup(&name, &symbols, None).unwrap().unwrap();
+            assert_eq!(symbol.name, name);
+            assert_eq!(sysv.lookup(&name, &symbols, None).unwrap().unwrap().0, index);
+            assert_eq!(elf.lookup_dynamic_symbol(&name).unwrap(), Some(symbol));
+        }
+        assert_eq!(gnu.lookup("export_200", &symbols, None).unwrap(), None);
+        assert_eq!(sysv.lookup("export_200", &symbols, None).unwrap(), None);
+        assert_eq!(elf.lookup_dynamic_symbol("missing").unwrap(), None);
+
+        // A bucket pointing before the hashed symbols is rejected
+        let section = elf.section_by_name(".gnu.hash").unwrap();
+        let data = elf.section_data(&section).unwrap();
+        let mut broken = data.to_vec();
+        let bucket = 16 + 8 * u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+        broken[bucket..bucket + 4].copy_from_slice(&1u32.to_le_bytes());
+        assert_eq!(GnuHashTable::parse(&broken, section.sh_offset, symbols.len(), BitType::_64, Endianness::LittleEndian).err(),
+                   Some(ParseError::InvalidHashTable { offset: section.sh_offset + bucket as u64 }));
+    }
+
+    #[test]
+    fn versioned_lookup() {
+        use super::*;
+        use std::fs;
+
+        // foo@VERS_1 is hidden, only foo@@VERS_2 answers to the bare name
+        let content = fs::read("testdata/libversions.so.1").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let symbols = elf.dynamic_symbol_table().unwrap().unwrap();
+        let versions = elf.symbol_versions().unwrap().unwrap();
+        assert_eq!(symbols.iter().map(Result::unwrap).filter(|s| s.name == "foo").count(), 2);
+
+        let table = elf.gnu_hash_table().unwrap().unwrap();
+        let (index, symbol) = table.lookup("foo", &symbols, Some(&versions)).unwrap().unwrap();
+        let version = versions.version_of(index).unwrap().unwrap();
+        assert_eq!((version.name, version.hidden), ("VERS_2", false));
+        assert_eq!(elf.lookup_dynamic_symbol("foo").unwrap(), Some(symbol));
+
+        // Without the versions the hidden one, first in the chain, is taken
+        let (index, _) = table.lookup("foo", &symbols, None).unwrap().unwrap();
+        assert!(versions.version_of(index).unwrap().unwrap().hidden);
+    }
+
+    #[test]
+    fn without_sections() {
+        use super::*;
+        use std::fs;
+
+        // Once the section headers are gone the dynamic symbols are found
+        // through DT_SYMTAB, and counted with whichever hash table is there
+        for path in ["testdata/libhash-gnu.so.1", "testdata/libhash-sysv.so.1"] {
+            let content = fs::read(path).unwrap();
+            let elf = Elf64::parse(&content).unwrap();
+            let expected: Vec<_> = elf.dynamic_symbols().map(Result::unwrap).collect();
+
+            let mut stripped = content.clone();
+            stripped[0x28..0x30].fill(0); // e_shoff
+            stripped[0x3C..0x40].fill(0); // e_shnum, e_shstrndx
+            let elf = Elf64::parse(&stripped).unwrap();
+            assert_eq!(elf.section_count(), 0);
+            assert_eq!(elf.dynamic_symbols().map(Result::unwrap).collect::<Vec<_>>(), expected);
+            let symbol = elf.lookup_dynamic_symbol("export_42").unwrap().unwrap();
+            assert_eq!(symbol.name, "export_42");
+            assert_eq!(elf.lookup_dynamic_symbol("missing").unwrap(), None);
+        }
+    }
+}
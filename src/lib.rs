@@ -1,4 +1,21 @@
 pub mod parse_error;
 pub mod elf;
 pub mod elf_parser;
+pub mod elf_file;
 pub mod endianness;
+pub mod cursor;
+pub mod mapped;
+pub mod reader;
+pub mod debug_link;
+pub mod dynamic;
+pub mod hash;
+pub mod machine_flags;
+pub mod note;
+pub mod relocation;
+pub mod segment_map;
+pub mod string_table;
+pub mod symbol;
+pub mod version;
+
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,415 @@
+use crate::elf::*;
+use std::fmt;
+
+// ARM
+pub const EF_ARM_EABIMASK: u32 = 0xFF000000;
+pub const EF_ARM_BE8: u32 = 0x00800000;
+pub const EF_ARM_LE8: u32 = 0x00400000;
+pub const EF_ARM_ABI_FLOAT_SOFT: u32 = 0x200; // EF_ARM_SOFT_FLOAT before EABI version 5
+pub const EF_ARM_ABI_FLOAT_HARD: u32 = 0x400; // EF_ARM_VFP_FLOAT before EABI version 5
+// RISC-V
+pub const EF_RISCV_RVC: u32 = 0x1;
+pub const EF_RISCV_FLOAT_ABI: u32 = 0x6;
+pub const EF_RISCV_RVE: u32 = 0x8;
+pub const EF_RISCV_TSO: u32 = 0x10;
+// MIPS
+pub const EF_MIPS_NOREORDER: u32 = 0x1;
+pub const EF_MIPS_PIC: u32 = 0x2;
+pub const EF_MIPS_CPIC: u32 = 0x4;
+pub const EF_MIPS_ABI2: u32 = 0x20;
+pub const EF_MIPS_FP64: u32 = 0x200;
+pub const EF_MIPS_NAN2008: u32 = 0x400;
+pub const EF_MIPS_ABI: u32 = 0xF000;
+pub const EF_MIPS_MICROMIPS: u32 = 0x02000000;
+pub const EF_MIPS_ARCH_ASE_M16: u32 = 0x04000000;
+pub const EF_MIPS_ARCH: u32 = 0xF0000000;
+// PowerPC64
+pub const EF_PPC64_ABI: u32 = 0x3;
+// AVR
+pub const EF_AVR_MACH: u32 = 0x7F;
+pub const EF_AVR_LINKRELAX_PREPARED: u32 = 0x80;
+// LoongArch
+pub const EF_LARCH_ABI_MODIFIER_MASK: u32 = 0x7;
+pub const EF_LARCH_OBJABI_MASK: u32 = 0xC0;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum FloatAbi {
+    Soft,
+    Single,
+    Double,
+    Quad,
+    Hard // ARM, floating point arguments in VFP registers
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ArmFlags {
+    pub eabi_version: u8, // 0 for the old GNU ABI
+    pub float_abi: Option<FloatAbi>,
+    pub be8: bool,
+    pub le8: bool
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct RiscVFlags {
+    pub rvc: bool,
+    pub float_abi: FloatAbi,
+    pub rve: bool,
+    pub tso: bool
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum MipsArch {
+    MIPS_1,
+    MIPS_2,
+    MIPS_3,
+    MIPS_4,
+    MIPS_5,
+    MIPS_32,
+    MIPS_64,
+    MIPS_32R2,
+    MIPS_64R2,
+    MIPS_32R6,
+    MIPS_64R6,
+    Unknown(u8)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum MipsAbi {
+    O32,
+    O64,
+    N32,
+    N64,
+    Eabi32,
+    Eabi64,
+    Unknown(u16)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct MipsFlags {
+    pub arch: MipsArch,
+    pub abi: MipsAbi, // Deduced from the class when the flags leave it out
+    pub noreorder: bool,
+    pub pic: bool,
+    pub cpic: bool,
+    pub fp64: bool,
+    pub nan2008: bool,
+    pub micromips: bool,
+    pub mips16: bool
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum PowerPC64Abi {
+    Unspecified,
+    ElfV1, // Function descriptors
+    ElfV2,
+    Unknown(u8)
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum AvrMachine {
+    AVR1,
+    AVR2,
+    AVR25,
+    AVR3,
+    AVR31,
+    AVR35,
+    AVR4,
+    AVR5,
+    AVR51,
+    AVR6,
+    AVRTINY,
+    XMEGA1,
+    XMEGA2,
+    XMEGA3,
+    XMEGA4,
+    XMEGA5,
+    XMEGA6,
+    XMEGA7,
+    Unknown(u8)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct AvrFlags {
+    pub machine: AvrMachine,
+    pub link_relax: bool
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct LoongArchFlags {
+    pub float_abi: Option<FloatAbi>, // None for reserved modifiers
+    pub object_abi_version: u8
+}
+
+// Meaning of e_flags for the architectures that define some, along with the
+// raw value
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum MachineFlags {
+    Arm(ArmFlags, u32),
+    RiscV(RiscVFlags, u32),
+    Mips(MipsFlags, u32),
+    PowerPC64(PowerPC64Abi, u32),
+    Avr(AvrFlags, u32),
+    LoongArch(LoongArchFlags, u32),
+    Other(u32)
+}
+
+impl MachineFlags {
+    // The class is needed to tell MIPS n64 from o32 when no ABI is set
+    pub fn decode(machine: MachineType, bits: BitType, flags: u32) -> Self {
+        match machine {
+            MachineType::ARM => {
+                let eabi_version = ((flags & EF_ARM_EABIMASK) >> 24) as u8;
+                let float_abi = match eabi_version {
+                    0 | 5 if flags & EF_ARM_ABI_FLOAT_HARD != 0 => Some(FloatAbi::Hard),
+                    0 | 5 if flags & EF_ARM_ABI_FLOAT_SOFT != 0 => Some(FloatAbi::Soft),
+                    _ => None
+                };
+                MachineFlags::Arm(ArmFlags {
+                    eabi_version,
+                    float_abi,
+                    be8: flags & EF_ARM_BE8 != 0,
+                    le8: flags & EF_ARM_LE8 != 0
+                }, flags)
+            },
+            MachineType::RISC_V => MachineFlags::RiscV(RiscVFlags {
+                rvc: flags & EF_RISCV_RVC != 0,
+                float_abi: match flags & EF_RISCV_FLOAT_ABI {
+                    0x0 => FloatAbi::Soft,
+                    0x2 => FloatAbi::Single,
+                    0x4 => FloatAbi::Double,
+                    _ => FloatAbi::Quad
+                },
+                rve: flags & EF_RISCV_RVE != 0,
+                tso: flags & EF_RISCV_TSO != 0
+            }, flags),
+            MachineType::MIPS_I | MachineType::MIPS_RS3_LE => {
+                let arch = match (flags & EF_MIPS_ARCH) >> 28 {
+                    0 => MipsArch::MIPS_1,
+                    1 => MipsArch::MIPS_2,
+                    2 => MipsArch::MIPS_3,
+                    3 => MipsArch::MIPS_4,
+                    4 => MipsArch::MIPS_5,
+                    5 => MipsArch::MIPS_32,
+                    6 => MipsArch::MIPS_64,
+                    7 => MipsArch::MIPS_32R2,
+                    8 => MipsArch::MIPS_64R2,
+                    9 => MipsArch::MIPS_32R6,
+                    10 => MipsArch::MIPS_64R6,
+                    v => MipsArch::Unknown(v as u8)
+                };
+                let abi = match flags & EF_MIPS_ABI {
+                    _ if flags & EF_MIPS_ABI2 != 0 => MipsAbi::N32,
+                    0x0000 if bits == BitType::_64 => MipsAbi::N64,
+                    0x0000 | 0x1000 => MipsAbi::O32,
+                    0x2000 => MipsAbi::O64,
+                    0x3000 => MipsAbi::Eabi32,
+                    0x4000 => MipsAbi::Eabi64,
+                    v => MipsAbi::Unknown(v as u16)
+                };
+                MachineFlags::Mips(MipsFlags {
+                    arch,
+                    abi,
+                    noreorder: flags & EF_MIPS_NOREORDER != 0,
+                    pic: flags & EF_MIPS_PIC != 0,
+                    cpic: flags & EF_MIPS_CPIC != 0,
+                    fp64: flags & EF_MIPS_FP64 != 0,
+                    nan2008: flags & EF_MIPS_NAN2008 != 0,
+                    micromips: flags & EF_MIPS_MICROMIPS != 0,
+                    mips16: flags & EF_MIPS_ARCH_ASE_M16 != 0
+                }, flags)
+            },
+            MachineType::PowerPC64 => MachineFlags::PowerPC64(match flags & EF_PPC64_ABI {
+                0 => PowerPC64Abi::Unspecified,
+                1 => PowerPC64Abi::ElfV1,
+                2 => PowerPC64Abi::ElfV2,
+                v => PowerPC64Abi::Unknown(v as u8)
+            }, flags),
+            MachineType::AVR => MachineFlags::Avr(AvrFlags {
+                machine: match flags & EF_AVR_MACH {
+                    1 => AvrMachine::AVR1,
+                    2 => AvrMachine::AVR2,
+                    25 => AvrMachine::AVR25,
+                    3 => AvrMachine::AVR3,
+                    31 => AvrMachine::AVR31,
+                    35 => AvrMachine::AVR35,
+                    4 => AvrMachine::AVR4,
+                    5 => AvrMachine::AVR5,
+                    51 => AvrMachine::AVR51,
+                    6 => AvrMachine::AVR6,
+                    100 => AvrMachine::AVRTINY,
+                    101 => AvrMachine::XMEGA1,
+                    102 => AvrMachine::XMEGA2,
+                    103 => AvrMachine::XMEGA3,
+                    104 => AvrMachine::XMEGA4,
+                    105 => AvrMachine::XMEGA5,
+                    106 => AvrMachine::XMEGA6,
+                    107 => AvrMachine::XMEGA7,
+                    v => AvrMachine::Unknown(v as u8)
+                },
+                link_relax: flags & EF_AVR_LINKRELAX_PREPARED != 0
+            }, flags),
+            MachineType::LoongArch => MachineFlags::LoongArch(LoongArchFlags {
+                float_abi: match flags & EF_LARCH_ABI_MODIFIER_MASK {
+                    1 => Some(FloatAbi::Soft),
+                    2 => Some(FloatAbi::Single),
+                    3 => Some(FloatAbi::Double),
+                    _ => None
+                },
+                object_abi_version: ((flags & EF_LARCH_OBJABI_MASK) >> 6) as u8
+            }, flags),
+            _ => MachineFlags::Other(flags)
+        }
+    }
+
+    pub fn raw(&self) -> u32 {
+        match *self {
+            MachineFlags::Arm(_, raw) | MachineFlags::RiscV(_, raw) | MachineFlags::Mips(_, raw) | MachineFlags::PowerPC64(_, raw)
+                | MachineFlags::Avr(_, raw) | MachineFlags::LoongArch(_, raw) | MachineFlags::Other(raw) => raw
+        }
+    }
+
+    // Descriptions readelf appends to the flags in the file header
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        if self.raw() == 0 { return names }
+        match self {
+            MachineFlags::Arm(arm, _) => {
+                names.push(match arm.eabi_version {
+                    0 => "GNU EABI".to_string(),
+                    1..=5 => format!("Version{} EABI", arm.eabi_version),
+                    _ => "<EABI version unrecognised>".to_string()
+                });
+                if arm.be8 { names.push("BE8".to_string()) }
+                if arm.le8 { names.push("LE8".to_string()) }
+                match (arm.eabi_version, arm.float_abi) {
+                    (0, Some(FloatAbi::Soft)) => names.push("software FP".to_string()),
+                    (0, Some(_)) => names.push("VFP".to_string()),
+                    (_, Some(FloatAbi::Soft)) => names.push("soft-float ABI".to_string()),
+                    (_, Some(_)) => names.push("hard-float ABI".to_string()),
+                    _ => {}
+                }
+            },
+            MachineFlags::RiscV(riscv, _) => {
+                if riscv.rvc { names.push("RVC".to_string()) }
+                if riscv.rve { names.push("RVE".to_string()) }
+                if riscv.tso { names.push("TSO".to_string()) }
+                names.push(format!("{}-float ABI", float_abi_name(riscv.float_abi)));
+            },
+            MachineFlags::Mips(mips, raw) => {
+                let bits = [(mips.noreorder, "noreorder"), (mips.pic, "pic"), (mips.cpic, "cpic"), (raw & EF_MIPS_ABI2 != 0, "abi2"),
+                    (mips.nan2008, "nan2008"), (mips.fp64, "fp64")];
+                names.extend(bits.iter().filter(|(set, _)| *set).map(|(_, name)| name.to_string()));
+                // Only an ABI spelled out in the flags is shown
+                if raw & EF_MIPS_ABI != 0 {
+                    names.push(match mips.abi {
+                        MipsAbi::O32 => "o32".to_string(),
+                        MipsAbi::O64 => "o64".to_string(),
+                        MipsAbi::Eabi32 => "eabi32".to_string(),
+                        MipsAbi::Eabi64 => "eabi64".to_string(),
+                        _ => "unknown ABI".to_string()
+                    });
+                }
+                if mips.mips16 { names.push("mips16".to_string()) }
+                if mips.micromips { names.push("micromips".to_string()) }
+                names.push(match mips.arch {
+                    MipsArch::Unknown(_) => "unknown ISA".to_string(),
+                    arch => format!("{:?}", arch).replace('_', "").to_lowercase()
+                });
+            },
+            MachineFlags::PowerPC64(abi, _) => match abi {
+                PowerPC64Abi::Unspecified => {},
+                PowerPC64Abi::ElfV1 => names.push("abiv1".to_string()),
+                PowerPC64Abi::ElfV2 => names.push("abiv2".to_string()),
+                PowerPC64Abi::Unknown(v) => names.push(format!("abiv{}", v))
+            },
+            MachineFlags::Avr(avr, raw) => {
+                names.push(format!("avr:{}", raw & EF_AVR_MACH));
+                if avr.link_relax { names.push("link-relax".to_string()) }
+            },
+            MachineFlags::LoongArch(loongarch, _) => {
+                if let Some(abi) = loongarch.float_abi {
+                    names.push(format!("{}-FLOAT", float_abi_name(abi).to_uppercase()));
+                }
+                names.push(format!("OBJ-v{}", loongarch.object_abi_version));
+            },
+            MachineFlags::Other(_) => {}
+        }
+        names
+    }
+}
+
+fn float_abi_name(abi: FloatAbi) -> &'static str {
+    match abi {
+        FloatAbi::Soft => "soft",
+        FloatAbi::Single => "single",
+        FloatAbi::Double => "double",
+        FloatAbi::Quad => "quad",
+        FloatAbi::Hard => "hard"
+    }
+}
+
+// The raw value followed by the descriptions, like in readelf
+impl fmt::Display for MachineFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#x}", self.raw())?;
+        for name in self.names() {
+            write!(f, ", {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn readelf_descriptions() {
+        use super::*;
+
+        let cases = [
+            (MachineType::ARM, BitType::_32, 0x5000400, "0x5000400, Version5 EABI, hard-float ABI"),
+            (MachineType::ARM, BitType::_32, 0x5000200, "0x5000200, Version5 EABI, soft-float ABI"),
+            (MachineType::ARM, BitType::_32, 0x5800000, "0x5800000, Version5 EABI, BE8"),
+            (MachineType::ARM, BitType::_32, 0x4000000, "0x4000000, Version4 EABI"),
+            (MachineType::ARM, BitType::_32, 0x200, "0x200, GNU EABI, software FP"),
+            (MachineType::ARM, BitType::_32, 0x400, "0x400, GNU EABI, VFP"),
+            (MachineType::ARM, BitType::_32, 0x0, "0x0"),
+            (MachineType::RISC_V, BitType::_64, 0x5, "0x5, RVC, double-float ABI"),
+            (MachineType::RISC_V, BitType::_64, 0x3, "0x3, RVC, single-float ABI"),
+            (MachineType::RISC_V, BitType::_64, 0x7, "0x7, RVC, quad-float ABI"),
+            (MachineType::RISC_V, BitType::_32, 0x1d, "0x1d, RVC, RVE, TSO, double-float ABI"),
+            (MachineType::RISC_V, BitType::_32, 0x8, "0x8, RVE, soft-float ABI"),
+            (MachineType::MIPS_I, BitType::_32, 0x70001007, "0x70001007, noreorder, pic, cpic, o32, mips32r2"),
+            (MachineType::MIPS_I, BitType::_32, 0x50001005, "0x50001005, noreorder, cpic, o32, mips32"),
+            (MachineType::MIPS_I, BitType::_64, 0x80000027, "0x80000027, noreorder, pic, cpic, abi2, mips64r2"),
+            (MachineType::MIPS_I, BitType::_64, 0x60000007, "0x60000007, noreorder, pic, cpic, mips64"),
+            (MachineType::MIPS_I, BitType::_64, 0xa0000407, "0xa0000407, noreorder, pic, cpic, nan2008, mips64r6"),
+            (MachineType::MIPS_I, BitType::_32, 0x90001407, "0x90001407, noreorder, pic, cpic, nan2008, o32, mips32r6"),
+            (MachineType::MIPS_I, BitType::_32, 0x1000, "0x1000, o32, mips1"),
+            (MachineType::MIPS_I, BitType::_32, 0x600, "0x600, nan2008, fp64, mips1"),
+            (MachineType::PowerPC64, BitType::_64, 0x1, "0x1, abiv1"),
+            (MachineType::PowerPC64, BitType::_64, 0x2, "0x2, abiv2"),
+            (MachineType::AVR, BitType::_32, 0x85, "0x85, avr:5, link-relax"),
+            (MachineType::AVR, BitType::_32, 0x66, "0x66, avr:102"),
+            (MachineType::LoongArch, BitType::_64, 0x43, "0x43, DOUBLE-FLOAT, OBJ-v1"),
+            (MachineType::LoongArch, BitType::_64, 0x41, "0x41, SOFT-FLOAT, OBJ-v1"),
+            (MachineType::LoongArch, BitType::_64, 0x42, "0x42, SINGLE-FLOAT, OBJ-v1"),
+            (MachineType::LoongArch, BitType::_64, 0x7, "0x7, OBJ-v0"),
+            (MachineType::x64, BitType::_64, 0x0, "0x0")
+        ];
+        for (machine, bits, flags, expected) in cases {
+            assert_eq!(MachineFlags::decode(machine, bits, flags).to_string(), expected);
+        }
+
+        assert_eq!(MachineFlags::decode(MachineType::ARM, BitType::_32, 0x5000400),
+            MachineFlags::Arm(ArmFlags { eabi_version: 5, float_abi: Some(FloatAbi::Hard), be8: false, le8: false }, 0x5000400));
+        let MachineFlags::Mips(n64, _) = MachineFlags::decode(MachineType::MIPS_I, BitType::_64, 0x60000007) else { panic!() };
+        assert_eq!((n64.arch, n64.abi), (MipsArch::MIPS_64, MipsAbi::N64));
+        let MachineFlags::Mips(n32, _) = MachineFlags::decode(MachineType::MIPS_I, BitType::_64, 0x80000027) else { panic!() };
+        assert_eq!((n32.arch, n32.abi), (MipsArch::MIPS_64R2, MipsAbi::N32));
+        assert_eq!(MachineFlags::decode(MachineType::AVR, BitType::_32, 0x85),
+            MachineFlags::Avr(AvrFlags { machine: AvrMachine::AVR5, link_relax: true }, 0x85));
+        assert_eq!(MachineFlags::decode(MachineType::PowerPC64, BitType::_64, 0x2), MachineFlags::PowerPC64(PowerPC64Abi::ElfV2, 0x2));
+    }
+}
@@ -1,9 +1,15 @@
-use elf_parser::elf_parser::*;
-use elf_parser::elf::*;
-use elf_parser::elf::{EI_NIDENT};
-use std::fs;
+mod cli;
 
-fn main() {
-    let content = fs::read("/bin/ls").unwrap();
-    Elf64::parse(&content).unwrap();
+use std::env;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    // The first argument picks the tool, readelf being the default
+    match args.first().map(String::as_str) {
+        Some("readelf") => cli::readelf::run(&args[1..]),
+        Some("nm") => cli::nm::run(&args[1..]),
+        Some("size") => cli::size::run(&args[1..]),
+        _ => cli::readelf::run(&args)
+    }
 }
@@ -0,0 +1,97 @@
+use crate::elf::{Elf, Elf64};
+use crate::parse_error::ParseError;
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::Path;
+use std::slice;
+
+// Maps the file at `path`, the returned slice points inside the map
+fn map<P: AsRef<Path>>(path: P) -> Result<(Mmap, &'static [u8]), ParseError> {
+    let file = File::open(path)?;
+    // SAFETY: the file must not be modified while it is mapped, like any other mapped file
+    let map = unsafe { Mmap::map(&file) }?;
+    // SAFETY: the mapping never moves and the callers keep it next to the
+    // parsed file, only handing out views bounded by the lifetime of the pair
+    let data: &'static [u8] = unsafe { slice::from_raw_parts(map.as_ptr(), map.len()) };
+    Ok((map, data))
+}
+
+// Elf64 parsed on top of a memory mapped file. Only the headers
+// are read when opening, section and segment contents are paged in
+// by the OS when they are accessed
+pub struct MappedElf64 {
+    elf: Elf64<'static>, // Borrows from `map`, declared first so it is dropped first
+    map: Mmap
+}
+
+impl MappedElf64 {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let (map, data) = map(path)?;
+        let elf = Elf64::parse(data)?;
+        Ok(Self { elf, map })
+    }
+
+    pub fn elf(&self) -> &Elf64<'_> {
+        &self.elf
+    }
+
+    pub fn mmap(&self) -> &Mmap {
+        &self.map
+    }
+}
+
+// Same for either class of file
+pub struct MappedElf {
+    elf: Elf<'static>,
+    map: Mmap
+}
+
+impl MappedElf {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let (map, data) = map(path)?;
+        let elf = Elf::parse(data)?;
+        Ok(Self { elf, map })
+    }
+
+    pub fn elf(&self) -> &Elf<'_> {
+        &self.elf
+    }
+
+    pub fn mmap(&self) -> &Mmap {
+        &self.map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn open() {
+        use super::*;
+        use crate::elf_file::ElfFile;
+        use std::fs;
+
+        let mapped = MappedElf64::open("testdata/hello").unwrap();
+        let content = fs::read("testdata/hello").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        assert_eq!(mapped.elf().header(), elf.header());
+        assert_eq!(mapped.elf().data(), &content[..]);
+
+        let text = mapped.elf().section_by_name(".text").unwrap();
+        assert_eq!(mapped.elf().section_data(&text).unwrap(), elf.section_data(&text).unwrap());
+
+        assert!(matches!(MappedElf64::open("/does/not/exist").err(), Some(ParseError::Io { kind: std::io::ErrorKind::NotFound, .. })));
+    }
+
+    #[test]
+    fn open_any_class() {
+        use super::*;
+        use crate::elf::BitType;
+        use crate::elf_file::ElfFile;
+
+        for (path, bits) in [("testdata/hello", BitType::_64), ("testdata/static32", BitType::_32)] {
+            let mapped = MappedElf::open(path).unwrap();
+            assert_eq!(mapped.elf().bits(), bits);
+            assert_eq!(mapped.elf().data(), &mapped.mmap()[..]);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::cursor::Cursor;
+use crate::elf::*;
+use crate::endianness::Endianness;
+use crate::parse_error::ParseError;
+use std::str;
+
+// Note types of the "GNU" owner
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_HWCAP: u32 = 2;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+// Note types of the "Go" owner
+pub const NT_GO_PKGLIST: u32 = 1;
+pub const NT_GO_ABIHASH: u32 = 2;
+pub const NT_GO_DEPS: u32 = 3;
+pub const NT_GO_BUILD_ID: u32 = 4;
+// Note type of the "FDO" owner, see https://systemd.io/ELF_PACKAGE_METADATA/
+pub const NT_FDO_PACKAGING_METADATA: u32 = 0xCAFE1A7E;
+
+// GNU property types
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xC0000000;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xC0000002;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xC0008002;
+pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xC0010002;
+
+// Bits of GNU_PROPERTY_X86_FEATURE_1_AND
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 0x1;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 0x2;
+// Bits of GNU_PROPERTY_AARCH64_FEATURE_1_AND
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 0x1;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 0x2;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_GCS: u32 = 0x4;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Note<'a> {
+    pub name: &'a [u8], // Owner, without the NUL terminator
+    pub n_type: u32,
+    pub desc: &'a [u8],
+    pub desc_offset: u64 // Where the descriptor is in the file
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum GnuAbiOs {
+    Linux,
+    Hurd,
+    Solaris,
+    FreeBSD,
+    Unknown(u32)
+}
+
+// x86 micro-architecture levels of GNU_PROPERTY_X86_ISA_1_*
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct X86IsaLevels(pub u32);
+
+impl X86IsaLevels {
+    const NAMES: [&'static str; 4] = ["x86-64-baseline", "x86-64-v2", "x86-64-v3", "x86-64-v4"];
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        Self::NAMES.iter().enumerate().filter(|(i, _)| self.0 >> i & 1 == 1).map(|(_, name)| *name)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum GnuProperty<'a> {
+    StackSize(u64),
+    NoCopyOnProtected,
+    X86IsaNeeded(X86IsaLevels),
+    X86IsaUsed(X86IsaLevels),
+    X86Features { ibt: bool, shstk: bool, raw: u32 },
+    AArch64Features { bti: bool, pac: bool, gcs: bool, raw: u32 },
+    Unknown { pr_type: u32, data: &'a [u8] }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum NoteDescriptor<'a> {
+    GnuAbiTag { os: GnuAbiOs, major: u32, minor: u32, patch: u32 },
+    GnuBuildId(&'a [u8]),
+    GnuGoldVersion(&'a str),
+    GnuProperties(Vec<GnuProperty<'a>>),
+    GoBuildId(&'a str),
+    GoPackageList(&'a str),
+    GoAbiHash(&'a str),
+    GoDependencies(&'a str),
+    FdoPackageMetadata(&'a str), // JSON
+    Unknown
+}
+
+impl<'a> Note<'a> {
+    pub fn name_str(&self) -> Option<&'a str> {
+        str::from_utf8(self.name).ok()
+    }
+
+    // Decodes the descriptor of the known note types. The property array
+    // layout depends on the class so it has to be given
+    pub fn decode(&self, bits: BitType, endian: Endianness) -> Result<NoteDescriptor<'a>, ParseError> {
+        let text = |desc: &'a [u8]| -> Result<&'a str, ParseError> {
+            // Strings may or may not carry their NUL terminator
+            let end = desc.iter().position(|&b| b == 0).unwrap_or(desc.len());
+            str::from_utf8(&desc[..end]).map_err(|_| ParseError::InvalidString { offset: self.desc_offset })
+        };
+        let word = |i: usize| Cursor::with_base(self.desc, self.desc_offset, i * 4, endian).read32().map_err(note_error);
+
+        match (self.name, self.n_type) {
+            (b"GNU", NT_GNU_ABI_TAG) => {
+                let os = match word(0)? {
+                    0 => GnuAbiOs::Linux,
+                    1 => GnuAbiOs::Hurd,
+                    2 => GnuAbiOs::Solaris,
+                    3 => GnuAbiOs::FreeBSD,
+                    v => GnuAbiOs::Unknown(v)
+                };
+                Ok(NoteDescriptor::GnuAbiTag { os, major: word(1)?, minor: word(2)?, patch: word(3)? })
+            },
+            (b"GNU", NT_GNU_BUILD_ID) => Ok(NoteDescriptor::GnuBuildId(self.desc)),
+            (b"GNU", NT_GNU_GOLD_VERSION) => Ok(NoteDescriptor::GnuGoldVersion(text(self.desc)?)),
+            (b"GNU", NT_GNU_PROPERTY_TYPE_0) => Ok(NoteDescriptor::GnuProperties(parse_gnu_properties(self.desc, self.desc_offset, bits, endian)?)),
+            (b"Go", NT_GO_BUILD_ID) => Ok(NoteDescriptor::GoBuildId(text(self.desc)?)),
+            (b"Go", NT_GO_PKGLIST) => Ok(NoteDescriptor::GoPackageList(text(self.desc)?)),
+            (b"Go", NT_GO_ABIHASH) => Ok(NoteDescriptor::GoAbiHash(text(self.desc)?)),
+            (b"Go", NT_GO_DEPS) => Ok(NoteDescriptor::GoDependencies(text(self.desc)?)),
+            (b"FDO", NT_FDO_PACKAGING_METADATA) => Ok(NoteDescriptor::FdoPackageMetadata(text(self.desc)?)),
+            _ => Ok(NoteDescriptor::Unknown)
+        }
+    }
+}
+
+// A read going past the end of a note or of its descriptor
+fn note_error(e: ParseError) -> ParseError {
+    match e {
+        ParseError::UnexpectedEnd { offset, expected, actual } =>
+            ParseError::TooSmallNote { offset, expected: expected as usize, actual: actual as usize },
+        e => e
+    }
+}
+
+// Reads the pr_type/pr_datasz/pr_data array of NT_GNU_PROPERTY_TYPE_0,
+// entries are aligned on 8 bytes on ELF64 and 4 bytes on ELF32. `desc_offset`
+// is where the descriptor is in the file
+pub fn parse_gnu_properties(desc: &[u8], desc_offset: u64, bits: BitType, endian: Endianness) -> Result<Vec<GnuProperty<'_>>, ParseError> {
+    let align = match bits {
+        BitType::_32 => 4,
+        BitType::_64 => 8
+    };
+
+    let mut properties = Vec::new();
+    let mut offset = 0;
+    while offset < desc.len() {
+        let mut c = Cursor::with_base(desc, desc_offset, offset, endian);
+        let (pr_type, datasz) = (c.read32().map_err(note_error)?, c.read32().map_err(note_error)? as usize);
+        let start = c.offset();
+        let data = c.bytes(datasz).map_err(note_error)?;
+        let data_cursor = || Cursor::with_base(data, desc_offset + start as u64, 0, endian);
+        let flags = || data_cursor().read32().map_err(note_error);
+
+        properties.push(match pr_type {
+            GNU_PROPERTY_STACK_SIZE => {
+                if data.len() != align {
+                    return Err(ParseError::TooSmallNote { offset: desc_offset + start as u64, expected: align, actual: data.len() });
+                }
+                GnuProperty::StackSize(data_cursor().read_word(bits)?)
+            },
+            GNU_PROPERTY_NO_COPY_ON_PROTECTED => GnuProperty::NoCopyOnProtected,
+            GNU_PROPERTY_X86_ISA_1_NEEDED => GnuProperty::X86IsaNeeded(X86IsaLevels(flags()?)),
+            GNU_PROPERTY_X86_ISA_1_USED => GnuProperty::X86IsaUsed(X86IsaLevels(flags()?)),
+            GNU_PROPERTY_X86_FEATURE_1_AND => {
+                let raw = flags()?;
+                GnuProperty::X86Features {
+                    ibt: raw & GNU_PROPERTY_X86_FEATURE_1_IBT != 0,
+                    shstk: raw & GNU_PROPERTY_X86_FEATURE_1_SHSTK != 0,
+                    raw
+                }
+            },
+            GNU_PROPERTY_AARCH64_FEATURE_1_AND => {
+                let raw = flags()?;
+                GnuProperty::AArch64Features {
+                    bti: raw & GNU_PROPERTY_AARCH64_FEATURE_1_BTI != 0,
+                    pac: raw & GNU_PROPERTY_AARCH64_FEATURE_1_PAC != 0,
+                    gcs: raw & GNU_PROPERTY_AARCH64_FEATURE_1_GCS != 0,
+                    raw
+                }
+            },
+            _ => GnuProperty::Unknown { pr_type, data }
+        });
+
+        // Jump over the padding
+        offset = (start + datasz).div_ceil(align) * align;
+    }
+    Ok(properties)
+}
+
+// Walks the notes of a PT_NOTE segment or a SHT_NOTE section. Headers are
+// always made of three 4 bytes words, the name and descriptor are padded
+// to the alignment of the segment or section (4 or 8)
+#[derive(Debug, Clone)]
+pub struct NoteIterator<'a> {
+    data: &'a [u8],
+    base: u64, // Where the notes are in the file
+    offset: usize,
+    align: usize,
+    endian: Endianness
+}
+
+impl<'a> NoteIterator<'a> {
+    pub fn new(data: &'a [u8], base: u64, align: u64, endian: Endianness) -> Self {
+        Self {
+            data,
+            base,
+            offset: 0,
+            align: if align == 8 { 8 } else { 4 },
+            endian
+        }
+    }
+}
+
+impl<'a> Iterator for NoteIterator<'a> {
+    type Item = Result<Note<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.data.len() { return None }
+        let f = self.data;
+        let pad = |v: usize| v.checked_add(self.align - 1).map(|v| v / self.align * self.align);
+
+        let start = self.offset;
+        let mut c = Cursor::with_base(f, self.base, start, self.endian);
+        let (namesz, descsz, n_type) = match (c.read32(), c.read32(), c.read32()) {
+            (Ok(namesz), Ok(descsz), Ok(n_type)) => (namesz as usize, descsz as usize, n_type),
+            _ => {
+                self.offset = f.len(); // Stop after reporting the error
+                return Some(Err(ParseError::TooSmallNote { offset: self.base + start as u64, expected: 12, actual: f.len() - start }));
+            }
+        };
+
+        let name_start = start + 12;
+        let desc_start = name_start.checked_add(namesz).and_then(pad);
+        let desc_end = desc_start.and_then(|s| s.checked_add(descsz));
+        // The descriptor starts at the first aligned offset after the name
+        let mut fields = || -> Option<(&'a [u8], &'a [u8])> {
+            let name = c.bytes(namesz).ok()?;
+            c.bytes(desc_start? - c.offset()).ok()?;
+            Some((name, c.bytes(descsz).ok()?))
+        };
+        let Some((name, desc)) = fields() else {
+            self.offset = f.len();
+            let expected = desc_end.map_or(usize::MAX, |end| end - start);
+            return Some(Err(ParseError::TooSmallNote { offset: self.base + start as u64, expected, actual: f.len() - start }));
+        };
+        let desc_offset = self.base + desc_start.unwrap_or_default() as u64;
+        self.offset = pad(desc_end.unwrap_or(f.len())).unwrap_or(f.len());
+
+        // The name length counts the NUL terminator
+        let name = match name.split_last() {
+            Some((0, name)) => name,
+            _ => name
+        };
+        Some(Ok(Note { name, n_type, desc, desc_offset }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn notes64() {
+        use super::*;
+        use crate::elf_file::ElfFile;
+        use std::fs;
+
+        let content = fs::read("testdata/hello").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let notes = elf.segment_notes().unwrap();
+        let decoded: Vec<NoteDescriptor> = notes.iter().map(|n| n.decode(BitType::_64, Endianness::LittleEndian).unwrap()).collect();
+
+        let build_id = notes.iter().find(|n| n.name == b"GNU" && n.n_type == NT_GNU_BUILD_ID).unwrap();
+        assert!(decoded.contains(&NoteDescriptor::GnuBuildId(build_id.desc)));
+        assert!(decoded.iter().any(|d| matches!(d, NoteDescriptor::GnuAbiTag { os: GnuAbiOs::Linux, major: 3.., .. })));
+
+        // The sections hold the same notes as the segments
+        assert_eq!(elf.section_notes().unwrap().len(), notes.len());
+    }
+
+    #[test]
+    fn properties() {
+        use super::*;
+        use crate::elf_file::ElfFile;
+        use std::fs;
+
+        // Relocatable files only have the SHT_NOTE sections
+        let content = fs::read("testdata/properties.o").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        assert!(elf.segment_notes().unwrap().is_empty());
+        let notes = elf.section_notes().unwrap();
+        let property = notes.iter().find(|n| n.name == b"GNU" && n.n_type == NT_GNU_PROPERTY_TYPE_0).unwrap();
+        match property.decode(BitType::_64, Endianness::LittleEndian).unwrap() {
+            NoteDescriptor::GnuProperties(properties) => {
+                assert!(properties.iter().any(|p| matches!(p, GnuProperty::X86Features { ibt: true, shstk: true, .. })));
+            },
+            d => panic!("unexpected descriptor {:?}", d)
+        }
+    }
+
+    #[test]
+    fn synthetic_notes() {
+        use super::*;
+
+        // Go build ID followed by FDO package metadata, 4 bytes alignment
+        let mut data = Vec::new();
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&5u32.to_le_bytes());
+        data.extend_from_slice(&NT_GO_BUILD_ID.to_le_bytes());
+        data.extend_from_slice(b"Go\0\0abcde\0\0\0");
+        let json = br#"{"type":"deb","name":"ls"}"#;
+        data.extend_from_slice(&4u32.to_le_bytes());
+        data.extend_from_slice(&(json.len() as u32 + 1).to_le_bytes());
+        data.extend_from_slice(&NT_FDO_PACKAGING_METADATA.to_le_bytes());
+        data.extend_from_slice(b"FDO\0");
+        data.extend_from_slice(json);
+        data.push(0);
+
+        let notes: Vec<Note> = NoteIterator::new(&data, 0x200, 4, Endianness::LittleEndian).map(Result::unwrap).collect();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].desc_offset, 0x210);
+        assert_eq!(notes[0].decode(BitType::_64, Endianness::LittleEndian), Ok(NoteDescriptor::GoBuildId("abcde")));
+        assert_eq!(notes[1].decode(BitType::_64, Endianness::LittleEndian), Ok(NoteDescriptor::FdoPackageMetadata(r#"{"type":"deb","name":"ls"}"#)));
+
+        // Truncated descriptor
+        let mut notes = NoteIterator::new(&data[..16], 0x200, 4, Endianness::LittleEndian);
+        assert_eq!(notes.next(), Some(Err(ParseError::TooSmallNote { offset: 0x200, expected: 21, actual: 16 })));
+        assert_eq!(notes.next(), None);
+
+        // Errors in the descriptor point into the file
+        let mut bad = Vec::from(&data[..16]);
+        bad.extend_from_slice(b"ab\xFF\0\0\0\0\0");
+        let note = NoteIterator::new(&bad, 0x200, 4, Endianness::LittleEndian).next().unwrap().unwrap();
+        assert_eq!(note.decode(BitType::_64, Endianness::LittleEndian), Err(ParseError::InvalidString { offset: 0x210 }));
+    }
+}
@@ -1,13 +1,107 @@
-#[derive(Debug, Eq, PartialEq)]
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+// Offsets are from the start of the file unless said otherwise. `expected`
+// is the number of bytes needed and `actual` the number of bytes there is
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub enum ParseError {
-    NotELF,
-    TooSmallIdent,
-    TooSmallHeader,
-    UnsupportedMode,
-    UnsupportedEndianness,
-    UnsupportedABI,
-    UnsupportedFileType,
-    UnsupportedMachineType,
-    UnsupportedVersion,
-    UnsupportedProgramHeaderType
+    NotELF { magic: [u8; 4] },
+    UnexpectedEnd { offset: u64, expected: u64, actual: u64 }, // A read going past the end of its buffer
+    TooSmallIdent { expected: usize, actual: usize },
+    TooSmallHeader { expected: usize, actual: usize },
+    UnsupportedMode(u8), // EI_CLASS
+    UnsupportedEndianness(u8), // EI_DATA
+    TooSmallProgramHeader { expected: usize, actual: usize }, // e_phentsize
+    TooSmallProgramHeaderTable { index: usize, offset: u64, expected: u64, actual: u64 }, // Entry cut off
+    TooSmallSectionHeader { expected: usize, actual: usize }, // e_shentsize
+    TooSmallSectionHeaderTable { index: usize, offset: u64, expected: u64, actual: u64 },
+    InvalidSectionIndex(usize),
+    InvalidSegmentIndex(usize),
+    SectionOutOfBounds { offset: u64, expected: u64, actual: u64 },
+    SegmentOutOfBounds { offset: u64, expected: u64, actual: u64 },
+    StringOutOfBounds { table: u64, offset: usize, size: usize }, // Offset of the string in the table
+    UnterminatedString { offset: u64 },
+    InvalidString { offset: u64 },
+    InvalidSectionLink(u32),
+    TooSmallSymbol { index: usize, offset: u64, expected: usize, actual: u64 }, // Section, where it is in the file and sh_entsize
+    SymbolOutOfBounds { index: usize, count: usize },
+    TooSmallSymbolTable { index: usize, offset: u64, expected: u64, actual: u64 }, // Entry cut off
+    MissingExtendedSectionIndex { symbol: usize },
+    UnmappedAddress(u64),
+    UnmappedOffset(u64),
+    ZeroFilledAddress(u64),
+    NotRelocationSection { index: usize, sh_type: u32 },
+    TooSmallRelocation { index: usize, offset: u64, expected: usize, actual: u64 }, // Same
+    TooSmallNote { offset: u64, expected: usize, actual: usize },
+    TooSmallVersion { offset: u64 },
+    InvalidVersionIndex(u16),
+    TooSmallDebugLink { index: usize, offset: u64, expected: usize, actual: usize },
+    InvalidHashTable { offset: u64 }, // Offset of the faulty word
+    MissingHashTable,
+    Io { kind: io::ErrorKind, message: String }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::NotELF { magic } => write!(f, "not an ELF file, magic number is {:02x} {:02x} {:02x} {:02x}", magic[0], magic[1], magic[2], magic[3]),
+            ParseError::UnexpectedEnd { offset, expected, actual } => write!(f, "reading {} bytes at offset {:#x} goes past the end, {} left", expected, offset, actual),
+            ParseError::TooSmallIdent { expected, actual } => write!(f, "ident needs {} bytes, the file has {}", expected, actual),
+            ParseError::TooSmallHeader { expected, actual } => write!(f, "ELF header needs {} bytes, the file has {}", expected, actual),
+            ParseError::UnsupportedMode(v) => write!(f, "unsupported class {:#x}", v),
+            ParseError::UnsupportedEndianness(v) => write!(f, "unsupported data encoding {:#x}", v),
+            ParseError::TooSmallProgramHeader { expected, actual } => write!(f, "program headers need {} bytes, e_phentsize is {}", expected, actual),
+            ParseError::TooSmallProgramHeaderTable { index, offset, expected, actual } =>
+                write!(f, "program header #{} at offset {:#x} needs {} bytes, {} left in the file", index, offset, expected, actual),
+            ParseError::TooSmallSectionHeader { expected, actual } => write!(f, "section headers need {} bytes, e_shentsize is {}", expected, actual),
+            ParseError::TooSmallSectionHeaderTable { index, offset, expected, actual } =>
+                write!(f, "section header #{} at offset {:#x} needs {} bytes, {} left in the file", index, offset, expected, actual),
+            ParseError::InvalidSectionIndex(index) => write!(f, "no section #{}", index),
+            ParseError::InvalidSegmentIndex(index) => write!(f, "no program header #{}", index),
+            ParseError::SectionOutOfBounds { offset, expected, actual } =>
+                write!(f, "section at offset {:#x} needs {} bytes, {} left in the file", offset, expected, actual),
+            ParseError::SegmentOutOfBounds { offset, expected, actual } =>
+                write!(f, "segment at offset {:#x} needs {} bytes, {} left in the file", offset, expected, actual),
+            ParseError::StringOutOfBounds { table, offset, size } =>
+                write!(f, "string offset {:#x} is past the end of the {} bytes string table at offset {:#x}", offset, size, table),
+            ParseError::UnterminatedString { offset } => write!(f, "string at offset {:#x} is not terminated", offset),
+            ParseError::InvalidString { offset } => write!(f, "string at offset {:#x} is not valid UTF-8", offset),
+            ParseError::InvalidSectionLink(link) => write!(f, "section link {} does not name a section", link),
+            ParseError::TooSmallSymbol { index, offset, expected, actual } =>
+                write!(f, "symbols of section {} at offset {:#x} need {} bytes, the entry size is {}", index, offset, expected, actual),
+            ParseError::SymbolOutOfBounds { index, count } => write!(f, "symbol #{} is past the end of a table of {}", index, count),
+            ParseError::TooSmallSymbolTable { index, offset, expected, actual } =>
+                write!(f, "symbol #{} at offset {:#x} needs {} bytes, {} left in the table", index, offset, expected, actual),
+            ParseError::MissingExtendedSectionIndex { symbol } => write!(f, "symbol #{} has no extended section index", symbol),
+            ParseError::UnmappedAddress(addr) => write!(f, "address {:#x} is not in a loaded segment of the file", addr),
+            ParseError::UnmappedOffset(offset) => write!(f, "offset {:#x} is not in a loaded segment", offset),
+            ParseError::ZeroFilledAddress(addr) => write!(f, "address {:#x} is in the zero-filled part of a segment", addr),
+            ParseError::NotRelocationSection { index, sh_type } => write!(f, "section #{} of type {:#x} holds no relocations", index, sh_type),
+            ParseError::TooSmallRelocation { index, offset, expected, actual } =>
+                write!(f, "relocations of section {} at offset {:#x} need {} bytes, the entry size is {}", index, offset, expected, actual),
+            ParseError::TooSmallNote { offset, expected, actual } => write!(f, "note at offset {:#x} needs {} bytes, {} left", offset, expected, actual),
+            ParseError::TooSmallVersion { offset } => write!(f, "version entry at offset {:#x} is cut off", offset),
+            ParseError::InvalidVersionIndex(index) => write!(f, "version index {} is neither defined nor required", index),
+            ParseError::TooSmallDebugLink { index, offset, expected, actual } =>
+                write!(f, "debug link in section {} at offset {:#x} needs {} bytes, the section has {}", index, offset, expected, actual),
+            ParseError::InvalidHashTable { offset } => write!(f, "hash table is inconsistent at offset {:#x}", offset),
+            ParseError::MissingHashTable => write!(f, "no hash table"),
+            ParseError::Io { message, .. } => write!(f, "{}", message)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+// Keeps the message of the OS, without the " (os error N)" Rust adds to it
+impl From<io::Error> for ParseError {
+    fn from(e: io::Error) -> Self {
+        let message = e.to_string();
+        let message = match message.find(" (os error ") {
+            Some(end) => message[..end].to_string(),
+            None => message
+        };
+        ParseError::Io { kind: e.kind(), message }
+    }
 }
@@ -0,0 +1,174 @@
+use crate::cursor::Cursor;
+use crate::elf::*;
+use crate::elf_parser::{check_program_header_table, check_section_header_table, program_header_error, section_count, section_header_error};
+use crate::parse_error::ParseError;
+use crate::string_table::StringTable;
+use std::io::{Read, Seek, SeekFrom};
+
+// Front ends reading the file on demand instead of taking the whole file
+// as a slice. Only the headers are read up front, sections and segments
+// are read when asked for
+
+// Reads `len` bytes at `offset`, less when the input ends before
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, len: u64) -> Result<Vec<u8>, ParseError> {
+    reader.seek(SeekFrom::Start(offset))?;
+    // Don't trust `len` for the allocation, it comes from the file
+    let mut buf = Vec::new();
+    reader.take(len).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+// Content of a section or segment, `out_of_bounds` builds the error from
+// the number of bytes there was
+fn read_exact_at<R: Read + Seek>(reader: &mut R, offset: u64, len: u64, out_of_bounds: impl FnOnce(u64) -> ParseError)
+    -> Result<Vec<u8>, ParseError>
+{
+    let buf = read_at(reader, offset, len)?;
+    if (buf.len() as u64) < len { return Err(out_of_bounds(buf.len() as u64)) }
+    Ok(buf)
+}
+
+pub struct ElfReaderN<R, C: Class> {
+    reader: R,
+    header: C::Header,
+    phtable: Vec<C::ProgramHeader>,
+    shtable: Vec<C::SectionHeader>,
+    shstrtab: Option<Vec<u8>> // Section names, loaded on first use
+}
+
+pub type ElfReader32<R> = ElfReaderN<R, Class32>;
+pub type ElfReader64<R> = ElfReaderN<R, Class64>;
+
+impl<R: Read + Seek, C: Class> ElfReaderN<R, C> {
+    // Reads the ELF header and the header tables, nothing else
+    pub fn new(mut reader: R) -> Result<Self, ParseError> {
+        let h = read_at(&mut reader, 0, C::HEADER_SIZE as u64)?;
+        let header = ElfN::<C>::parse_header(&h)?;
+        let wide = C::widen_header(&header);
+        let endian = wide.e_ident.e_endianness; // Get endianness
+
+        // Read each program header
+        let phnum = wide.e_phnum as usize;
+        let mut phtable: Vec<C::ProgramHeader> = Vec::with_capacity(phnum);
+        if phnum > 0 {
+            let entsize = wide.e_phentsize as usize;
+            if entsize < C::PROGRAM_HEADER_SIZE { return Err(ParseError::TooSmallProgramHeader { expected: C::PROGRAM_HEADER_SIZE, actual: entsize }) }
+            let phoff = wide.e_phoff;
+            let table = read_at(&mut reader, phoff, (entsize * phnum) as u64)?;
+            check_program_header_table(phoff.saturating_add(table.len() as u64), phoff, entsize, phnum)?;
+            for i in 0..phnum {
+                phtable.push(Cursor::entry(&table, phoff, 0, i, entsize, endian)
+                    .and_then(|mut c| C::parse_program_header(&mut c))
+                    .map_err(program_header_error(i))?);
+            }
+        }
+
+        // Read each section header
+        let mut shtable: Vec<C::SectionHeader> = Vec::new();
+        if wide.e_shoff != 0 {
+            let entsize = wide.e_shentsize as usize;
+            if entsize < C::SECTION_HEADER_SIZE { return Err(ParseError::TooSmallSectionHeader { expected: C::SECTION_HEADER_SIZE, actual: entsize }) }
+            let shoff = wide.e_shoff;
+            let first = read_at(&mut reader, shoff, entsize as u64)?;
+            check_section_header_table(shoff.saturating_add(first.len() as u64), shoff, entsize, 1)?;
+            let entry = |table: &[u8], i: usize| Cursor::entry(table, shoff, 0, i, entsize, endian)
+                .and_then(|mut c| C::parse_section_header(&mut c))
+                .map_err(section_header_error(i));
+            let shnum = section_count::<C>(&header, &entry(&first, 0)?);
+            let table = read_at(&mut reader, shoff, (shnum as u64).saturating_mul(entsize as u64))?;
+            check_section_header_table(shoff.saturating_add(table.len() as u64), shoff, entsize, shnum)?;
+            for i in 0..shnum {
+                shtable.push(entry(&table, i)?);
+            }
+        }
+
+        Ok(Self {
+            reader,
+            header,
+            phtable,
+            shtable,
+            shstrtab: None
+        })
+    }
+
+    pub fn header(&self) -> &C::Header {
+        &self.header
+    }
+
+    pub fn phtable(&self) -> &[C::ProgramHeader] {
+        &self.phtable
+    }
+
+    pub fn shtable(&self) -> &[C::SectionHeader] {
+        &self.shtable
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    // Reads the content of the section at `index`, empty for SHT_NOBITS
+    pub fn read_section(&mut self, index: usize) -> Result<Vec<u8>, ParseError> {
+        let section = C::widen_section_header(self.shtable.get(index).ok_or(ParseError::InvalidSectionIndex(index))?);
+        if section.sh_type == SectionType::SHT_NOBITS { return Ok(Vec::new()) }
+        let (offset, size) = (section.sh_offset, section.sh_size);
+        read_exact_at(&mut self.reader, offset, size, |actual| ParseError::SectionOutOfBounds { offset, expected: size, actual })
+    }
+
+    // Reads the bytes of the segment at `index` present in the file
+    pub fn read_segment(&mut self, index: usize) -> Result<Vec<u8>, ParseError> {
+        let segment = C::widen_program_header(self.phtable.get(index).ok_or(ParseError::InvalidSegmentIndex(index))?);
+        let (offset, size) = (segment.offset, segment.filesz);
+        read_exact_at(&mut self.reader, offset, size, |actual| ParseError::SegmentOutOfBounds { offset, expected: size, actual })
+    }
+
+    // Name of the section at `index`, empty when the file has no section names
+    pub fn section_name(&mut self, index: usize) -> Result<String, ParseError> {
+        let section = C::widen_section_header(self.shtable.get(index).ok_or(ParseError::InvalidSectionIndex(index))?);
+        let name = section.sh_name as usize;
+        let shstrndx = match C::widen_header(&self.header).e_shstrndx {
+            SHN_UNDEF => return Ok(String::new()),
+            SHN_XINDEX => C::widen_section_header(&self.shtable[0]).sh_link as usize,
+            i => i as usize
+        };
+        if self.shstrtab.is_none() { self.shstrtab = Some(self.read_section(shstrndx)?) }
+        let offset = self.shtable.get(shstrndx).map_or(0, |section| C::widen_section_header(section).sh_offset);
+        let strtab = StringTable::new(self.shstrtab.as_deref().unwrap_or_default(), offset);
+        strtab.get(name).map(str::to_owned)
+    }
+
+    // Index of the first section with the given name
+    pub fn section_by_name(&mut self, name: &str) -> Result<Option<usize>, ParseError> {
+        for i in 0..self.shtable.len() {
+            if self.section_name(i)? == name { return Ok(Some(i)) }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn reader64() {
+        use super::*;
+        use crate::elf_file::ElfFile;
+        use std::{fs, io};
+
+        let content = fs::read("testdata/hello").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let mut reader = ElfReader64::new(fs::File::open("testdata/hello").unwrap()).unwrap();
+        assert_eq!(reader.header(), elf.header());
+        assert_eq!(reader.phtable(), elf.phtable());
+        assert_eq!(reader.shtable(), elf.shtable());
+
+        let text = reader.section_by_name(".text").unwrap().unwrap();
+        assert_eq!(reader.section_name(text).unwrap(), ".text");
+        assert_eq!(reader.read_section(text).unwrap(), elf.section_data(&elf.shtable()[text]).unwrap());
+        assert_eq!(reader.read_segment(0).unwrap(), elf.segment_data(&elf.phtable()[0]).unwrap());
+        assert_eq!(reader.read_section(10000), Err(ParseError::InvalidSectionIndex(10000)));
+
+        // A truncated stream fails the same way as a truncated slice
+        let truncated = io::Cursor::new(&content[..ELF64_HEADER_SIZE + 10]);
+        assert_eq!(ElfReader64::new(truncated).err(), Some(ParseError::TooSmallProgramHeaderTable { index: 0, offset: 64, expected: 56, actual: 10 }));
+    }
+}
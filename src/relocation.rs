@@ -0,0 +1,1160 @@
+use crate::cursor::Cursor;
+use crate::elf::*;
+use crate::elf_file::ElfFile;
+use crate::endianness::Endianness;
+use crate::parse_error::ParseError;
+use std::fmt;
+
+pub const REL32_SIZE: usize = 8;
+pub const RELA32_SIZE: usize = 12;
+pub const REL64_SIZE: usize = 16;
+pub const RELA64_SIZE: usize = 24;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RelocationKind {
+    Rel,  // Implicit addend stored at the target
+    Rela, // Explicit addend
+    Relr  // Packed relative relocations
+}
+
+// Relocation type, decoded according to the machine of the file
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RelocationType {
+    X86_64(RelocationX86_64),
+    I386(RelocationI386),
+    Arm(RelocationArm),
+    AArch64(RelocationAArch64),
+    RiscV(RelocationRiscV),
+    PowerPC(RelocationPowerPC),
+    Mips(RelocationMips),
+    Sparc(RelocationSparc),
+    Unknown(u32) // Unknown machine or value
+}
+
+impl fmt::Display for RelocationType {
+    // Prints the constant name, like R_X86_64_RELATIVE
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelocationType::X86_64(r) => write!(f, "{:?}", r),
+            RelocationType::I386(r) => write!(f, "{:?}", r),
+            RelocationType::Arm(r) => write!(f, "{:?}", r),
+            RelocationType::AArch64(r) => write!(f, "{:?}", r),
+            RelocationType::RiscV(r) => write!(f, "{:?}", r),
+            RelocationType::PowerPC(r) => write!(f, "{:?}", r),
+            RelocationType::Mips(r) => write!(f, "{:?}", r),
+            RelocationType::Sparc(r) => write!(f, "{:?}", r),
+            RelocationType::Unknown(v) => write!(f, "{:#x}", v)
+        }
+    }
+}
+
+pub fn parse_relocation_type(machine: MachineType, v: u32) -> RelocationType {
+    let r#type = match machine {
+        MachineType::x64 => parse_relocation_x86_64(v).map(RelocationType::X86_64),
+        MachineType::Intel_80386 => parse_relocation_i386(v).map(RelocationType::I386),
+        MachineType::ARM => parse_relocation_arm(v).map(RelocationType::Arm),
+        MachineType::AArch64 => parse_relocation_aarch64(v).map(RelocationType::AArch64),
+        MachineType::RISC_V => parse_relocation_riscv(v).map(RelocationType::RiscV),
+        MachineType::PowerPC => parse_relocation_powerpc(v).map(RelocationType::PowerPC),
+        MachineType::MIPS_I | MachineType::MIPS_RS3_LE => parse_relocation_mips(v).map(RelocationType::Mips),
+        // 32-bit, V8+ and 64-bit SPARC share their relocations
+        MachineType::SPARC | MachineType::SPARC32Plus | MachineType::SPARCV9 => parse_relocation_sparc(v).map(RelocationType::Sparc),
+        _ => None
+    };
+    r#type.unwrap_or(RelocationType::Unknown(v))
+}
+
+// Type used for the entries of packed relative relocations
+fn relative_type(machine: MachineType) -> RelocationType {
+    let r#type = match machine {
+        MachineType::x64 => Some(RelocationType::X86_64(RelocationX86_64::R_X86_64_RELATIVE)),
+        MachineType::Intel_80386 => Some(RelocationType::I386(RelocationI386::R_386_RELATIVE)),
+        MachineType::ARM => Some(RelocationType::Arm(RelocationArm::R_ARM_RELATIVE)),
+        MachineType::AArch64 => Some(RelocationType::AArch64(RelocationAArch64::R_AARCH64_RELATIVE)),
+        MachineType::RISC_V => Some(RelocationType::RiscV(RelocationRiscV::R_RISCV_RELATIVE)),
+        MachineType::PowerPC => Some(RelocationType::PowerPC(RelocationPowerPC::R_PPC_RELATIVE)),
+        MachineType::SPARC | MachineType::SPARC32Plus | MachineType::SPARCV9 => Some(RelocationType::Sparc(RelocationSparc::R_SPARC_RELATIVE)),
+        MachineType::MIPS_I | MachineType::MIPS_RS3_LE => Some(RelocationType::Mips(RelocationMips::R_MIPS_REL32)),
+        _ => None
+    };
+    r#type.unwrap_or(RelocationType::Unknown(0))
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Relocation {
+    pub offset: u64,
+    pub symbol: u32, // Index in the linked symbol table, 0 for none
+    pub r#type: RelocationType,
+    pub addend: Option<i64>, // Only for RELA
+    pub info: u64 // Raw r_info, 0 for RELR
+}
+
+// View over a SHT_REL, SHT_RELA or SHT_RELR section
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RelocationTable<'a> {
+    data: &'a [u8],
+    offset: u64, // Where the table is in the file
+    kind: RelocationKind,
+    index: usize, // Section holding the table
+    link: usize, // Symbol table
+    info: usize, // Section the relocations apply to
+    bits: BitType,
+    endian: Endianness,
+    machine: MachineType,
+    entsize: usize
+}
+
+impl<'a> RelocationTable<'a> {
+    // Relocations stored in the section at `index`
+    pub fn parse<E: ElfFile<'a> + ?Sized>(elf: &E, index: usize) -> Result<Self, ParseError> {
+        let section = elf.section_header(index).ok_or(ParseError::InvalidSectionIndex(index))?;
+        let bits = elf.bits();
+        let (kind, min_size) = match (section.sh_type, bits) {
+            (SectionType::SHT_REL, BitType::_32) => (RelocationKind::Rel, REL32_SIZE),
+            (SectionType::SHT_REL, BitType::_64) => (RelocationKind::Rel, REL64_SIZE),
+            (SectionType::SHT_RELA, BitType::_32) => (RelocationKind::Rela, RELA32_SIZE),
+            (SectionType::SHT_RELA, BitType::_64) => (RelocationKind::Rela, RELA64_SIZE),
+            (SectionType::SHT_RELR, BitType::_32) => (RelocationKind::Relr, 4),
+            (SectionType::SHT_RELR, BitType::_64) => (RelocationKind::Relr, 8),
+            _ => return Err(ParseError::NotRelocationSection { index, sh_type: section.sh_type.value() })
+        };
+        let entsize = match section.sh_entsize {
+            0 => min_size,
+            n => usize::try_from(n).unwrap_or(usize::MAX)
+        };
+        // RELR entries are words, other sizes make no sense
+        if entsize < min_size || (kind == RelocationKind::Relr && entsize != min_size) {
+            return Err(ParseError::TooSmallRelocation { index, offset: section.sh_offset, expected: min_size, actual: section.sh_entsize });
+        }
+
+        Ok(Self {
+            data: elf.section_data(&section)?,
+            offset: section.sh_offset,
+            kind,
+            index,
+            link: section.sh_link as usize,
+            info: section.sh_info as usize,
+            bits,
+            endian: elf.endianness(),
+            machine: elf.machine(),
+            entsize
+        })
+    }
+
+    pub fn kind(&self) -> RelocationKind {
+        self.kind
+    }
+
+    pub fn section_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn symbol_table_index(&self) -> usize {
+        self.link
+    }
+
+    pub fn target_section_index(&self) -> usize {
+        self.info
+    }
+
+    // Number of entries, packed RELR words count as one each
+    pub fn len(&self) -> usize {
+        self.data.len() / self.entsize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Whether some bytes of entry `index` are there, a cut off last entry
+    // counts so that reading it reports the error
+    fn has_entry(&self, index: usize) -> bool {
+        index < self.data.len().div_ceil(self.entsize)
+    }
+
+    // Reads the word or the REL/RELA entry at `index`
+    fn read(&self, index: usize) -> Result<(u64, u64, Option<i64>), ParseError> {
+        let mut c = Cursor::entry(self.data, self.offset, 0, index, self.entsize, self.endian)?;
+        let offset = c.read_word(self.bits)?;
+        Ok(match self.kind {
+            RelocationKind::Relr => (offset, 0, None),
+            RelocationKind::Rel => (offset, c.read_word(self.bits)?, None),
+            RelocationKind::Rela => {
+                let info = c.read_word(self.bits)?;
+                let addend = match self.bits {
+                    BitType::_32 => c.read32()? as i32 as i64,
+                    BitType::_64 => c.read64()? as i64
+                };
+                (offset, info, Some(addend))
+            }
+        })
+    }
+
+    // Splits r_info into the symbol index and the type
+    fn split_info(&self, info: u64) -> (u32, u32) {
+        match (self.bits, self.machine) {
+            (BitType::_32, _) => ((info >> 8) as u32, (info & 0xFF) as u32),
+            // MIPS64 packs three types in r_info and little endian files store
+            // the symbol first, which makes it look byte swapped
+            (BitType::_64, MachineType::MIPS_I | MachineType::MIPS_RS3_LE) => match self.endian {
+                Endianness::LittleEndian => ((info & 0xFFFFFFFF) as u32, (info >> 56) as u32),
+                Endianness::BigEndian => ((info >> 32) as u32, (info & 0xFF) as u32)
+            },
+            // SPARC64 keeps extra addend bits above the type
+            (BitType::_64, MachineType::SPARC | MachineType::SPARCV9) => ((info >> 32) as u32, (info & 0xFF) as u32),
+            (BitType::_64, _) => ((info >> 32) as u32, (info & 0xFFFFFFFF) as u32)
+        }
+    }
+
+    pub fn iter(&self) -> RelocationIterator<'a> {
+        RelocationIterator {
+            table: self.clone(),
+            index: 0,
+            next_address: 0,
+            bitmap: 0,
+            bit: 0
+        }
+    }
+}
+
+pub struct RelocationIterator<'a> {
+    table: RelocationTable<'a>,
+    index: usize,
+    // State of RELR decoding
+    next_address: u64,
+    bitmap: u64,
+    bit: u32
+}
+
+impl RelocationIterator<'_> {
+    fn relative(&self, offset: u64) -> Relocation {
+        Relocation {
+            offset,
+            symbol: 0,
+            r#type: relative_type(self.table.machine),
+            addend: None,
+            info: 0
+        }
+    }
+}
+
+impl Iterator for RelocationIterator<'_> {
+    type Item = Result<Relocation, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.table.kind != RelocationKind::Relr {
+            if !self.table.has_entry(self.index) { return None }
+            let entry = self.table.read(self.index);
+            self.index += 1;
+            return Some(entry.map(|(offset, info, addend)| {
+                let (symbol, r#type) = self.table.split_info(info);
+                Relocation {
+                    offset,
+                    symbol,
+                    r#type: parse_relocation_type(self.table.machine, r#type),
+                    addend,
+                    info
+                }
+            }));
+        }
+
+        // An even word is an address to relocate, an odd word is a bitmap
+        // of the following words relative to the last address
+        let word_size = self.table.entsize as u64;
+        let bitmap_bits = word_size as u32 * 8 - 1;
+        loop {
+            while self.bit < bitmap_bits {
+                let bit = self.bit;
+                self.bit += 1;
+                if (self.bitmap >> (bit + 1)) & 1 == 1 {
+                    return Some(Ok(self.relative(self.next_address.wrapping_add(bit as u64 * word_size))));
+                }
+            }
+            if self.bitmap != 0 {
+                self.next_address = self.next_address.wrapping_add(bitmap_bits as u64 * word_size);
+                self.bitmap = 0;
+            }
+
+            if !self.table.has_entry(self.index) { return None }
+            let word = match self.table.read(self.index) {
+                Ok((word, _, _)) => word,
+                Err(e) => {
+                    self.index += 1;
+                    return Some(Err(e));
+                }
+            };
+            self.index += 1;
+            if word & 1 == 0 {
+                self.next_address = word.wrapping_add(word_size);
+                return Some(Ok(self.relative(word)));
+            }
+            self.bitmap = word;
+            self.bit = 0;
+        }
+    }
+}
+
+impl<'a> IntoIterator for &RelocationTable<'a> {
+    type Item = Result<Relocation, ParseError>;
+    type IntoIter = RelocationIterator<'a>;
+
+    fn into_iter(self) -> RelocationIterator<'a> {
+        self.iter()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum RelocationX86_64 {
+    R_X86_64_NONE,
+    R_X86_64_64,
+    R_X86_64_PC32,
+    R_X86_64_GOT32,
+    R_X86_64_PLT32,
+    R_X86_64_COPY,
+    R_X86_64_GLOB_DAT,
+    R_X86_64_JUMP_SLOT,
+    R_X86_64_RELATIVE,
+    R_X86_64_GOTPCREL,
+    R_X86_64_32,
+    R_X86_64_32S,
+    R_X86_64_16,
+    R_X86_64_PC16,
+    R_X86_64_8,
+    R_X86_64_PC8,
+    R_X86_64_DTPMOD64,
+    R_X86_64_DTPOFF64,
+    R_X86_64_TPOFF64,
+    R_X86_64_TLSGD,
+    R_X86_64_TLSLD,
+    R_X86_64_DTPOFF32,
+    R_X86_64_GOTTPOFF,
+    R_X86_64_TPOFF32,
+    R_X86_64_PC64,
+    R_X86_64_GOTOFF64,
+    R_X86_64_GOTPC32,
+    R_X86_64_SIZE32,
+    R_X86_64_SIZE64,
+    R_X86_64_GOTPC32_TLSDESC,
+    R_X86_64_TLSDESC_CALL,
+    R_X86_64_TLSDESC,
+    R_X86_64_IRELATIVE,
+    R_X86_64_GOTPCRELX,
+    R_X86_64_REX_GOTPCRELX
+}
+
+fn parse_relocation_x86_64(v: u32) -> Option<RelocationX86_64> {
+    match v {
+        0 => Some(RelocationX86_64::R_X86_64_NONE),
+        1 => Some(RelocationX86_64::R_X86_64_64),
+        2 => Some(RelocationX86_64::R_X86_64_PC32),
+        3 => Some(RelocationX86_64::R_X86_64_GOT32),
+        4 => Some(RelocationX86_64::R_X86_64_PLT32),
+        5 => Some(RelocationX86_64::R_X86_64_COPY),
+        6 => Some(RelocationX86_64::R_X86_64_GLOB_DAT),
+        7 => Some(RelocationX86_64::R_X86_64_JUMP_SLOT),
+        8 => Some(RelocationX86_64::R_X86_64_RELATIVE),
+        9 => Some(RelocationX86_64::R_X86_64_GOTPCREL),
+        10 => Some(RelocationX86_64::R_X86_64_32),
+        11 => Some(RelocationX86_64::R_X86_64_32S),
+        12 => Some(RelocationX86_64::R_X86_64_16),
+        13 => Some(RelocationX86_64::R_X86_64_PC16),
+        14 => Some(RelocationX86_64::R_X86_64_8),
+        15 => Some(RelocationX86_64::R_X86_64_PC8),
+        16 => Some(RelocationX86_64::R_X86_64_DTPMOD64),
+        17 => Some(RelocationX86_64::R_X86_64_DTPOFF64),
+        18 => Some(RelocationX86_64::R_X86_64_TPOFF64),
+        19 => Some(RelocationX86_64::R_X86_64_TLSGD),
+        20 => Some(RelocationX86_64::R_X86_64_TLSLD),
+        21 => Some(RelocationX86_64::R_X86_64_DTPOFF32),
+        22 => Some(RelocationX86_64::R_X86_64_GOTTPOFF),
+        23 => Some(RelocationX86_64::R_X86_64_TPOFF32),
+        24 => Some(RelocationX86_64::R_X86_64_PC64),
+        25 => Some(RelocationX86_64::R_X86_64_GOTOFF64),
+        26 => Some(RelocationX86_64::R_X86_64_GOTPC32),
+        32 => Some(RelocationX86_64::R_X86_64_SIZE32),
+        33 => Some(RelocationX86_64::R_X86_64_SIZE64),
+        34 => Some(RelocationX86_64::R_X86_64_GOTPC32_TLSDESC),
+        35 => Some(RelocationX86_64::R_X86_64_TLSDESC_CALL),
+        36 => Some(RelocationX86_64::R_X86_64_TLSDESC),
+        37 => Some(RelocationX86_64::R_X86_64_IRELATIVE),
+        41 => Some(RelocationX86_64::R_X86_64_GOTPCRELX),
+        42 => Some(RelocationX86_64::R_X86_64_REX_GOTPCRELX),
+        _ => None
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum RelocationI386 {
+    R_386_NONE,
+    R_386_32,
+    R_386_PC32,
+    R_386_GOT32,
+    R_386_PLT32,
+    R_386_COPY,
+    R_386_GLOB_DAT,
+    R_386_JMP_SLOT,
+    R_386_RELATIVE,
+    R_386_GOTOFF,
+    R_386_GOTPC,
+    R_386_TLS_TPOFF,
+    R_386_TLS_IE,
+    R_386_TLS_GOTIE,
+    R_386_TLS_LE,
+    R_386_TLS_GD,
+    R_386_TLS_LDM,
+    R_386_16,
+    R_386_PC16,
+    R_386_8,
+    R_386_PC8,
+    R_386_TLS_DTPMOD32,
+    R_386_TLS_DTPOFF32,
+    R_386_TLS_TPOFF32,
+    R_386_SIZE32,
+    R_386_TLS_GOTDESC,
+    R_386_TLS_DESC_CALL,
+    R_386_TLS_DESC,
+    R_386_IRELATIVE,
+    R_386_GOT32X
+}
+
+fn parse_relocation_i386(v: u32) -> Option<RelocationI386> {
+    match v {
+        0 => Some(RelocationI386::R_386_NONE),
+        1 => Some(RelocationI386::R_386_32),
+        2 => Some(RelocationI386::R_386_PC32),
+        3 => Some(RelocationI386::R_386_GOT32),
+        4 => Some(RelocationI386::R_386_PLT32),
+        5 => Some(RelocationI386::R_386_COPY),
+        6 => Some(RelocationI386::R_386_GLOB_DAT),
+        7 => Some(RelocationI386::R_386_JMP_SLOT),
+        8 => Some(RelocationI386::R_386_RELATIVE),
+        9 => Some(RelocationI386::R_386_GOTOFF),
+        10 => Some(RelocationI386::R_386_GOTPC),
+        14 => Some(RelocationI386::R_386_TLS_TPOFF),
+        15 => Some(RelocationI386::R_386_TLS_IE),
+        16 => Some(RelocationI386::R_386_TLS_GOTIE),
+        17 => Some(RelocationI386::R_386_TLS_LE),
+        18 => Some(RelocationI386::R_386_TLS_GD),
+        19 => Some(RelocationI386::R_386_TLS_LDM),
+        20 => Some(RelocationI386::R_386_16),
+        21 => Some(RelocationI386::R_386_PC16),
+        22 => Some(RelocationI386::R_386_8),
+        23 => Some(RelocationI386::R_386_PC8),
+        35 => Some(RelocationI386::R_386_TLS_DTPMOD32),
+        36 => Some(RelocationI386::R_386_TLS_DTPOFF32),
+        37 => Some(RelocationI386::R_386_TLS_TPOFF32),
+        38 => Some(RelocationI386::R_386_SIZE32),
+        39 => Some(RelocationI386::R_386_TLS_GOTDESC),
+        40 => Some(RelocationI386::R_386_TLS_DESC_CALL),
+        41 => Some(RelocationI386::R_386_TLS_DESC),
+        42 => Some(RelocationI386::R_386_IRELATIVE),
+        43 => Some(RelocationI386::R_386_GOT32X),
+        _ => None
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum RelocationArm {
+    R_ARM_NONE,
+    R_ARM_PC24,
+    R_ARM_ABS32,
+    R_ARM_REL32,
+    R_ARM_ABS16,
+    R_ARM_ABS12,
+    R_ARM_THM_ABS5,
+    R_ARM_ABS8,
+    R_ARM_SBREL32,
+    R_ARM_THM_CALL,
+    R_ARM_TLS_DESC,
+    R_ARM_TLS_DTPMOD32,
+    R_ARM_TLS_DTPOFF32,
+    R_ARM_TLS_TPOFF32,
+    R_ARM_COPY,
+    R_ARM_GLOB_DAT,
+    R_ARM_JUMP_SLOT,
+    R_ARM_RELATIVE,
+    R_ARM_GOTOFF32,
+    R_ARM_BASE_PREL,
+    R_ARM_GOT_BREL,
+    R_ARM_PLT32,
+    R_ARM_CALL,
+    R_ARM_JUMP24,
+    R_ARM_THM_JUMP24,
+    R_ARM_BASE_ABS,
+    R_ARM_TARGET1,
+    R_ARM_V4BX,
+    R_ARM_TARGET2,
+    R_ARM_PREL31,
+    R_ARM_MOVW_ABS_NC,
+    R_ARM_MOVT_ABS,
+    R_ARM_MOVW_PREL_NC,
+    R_ARM_MOVT_PREL,
+    R_ARM_THM_MOVW_ABS_NC,
+    R_ARM_THM_MOVT_ABS,
+    R_ARM_GOT_PREL,
+    R_ARM_TLS_GD32,
+    R_ARM_TLS_LDM32,
+    R_ARM_TLS_IE32,
+    R_ARM_TLS_LE32,
+    R_ARM_IRELATIVE
+}
+
+fn parse_relocation_arm(v: u32) -> Option<RelocationArm> {
+    match v {
+        0 => Some(RelocationArm::R_ARM_NONE),
+        1 => Some(RelocationArm::R_ARM_PC24),
+        2 => Some(RelocationArm::R_ARM_ABS32),
+        3 => Some(RelocationArm::R_ARM_REL32),
+        5 => Some(RelocationArm::R_ARM_ABS16),
+        6 => Some(RelocationArm::R_ARM_ABS12),
+        7 => Some(RelocationArm::R_ARM_THM_ABS5),
+        8 => Some(RelocationArm::R_ARM_ABS8),
+        9 => Some(RelocationArm::R_ARM_SBREL32),
+        10 => Some(RelocationArm::R_ARM_THM_CALL),
+        13 => Some(RelocationArm::R_ARM_TLS_DESC),
+        17 => Some(RelocationArm::R_ARM_TLS_DTPMOD32),
+        18 => Some(RelocationArm::R_ARM_TLS_DTPOFF32),
+        19 => Some(RelocationArm::R_ARM_TLS_TPOFF32),
+        20 => Some(RelocationArm::R_ARM_COPY),
+        21 => Some(RelocationArm::R_ARM_GLOB_DAT),
+        22 => Some(RelocationArm::R_ARM_JUMP_SLOT),
+        23 => Some(RelocationArm::R_ARM_RELATIVE),
+        24 => Some(RelocationArm::R_ARM_GOTOFF32),
+        25 => Some(RelocationArm::R_ARM_BASE_PREL),
+        26 => Some(RelocationArm::R_ARM_GOT_BREL),
+        27 => Some(RelocationArm::R_ARM_PLT32),
+        28 => Some(RelocationArm::R_ARM_CALL),
+        29 => Some(RelocationArm::R_ARM_JUMP24),
+        30 => Some(RelocationArm::R_ARM_THM_JUMP24),
+        31 => Some(RelocationArm::R_ARM_BASE_ABS),
+        38 => Some(RelocationArm::R_ARM_TARGET1),
+        40 => Some(RelocationArm::R_ARM_V4BX),
+        41 => Some(RelocationArm::R_ARM_TARGET2),
+        42 => Some(RelocationArm::R_ARM_PREL31),
+        43 => Some(RelocationArm::R_ARM_MOVW_ABS_NC),
+        44 => Some(RelocationArm::R_ARM_MOVT_ABS),
+        45 => Some(RelocationArm::R_ARM_MOVW_PREL_NC),
+        46 => Some(RelocationArm::R_ARM_MOVT_PREL),
+        47 => Some(RelocationArm::R_ARM_THM_MOVW_ABS_NC),
+        48 => Some(RelocationArm::R_ARM_THM_MOVT_ABS),
+        96 => Some(RelocationArm::R_ARM_GOT_PREL),
+        104 => Some(RelocationArm::R_ARM_TLS_GD32),
+        105 => Some(RelocationArm::R_ARM_TLS_LDM32),
+        107 => Some(RelocationArm::R_ARM_TLS_IE32),
+        108 => Some(RelocationArm::R_ARM_TLS_LE32),
+        160 => Some(RelocationArm::R_ARM_IRELATIVE),
+        _ => None
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum RelocationAArch64 {
+    R_AARCH64_NONE,
+    R_AARCH64_ABS64,
+    R_AARCH64_ABS32,
+    R_AARCH64_ABS16,
+    R_AARCH64_PREL64,
+    R_AARCH64_PREL32,
+    R_AARCH64_PREL16,
+    R_AARCH64_MOVW_UABS_G0,
+    R_AARCH64_MOVW_UABS_G0_NC,
+    R_AARCH64_MOVW_UABS_G1,
+    R_AARCH64_MOVW_UABS_G1_NC,
+    R_AARCH64_MOVW_UABS_G2,
+    R_AARCH64_MOVW_UABS_G2_NC,
+    R_AARCH64_MOVW_UABS_G3,
+    R_AARCH64_ADR_PREL_LO21,
+    R_AARCH64_ADR_PREL_PG_HI21,
+    R_AARCH64_ADD_ABS_LO12_NC,
+    R_AARCH64_LDST8_ABS_LO12_NC,
+    R_AARCH64_TSTBR14,
+    R_AARCH64_CONDBR19,
+    R_AARCH64_JUMP26,
+    R_AARCH64_CALL26,
+    R_AARCH64_LDST16_ABS_LO12_NC,
+    R_AARCH64_LDST32_ABS_LO12_NC,
+    R_AARCH64_LDST64_ABS_LO12_NC,
+    R_AARCH64_LDST128_ABS_LO12_NC,
+    R_AARCH64_ADR_GOT_PAGE,
+    R_AARCH64_LD64_GOT_LO12_NC,
+    R_AARCH64_TLSGD_ADR_PAGE21,
+    R_AARCH64_TLSGD_ADD_LO12_NC,
+    R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21,
+    R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC,
+    R_AARCH64_TLSLE_ADD_TPREL_HI12,
+    R_AARCH64_TLSLE_ADD_TPREL_LO12_NC,
+    R_AARCH64_TLSDESC_ADR_PAGE21,
+    R_AARCH64_TLSDESC_LD64_LO12,
+    R_AARCH64_TLSDESC_ADD_LO12,
+    R_AARCH64_TLSDESC_CALL,
+    R_AARCH64_COPY,
+    R_AARCH64_GLOB_DAT,
+    R_AARCH64_JUMP_SLOT,
+    R_AARCH64_RELATIVE,
+    R_AARCH64_TLS_DTPMOD,
+    R_AARCH64_TLS_DTPREL,
+    R_AARCH64_TLS_TPREL,
+    R_AARCH64_TLSDESC,
+    R_AARCH64_IRELATIVE
+}
+
+fn parse_relocation_aarch64(v: u32) -> Option<RelocationAArch64> {
+    match v {
+        0 => Some(RelocationAArch64::R_AARCH64_NONE),
+        257 => Some(RelocationAArch64::R_AARCH64_ABS64),
+        258 => Some(RelocationAArch64::R_AARCH64_ABS32),
+        259 => Some(RelocationAArch64::R_AARCH64_ABS16),
+        260 => Some(RelocationAArch64::R_AARCH64_PREL64),
+        261 => Some(RelocationAArch64::R_AARCH64_PREL32),
+        262 => Some(RelocationAArch64::R_AARCH64_PREL16),
+        263 => Some(RelocationAArch64::R_AARCH64_MOVW_UABS_G0),
+        264 => Some(RelocationAArch64::R_AARCH64_MOVW_UABS_G0_NC),
+        265 => Some(RelocationAArch64::R_AARCH64_MOVW_UABS_G1),
+        266 => Some(RelocationAArch64::R_AARCH64_MOVW_UABS_G1_NC),
+        267 => Some(RelocationAArch64::R_AARCH64_MOVW_UABS_G2),
+        268 => Some(RelocationAArch64::R_AARCH64_MOVW_UABS_G2_NC),
+        269 => Some(RelocationAArch64::R_AARCH64_MOVW_UABS_G3),
+        274 => Some(RelocationAArch64::R_AARCH64_ADR_PREL_LO21),
+        275 => Some(RelocationAArch64::R_AARCH64_ADR_PREL_PG_HI21),
+        277 => Some(RelocationAArch64::R_AARCH64_ADD_ABS_LO12_NC),
+        278 => Some(RelocationAArch64::R_AARCH64_LDST8_ABS_LO12_NC),
+        279 => Some(RelocationAArch64::R_AARCH64_TSTBR14),
+        280 => Some(RelocationAArch64::R_AARCH64_CONDBR19),
+        282 => Some(RelocationAArch64::R_AARCH64_JUMP26),
+        283 => Some(RelocationAArch64::R_AARCH64_CALL26),
+        284 => Some(RelocationAArch64::R_AARCH64_LDST16_ABS_LO12_NC),
+        285 => Some(RelocationAArch64::R_AARCH64_LDST32_ABS_LO12_NC),
+        286 => Some(RelocationAArch64::R_AARCH64_LDST64_ABS_LO12_NC),
+        299 => Some(RelocationAArch64::R_AARCH64_LDST128_ABS_LO12_NC),
+        311 => Some(RelocationAArch64::R_AARCH64_ADR_GOT_PAGE),
+        312 => Some(RelocationAArch64::R_AARCH64_LD64_GOT_LO12_NC),
+        513 => Some(RelocationAArch64::R_AARCH64_TLSGD_ADR_PAGE21),
+        514 => Some(RelocationAArch64::R_AARCH64_TLSGD_ADD_LO12_NC),
+        541 => Some(RelocationAArch64::R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21),
+        542 => Some(RelocationAArch64::R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC),
+        549 => Some(RelocationAArch64::R_AARCH64_TLSLE_ADD_TPREL_HI12),
+        551 => Some(RelocationAArch64::R_AARCH64_TLSLE_ADD_TPREL_LO12_NC),
+        562 => Some(RelocationAArch64::R_AARCH64_TLSDESC_ADR_PAGE21),
+        563 => Some(RelocationAArch64::R_AARCH64_TLSDESC_LD64_LO12),
+        564 => Some(RelocationAArch64::R_AARCH64_TLSDESC_ADD_LO12),
+        569 => Some(RelocationAArch64::R_AARCH64_TLSDESC_CALL),
+        1024 => Some(RelocationAArch64::R_AARCH64_COPY),
+        1025 => Some(RelocationAArch64::R_AARCH64_GLOB_DAT),
+        1026 => Some(RelocationAArch64::R_AARCH64_JUMP_SLOT),
+        1027 => Some(RelocationAArch64::R_AARCH64_RELATIVE),
+        1028 => Some(RelocationAArch64::R_AARCH64_TLS_DTPMOD),
+        1029 => Some(RelocationAArch64::R_AARCH64_TLS_DTPREL),
+        1030 => Some(RelocationAArch64::R_AARCH64_TLS_TPREL),
+        1031 => Some(RelocationAArch64::R_AARCH64_TLSDESC),
+        1032 => Some(RelocationAArch64::R_AARCH64_IRELATIVE),
+        _ => None
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum RelocationRiscV {
+    R_RISCV_NONE,
+    R_RISCV_32,
+    R_RISCV_64,
+    R_RISCV_RELATIVE,
+    R_RISCV_COPY,
+    R_RISCV_JUMP_SLOT,
+    R_RISCV_TLS_DTPMOD32,
+    R_RISCV_TLS_DTPMOD64,
+    R_RISCV_TLS_DTPREL32,
+    R_RISCV_TLS_DTPREL64,
+    R_RISCV_TLS_TPREL32,
+    R_RISCV_TLS_TPREL64,
+    R_RISCV_TLSDESC,
+    R_RISCV_BRANCH,
+    R_RISCV_JAL,
+    R_RISCV_CALL,
+    R_RISCV_CALL_PLT,
+    R_RISCV_GOT_HI20,
+    R_RISCV_TLS_GOT_HI20,
+    R_RISCV_TLS_GD_HI20,
+    R_RISCV_PCREL_HI20,
+    R_RISCV_PCREL_LO12_I,
+    R_RISCV_PCREL_LO12_S,
+    R_RISCV_HI20,
+    R_RISCV_LO12_I,
+    R_RISCV_LO12_S,
+    R_RISCV_TPREL_HI20,
+    R_RISCV_TPREL_LO12_I,
+    R_RISCV_TPREL_LO12_S,
+    R_RISCV_TPREL_ADD,
+    R_RISCV_ADD8,
+    R_RISCV_ADD16,
+    R_RISCV_ADD32,
+    R_RISCV_ADD64,
+    R_RISCV_SUB8,
+    R_RISCV_SUB16,
+    R_RISCV_SUB32,
+    R_RISCV_SUB64,
+    R_RISCV_ALIGN,
+    R_RISCV_RVC_BRANCH,
+    R_RISCV_RVC_JUMP,
+    R_RISCV_RELAX,
+    R_RISCV_SUB6,
+    R_RISCV_SET6,
+    R_RISCV_SET8,
+    R_RISCV_SET16,
+    R_RISCV_SET32,
+    R_RISCV_32_PCREL,
+    R_RISCV_IRELATIVE,
+    R_RISCV_SET_ULEB128,
+    R_RISCV_SUB_ULEB128
+}
+
+fn parse_relocation_riscv(v: u32) -> Option<RelocationRiscV> {
+    match v {
+        0 => Some(RelocationRiscV::R_RISCV_NONE),
+        1 => Some(RelocationRiscV::R_RISCV_32),
+        2 => Some(RelocationRiscV::R_RISCV_64),
+        3 => Some(RelocationRiscV::R_RISCV_RELATIVE),
+        4 => Some(RelocationRiscV::R_RISCV_COPY),
+        5 => Some(RelocationRiscV::R_RISCV_JUMP_SLOT),
+        6 => Some(RelocationRiscV::R_RISCV_TLS_DTPMOD32),
+        7 => Some(RelocationRiscV::R_RISCV_TLS_DTPMOD64),
+        8 => Some(RelocationRiscV::R_RISCV_TLS_DTPREL32),
+        9 => Some(RelocationRiscV::R_RISCV_TLS_DTPREL64),
+        10 => Some(RelocationRiscV::R_RISCV_TLS_TPREL32),
+        11 => Some(RelocationRiscV::R_RISCV_TLS_TPREL64),
+        12 => Some(RelocationRiscV::R_RISCV_TLSDESC),
+        16 => Some(RelocationRiscV::R_RISCV_BRANCH),
+        17 => Some(RelocationRiscV::R_RISCV_JAL),
+        18 => Some(RelocationRiscV::R_RISCV_CALL),
+        19 => Some(RelocationRiscV::R_RISCV_CALL_PLT),
+        20 => Some(RelocationRiscV::R_RISCV_GOT_HI20),
+        21 => Some(RelocationRiscV::R_RISCV_TLS_GOT_HI20),
+        22 => Some(RelocationRiscV::R_RISCV_TLS_GD_HI20),
+        23 => Some(RelocationRiscV::R_RISCV_PCREL_HI20),
+        24 => Some(RelocationRiscV::R_RISCV_PCREL_LO12_I),
+        25 => Some(RelocationRiscV::R_RISCV_PCREL_LO12_S),
+        26 => Some(RelocationRiscV::R_RISCV_HI20),
+        27 => Some(RelocationRiscV::R_RISCV_LO12_I),
+        28 => Some(RelocationRiscV::R_RISCV_LO12_S),
+        29 => Some(RelocationRiscV::R_RISCV_TPREL_HI20),
+        30 => Some(RelocationRiscV::R_RISCV_TPREL_LO12_I),
+        31 => Some(RelocationRiscV::R_RISCV_TPREL_LO12_S),
+        32 => Some(RelocationRiscV::R_RISCV_TPREL_ADD),
+        33 => Some(RelocationRiscV::R_RISCV_ADD8),
+        34 => Some(RelocationRiscV::R_RISCV_ADD16),
+        35 => Some(RelocationRiscV::R_RISCV_ADD32),
+        36 => Some(RelocationRiscV::R_RISCV_ADD64),
+        37 => Some(RelocationRiscV::R_RISCV_SUB8),
+        38 => Some(RelocationRiscV::R_RISCV_SUB16),
+        39 => Some(RelocationRiscV::R_RISCV_SUB32),
+        40 => Some(RelocationRiscV::R_RISCV_SUB64),
+        43 => Some(RelocationRiscV::R_RISCV_ALIGN),
+        44 => Some(RelocationRiscV::R_RISCV_RVC_BRANCH),
+        45 => Some(RelocationRiscV::R_RISCV_RVC_JUMP),
+        51 => Some(RelocationRiscV::R_RISCV_RELAX),
+        52 => Some(RelocationRiscV::R_RISCV_SUB6),
+        53 => Some(RelocationRiscV::R_RISCV_SET6),
+        54 => Some(RelocationRiscV::R_RISCV_SET8),
+        55 => Some(RelocationRiscV::R_RISCV_SET16),
+        56 => Some(RelocationRiscV::R_RISCV_SET32),
+        57 => Some(RelocationRiscV::R_RISCV_32_PCREL),
+        58 => Some(RelocationRiscV::R_RISCV_IRELATIVE),
+        60 => Some(RelocationRiscV::R_RISCV_SET_ULEB128),
+        61 => Some(RelocationRiscV::R_RISCV_SUB_ULEB128),
+        _ => None
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum RelocationPowerPC {
+    R_PPC_NONE,
+    R_PPC_ADDR32,
+    R_PPC_ADDR24,
+    R_PPC_ADDR16,
+    R_PPC_ADDR16_LO,
+    R_PPC_ADDR16_HI,
+    R_PPC_ADDR16_HA,
+    R_PPC_ADDR14,
+    R_PPC_REL24,
+    R_PPC_REL14,
+    R_PPC_GOT16,
+    R_PPC_GOT16_LO,
+    R_PPC_GOT16_HI,
+    R_PPC_GOT16_HA,
+    R_PPC_PLTREL24,
+    R_PPC_COPY,
+    R_PPC_GLOB_DAT,
+    R_PPC_JMP_SLOT,
+    R_PPC_RELATIVE,
+    R_PPC_LOCAL24PC,
+    R_PPC_UADDR32,
+    R_PPC_UADDR16,
+    R_PPC_REL32,
+    R_PPC_PLT32,
+    R_PPC_PLTREL32,
+    R_PPC_SECTOFF,
+    R_PPC_ADDR30,
+    R_PPC_DTPMOD32,
+    R_PPC_TPREL16,
+    R_PPC_TPREL32,
+    R_PPC_DTPREL32,
+    R_PPC_IRELATIVE,
+    R_PPC_REL16,
+    R_PPC_REL16_LO,
+    R_PPC_REL16_HI,
+    R_PPC_REL16_HA
+}
+
+fn parse_relocation_powerpc(v: u32) -> Option<RelocationPowerPC> {
+    match v {
+        0 => Some(RelocationPowerPC::R_PPC_NONE),
+        1 => Some(RelocationPowerPC::R_PPC_ADDR32),
+        2 => Some(RelocationPowerPC::R_PPC_ADDR24),
+        3 => Some(RelocationPowerPC::R_PPC_ADDR16),
+        4 => Some(RelocationPowerPC::R_PPC_ADDR16_LO),
+        5 => Some(RelocationPowerPC::R_PPC_ADDR16_HI),
+        6 => Some(RelocationPowerPC::R_PPC_ADDR16_HA),
+        7 => Some(RelocationPowerPC::R_PPC_ADDR14),
+        10 => Some(RelocationPowerPC::R_PPC_REL24),
+        11 => Some(RelocationPowerPC::R_PPC_REL14),
+        14 => Some(RelocationPowerPC::R_PPC_GOT16),
+        15 => Some(RelocationPowerPC::R_PPC_GOT16_LO),
+        16 => Some(RelocationPowerPC::R_PPC_GOT16_HI),
+        17 => Some(RelocationPowerPC::R_PPC_GOT16_HA),
+        18 => Some(RelocationPowerPC::R_PPC_PLTREL24),
+        19 => Some(RelocationPowerPC::R_PPC_COPY),
+        20 => Some(RelocationPowerPC::R_PPC_GLOB_DAT),
+        21 => Some(RelocationPowerPC::R_PPC_JMP_SLOT),
+        22 => Some(RelocationPowerPC::R_PPC_RELATIVE),
+        23 => Some(RelocationPowerPC::R_PPC_LOCAL24PC),
+        24 => Some(RelocationPowerPC::R_PPC_UADDR32),
+        25 => Some(RelocationPowerPC::R_PPC_UADDR16),
+        26 => Some(RelocationPowerPC::R_PPC_REL32),
+        27 => Some(RelocationPowerPC::R_PPC_PLT32),
+        28 => Some(RelocationPowerPC::R_PPC_PLTREL32),
+        33 => Some(RelocationPowerPC::R_PPC_SECTOFF),
+        37 => Some(RelocationPowerPC::R_PPC_ADDR30),
+        68 => Some(RelocationPowerPC::R_PPC_DTPMOD32),
+        69 => Some(RelocationPowerPC::R_PPC_TPREL16),
+        73 => Some(RelocationPowerPC::R_PPC_TPREL32),
+        78 => Some(RelocationPowerPC::R_PPC_DTPREL32),
+        248 => Some(RelocationPowerPC::R_PPC_IRELATIVE),
+        249 => Some(RelocationPowerPC::R_PPC_REL16),
+        250 => Some(RelocationPowerPC::R_PPC_REL16_LO),
+        251 => Some(RelocationPowerPC::R_PPC_REL16_HI),
+        252 => Some(RelocationPowerPC::R_PPC_REL16_HA),
+        _ => None
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum RelocationMips {
+    R_MIPS_NONE,
+    R_MIPS_16,
+    R_MIPS_32,
+    R_MIPS_REL32,
+    R_MIPS_26,
+    R_MIPS_HI16,
+    R_MIPS_LO16,
+    R_MIPS_GPREL16,
+    R_MIPS_LITERAL,
+    R_MIPS_GOT16,
+    R_MIPS_PC16,
+    R_MIPS_CALL16,
+    R_MIPS_GPREL32,
+    R_MIPS_SHIFT5,
+    R_MIPS_SHIFT6,
+    R_MIPS_64,
+    R_MIPS_GOT_DISP,
+    R_MIPS_GOT_PAGE,
+    R_MIPS_GOT_OFST,
+    R_MIPS_GOT_HI16,
+    R_MIPS_GOT_LO16,
+    R_MIPS_SUB,
+    R_MIPS_HIGHER,
+    R_MIPS_HIGHEST,
+    R_MIPS_CALL_HI16,
+    R_MIPS_CALL_LO16,
+    R_MIPS_JALR,
+    R_MIPS_TLS_DTPMOD32,
+    R_MIPS_TLS_DTPREL32,
+    R_MIPS_TLS_DTPMOD64,
+    R_MIPS_TLS_DTPREL64,
+    R_MIPS_TLS_GD,
+    R_MIPS_TLS_LDM,
+    R_MIPS_TLS_GOTTPREL,
+    R_MIPS_TLS_TPREL32,
+    R_MIPS_TLS_TPREL64,
+    R_MIPS_COPY,
+    R_MIPS_JUMP_SLOT
+}
+
+fn parse_relocation_mips(v: u32) -> Option<RelocationMips> {
+    match v {
+        0 => Some(RelocationMips::R_MIPS_NONE),
+        1 => Some(RelocationMips::R_MIPS_16),
+        2 => Some(RelocationMips::R_MIPS_32),
+        3 => Some(RelocationMips::R_MIPS_REL32),
+        4 => Some(RelocationMips::R_MIPS_26),
+        5 => Some(RelocationMips::R_MIPS_HI16),
+        6 => Some(RelocationMips::R_MIPS_LO16),
+        7 => Some(RelocationMips::R_MIPS_GPREL16),
+        8 => Some(RelocationMips::R_MIPS_LITERAL),
+        9 => Some(RelocationMips::R_MIPS_GOT16),
+        10 => Some(RelocationMips::R_MIPS_PC16),
+        11 => Some(RelocationMips::R_MIPS_CALL16),
+        12 => Some(RelocationMips::R_MIPS_GPREL32),
+        16 => Some(RelocationMips::R_MIPS_SHIFT5),
+        17 => Some(RelocationMips::R_MIPS_SHIFT6),
+        18 => Some(RelocationMips::R_MIPS_64),
+        19 => Some(RelocationMips::R_MIPS_GOT_DISP),
+        20 => Some(RelocationMips::R_MIPS_GOT_PAGE),
+        21 => Some(RelocationMips::R_MIPS_GOT_OFST),
+        22 => Some(RelocationMips::R_MIPS_GOT_HI16),
+        23 => Some(RelocationMips::R_MIPS_GOT_LO16),
+        24 => Some(RelocationMips::R_MIPS_SUB),
+        28 => Some(RelocationMips::R_MIPS_HIGHER),
+        29 => Some(RelocationMips::R_MIPS_HIGHEST),
+        30 => Some(RelocationMips::R_MIPS_CALL_HI16),
+        31 => Some(RelocationMips::R_MIPS_CALL_LO16),
+        37 => Some(RelocationMips::R_MIPS_JALR),
+        38 => Some(RelocationMips::R_MIPS_TLS_DTPMOD32),
+        39 => Some(RelocationMips::R_MIPS_TLS_DTPREL32),
+        40 => Some(RelocationMips::R_MIPS_TLS_DTPMOD64),
+        41 => Some(RelocationMips::R_MIPS_TLS_DTPREL64),
+        42 => Some(RelocationMips::R_MIPS_TLS_GD),
+        43 => Some(RelocationMips::R_MIPS_TLS_LDM),
+        46 => Some(RelocationMips::R_MIPS_TLS_GOTTPREL),
+        47 => Some(RelocationMips::R_MIPS_TLS_TPREL32),
+        48 => Some(RelocationMips::R_MIPS_TLS_TPREL64),
+        126 => Some(RelocationMips::R_MIPS_COPY),
+        127 => Some(RelocationMips::R_MIPS_JUMP_SLOT),
+        _ => None
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum RelocationSparc {
+    R_SPARC_NONE,
+    R_SPARC_8,
+    R_SPARC_16,
+    R_SPARC_32,
+    R_SPARC_DISP8,
+    R_SPARC_DISP16,
+    R_SPARC_DISP32,
+    R_SPARC_WDISP30,
+    R_SPARC_WDISP22,
+    R_SPARC_HI22,
+    R_SPARC_22,
+    R_SPARC_13,
+    R_SPARC_LO10,
+    R_SPARC_GOT10,
+    R_SPARC_GOT13,
+    R_SPARC_GOT22,
+    R_SPARC_PC10,
+    R_SPARC_PC22,
+    R_SPARC_WPLT30,
+    R_SPARC_COPY,
+    R_SPARC_GLOB_DAT,
+    R_SPARC_JMP_SLOT,
+    R_SPARC_RELATIVE,
+    R_SPARC_UA32,
+    R_SPARC_PLT32,
+    R_SPARC_64,
+    R_SPARC_OLO10,
+    R_SPARC_HH22,
+    R_SPARC_HM10,
+    R_SPARC_LM22,
+    R_SPARC_WDISP16,
+    R_SPARC_WDISP19,
+    R_SPARC_7,
+    R_SPARC_5,
+    R_SPARC_6,
+    R_SPARC_DISP64,
+    R_SPARC_PLT64,
+    R_SPARC_UA64,
+    R_SPARC_UA16,
+    R_SPARC_TLS_DTPMOD32,
+    R_SPARC_TLS_DTPMOD64,
+    R_SPARC_TLS_DTPOFF32,
+    R_SPARC_TLS_DTPOFF64,
+    R_SPARC_TLS_TPOFF32,
+    R_SPARC_TLS_TPOFF64,
+    R_SPARC_IRELATIVE
+}
+
+fn parse_relocation_sparc(v: u32) -> Option<RelocationSparc> {
+    match v {
+        0 => Some(RelocationSparc::R_SPARC_NONE),
+        1 => Some(RelocationSparc::R_SPARC_8),
+        2 => Some(RelocationSparc::R_SPARC_16),
+        3 => Some(RelocationSparc::R_SPARC_32),
+        4 => Some(RelocationSparc::R_SPARC_DISP8),
+        5 => Some(RelocationSparc::R_SPARC_DISP16),
+        6 => Some(RelocationSparc::R_SPARC_DISP32),
+        7 => Some(RelocationSparc::R_SPARC_WDISP30),
+        8 => Some(RelocationSparc::R_SPARC_WDISP22),
+        9 => Some(RelocationSparc::R_SPARC_HI22),
+        10 => Some(RelocationSparc::R_SPARC_22),
+        11 => Some(RelocationSparc::R_SPARC_13),
+        12 => Some(RelocationSparc::R_SPARC_LO10),
+        13 => Some(RelocationSparc::R_SPARC_GOT10),
+        14 => Some(RelocationSparc::R_SPARC_GOT13),
+        15 => Some(RelocationSparc::R_SPARC_GOT22),
+        16 => Some(RelocationSparc::R_SPARC_PC10),
+        17 => Some(RelocationSparc::R_SPARC_PC22),
+        18 => Some(RelocationSparc::R_SPARC_WPLT30),
+        19 => Some(RelocationSparc::R_SPARC_COPY),
+        20 => Some(RelocationSparc::R_SPARC_GLOB_DAT),
+        21 => Some(RelocationSparc::R_SPARC_JMP_SLOT),
+        22 => Some(RelocationSparc::R_SPARC_RELATIVE),
+        23 => Some(RelocationSparc::R_SPARC_UA32),
+        24 => Some(RelocationSparc::R_SPARC_PLT32),
+        32 => Some(RelocationSparc::R_SPARC_64),
+        33 => Some(RelocationSparc::R_SPARC_OLO10),
+        34 => Some(RelocationSparc::R_SPARC_HH22),
+        35 => Some(RelocationSparc::R_SPARC_HM10),
+        36 => Some(RelocationSparc::R_SPARC_LM22),
+        40 => Some(RelocationSparc::R_SPARC_WDISP16),
+        41 => Some(RelocationSparc::R_SPARC_WDISP19),
+        43 => Some(RelocationSparc::R_SPARC_7),
+        44 => Some(RelocationSparc::R_SPARC_5),
+        45 => Some(RelocationSparc::R_SPARC_6),
+        46 => Some(RelocationSparc::R_SPARC_DISP64),
+        47 => Some(RelocationSparc::R_SPARC_PLT64),
+        54 => Some(RelocationSparc::R_SPARC_UA64),
+        55 => Some(RelocationSparc::R_SPARC_UA16),
+        74 => Some(RelocationSparc::R_SPARC_TLS_DTPMOD32),
+        75 => Some(RelocationSparc::R_SPARC_TLS_DTPMOD64),
+        76 => Some(RelocationSparc::R_SPARC_TLS_DTPOFF32),
+        77 => Some(RelocationSparc::R_SPARC_TLS_DTPOFF64),
+        78 => Some(RelocationSparc::R_SPARC_TLS_TPOFF32),
+        79 => Some(RelocationSparc::R_SPARC_TLS_TPOFF64),
+        249 => Some(RelocationSparc::R_SPARC_IRELATIVE),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn rela64() {
+        use super::*;
+        use std::fs;
+
+        let content = fs::read("testdata/librelocations.so.1").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let tables = elf.relocation_tables().unwrap();
+        let name = |t: &RelocationTable| elf.section_name(&elf.shtable()[t.section_index()]).unwrap();
+
+        let dyn_table = tables.iter().find(|t| name(t) == ".rela.dyn").unwrap();
+        assert_eq!(dyn_table.kind(), RelocationKind::Rela);
+        let relative = dyn_table.iter().map(Result::unwrap)
+            .filter(|r| r.r#type == RelocationType::X86_64(RelocationX86_64::R_X86_64_RELATIVE))
+            .count();
+        assert!(relative >= 6);
+
+        // PLT relocations point at the imported symbols
+        let plt = tables.iter().find(|t| name(t) == ".rela.plt").unwrap();
+        let dynsym = elf.dynamic_symbol_table().unwrap().unwrap();
+        assert_eq!(plt.symbol_table_index(), elf.shtable().iter().position(|s| s.sh_type == SectionType::SHT_DYNSYM).unwrap());
+        let getenv = plt.iter().map(Result::unwrap).find(|r| dynsym.get(r.symbol as usize).unwrap().name == "getenv").unwrap();
+        assert_eq!(getenv.r#type, RelocationType::X86_64(RelocationX86_64::R_X86_64_JUMP_SLOT));
+        assert_eq!(getenv.r#type.to_string(), "R_X86_64_JUMP_SLOT");
+        assert_eq!(getenv.addend, Some(0));
+    }
+
+    #[test]
+    fn truncated() {
+        use super::*;
+        use std::fs;
+
+        // .rela.dyn cut in the middle of its last entry
+        let mut content = fs::read("testdata/librelocations.so.1").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let index = elf.shtable().iter().position(|sh| elf.section_name(sh) == Ok(".rela.dyn")).unwrap();
+        let section = elf.shtable()[index].clone();
+        let header = elf.header().e_shoff as usize + index * elf.header().e_shentsize as usize;
+        content[header + 0x20..header + 0x28].copy_from_slice(&(section.sh_size - 4).to_le_bytes()); // sh_size
+
+        let elf = Elf64::parse(&content).unwrap();
+        let table = RelocationTable::parse(&elf, index).unwrap();
+        let relocations: Vec<_> = table.iter().collect();
+        assert_eq!(relocations.len() as u64, section.sh_size / RELA64_SIZE as u64);
+        assert!(relocations[..relocations.len() - 1].iter().all(Result::is_ok));
+        let last = section.sh_offset + section.sh_size - RELA64_SIZE as u64;
+        assert_eq!(relocations.last().unwrap().clone().err(),
+                   Some(ParseError::UnexpectedEnd { offset: last, expected: RELA64_SIZE as u64, actual: RELA64_SIZE as u64 - 4 }));
+    }
+
+    #[test]
+    fn relr64() {
+        use super::*;
+        use std::fs;
+
+        let relative = RelocationType::X86_64(RelocationX86_64::R_X86_64_RELATIVE);
+        let offsets = |content: &[u8]| {
+            let elf = Elf64::parse(content).unwrap();
+            let offsets: Vec<u64> = elf.relocation_tables().unwrap().iter()
+                .flat_map(|t| t.iter().map(Result::unwrap))
+                .filter(|r| r.r#type == relative)
+                .map(|r| r.offset)
+                .collect();
+            let pointers = elf.symbols().map(Result::unwrap).find(|s| s.name == "pointers").unwrap().value;
+            (offsets, pointers)
+        };
+        let (unpacked, _) = offsets(&fs::read("testdata/librelocations.so.1").unwrap());
+        let packed_content = fs::read("testdata/librelocations-relr.so.1").unwrap();
+        let elf = Elf64::parse(&packed_content).unwrap();
+        assert!(elf.relocation_tables().unwrap().iter().any(|t| t.kind() == RelocationKind::Relr));
+
+        // Same relocations once decoded, including every non null entry of `pointers`
+        let (packed, pointers) = offsets(&packed_content);
+        assert_eq!(packed.len(), unpacked.len());
+        for i in [0, 1, 2, 3, 4, 8] {
+            assert!(packed.contains(&(pointers + i * 8)));
+        }
+        assert!(!packed.contains(&(pointers + 5 * 8)));
+    }
+
+    #[test]
+    fn rel32() {
+        use super::*;
+        use std::fs;
+
+        let content = fs::read("testdata/librelocations32.so.1").unwrap();
+        let elf = Elf::parse(&content).unwrap();
+        let tables = elf.relocation_tables().unwrap();
+        let table = tables.iter().find(|t| t.kind() == RelocationKind::Rel).unwrap();
+        let relocations: Vec<Relocation> = table.iter().map(Result::unwrap).collect();
+        assert!(relocations.iter().any(|r| r.r#type == RelocationType::I386(RelocationI386::R_386_RELATIVE)));
+        assert!(relocations.iter().all(|r| r.addend.is_none()));
+
+        assert_eq!(parse_relocation_type(MachineType::AArch64, 1026), RelocationType::AArch64(RelocationAArch64::R_AARCH64_JUMP_SLOT));
+        assert_eq!(parse_relocation_type(MachineType::x64, 9999), RelocationType::Unknown(9999));
+        assert_eq!(parse_relocation_type(MachineType::Intel_i960, 1), RelocationType::Unknown(1));
+    }
+
+    #[test]
+    fn mips64_info() {
+        use super::*;
+
+        // Little endian MIPS64 files store the symbol in the low word and the
+        // first type in the high byte, whichever e_machine they use
+        for machine in [MachineType::MIPS_I, MachineType::MIPS_RS3_LE] {
+            let table = RelocationTable {
+                data: &[], offset: 0, kind: RelocationKind::Rela, index: 1, link: 2, info: 0,
+                bits: BitType::_64, endian: Endianness::LittleEndian, machine, entsize: RELA64_SIZE
+            };
+            assert_eq!(table.split_info(0x03000000_00000007), (7, 3));
+            let table = RelocationTable { endian: Endianness::BigEndian, ..table };
+            assert_eq!(table.split_info(0x00000007_00000003), (7, 3));
+        }
+    }
+}
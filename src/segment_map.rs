@@ -0,0 +1,116 @@
+use crate::elf::*;
+
+// .tbss only takes room in the PT_TLS segment, the other segments holding it
+// see it as empty since its memory is allocated per thread
+pub fn is_tbss_special(section: &SectionHeader, segment: &ProgramHeader) -> bool {
+    section.sh_flags.contains(SectionFlags::TLS)
+        && section.sh_type == SectionType::SHT_NOBITS
+        && segment.r#type != ProgramHeaderType::PT_TLS
+}
+
+fn section_size(section: &SectionHeader, segment: &ProgramHeader) -> u64 {
+    if is_tbss_special(section, segment) { 0 } else { section.sh_size }
+}
+
+// Whether `section` lies inside `segment`, following binutils'
+// ELF_SECTION_IN_SEGMENT_1. `check_vma` also compares the addresses of
+// allocated sections, `strict` rejects empty sections sitting at the end
+pub fn section_in_segment(section: &SectionHeader, segment: &ProgramHeader, check_vma: bool, strict: bool) -> bool {
+    use ProgramHeaderType::*;
+
+    let tls = section.sh_flags.contains(SectionFlags::TLS);
+    let alloc = section.sh_flags.contains(SectionFlags::ALLOC);
+    let size = section_size(section, segment);
+
+    // TLS sections only go in TLS, RELRO and loadable segments, the others never go in PT_TLS
+    let type_matches = if tls {
+        matches!(segment.r#type, PT_TLS | GNU_RELRO | PT_LOAD)
+    } else {
+        !matches!(segment.r#type, PT_TLS | PT_PHDR)
+    };
+    // These segments only hold allocated sections
+    let alloc_matches = alloc || !(matches!(segment.r#type, PT_LOAD | PT_DYNAMIC | GNU_EH_FRAME | GNU_STACK | GNU_RELRO | GNU_SFRAME)
+        || segment.r#type.is_gnu_mbind());
+
+    // Sections with content must be inside the file part of the segment
+    let offset_matches = section.sh_type == SectionType::SHT_NOBITS || (
+        section.sh_offset >= segment.offset
+            && (!strict || section.sh_offset - segment.offset <= segment.filesz.wrapping_sub(1))
+            && (section.sh_offset - segment.offset).checked_add(size).is_some_and(|end| end <= segment.filesz)
+    );
+    // Allocated sections must be inside the memory image of the segment
+    let vma_matches = !check_vma || !alloc || (
+        section.sh_addr >= segment.vaddr
+            && (!strict || section.sh_addr - segment.vaddr <= segment.memsz.wrapping_sub(1))
+            && (section.sh_addr - segment.vaddr).checked_add(size).is_some_and(|end| end <= segment.memsz)
+    );
+    // No empty section at the start or the end of PT_DYNAMIC and PT_NOTE
+    let empty_matches = !matches!(segment.r#type, PT_DYNAMIC | PT_NOTE)
+        || section.sh_size != 0
+        || segment.memsz == 0
+        || ((section.sh_type == SectionType::SHT_NOBITS
+                || (section.sh_offset > segment.offset && section.sh_offset - segment.offset < segment.filesz))
+            && (!alloc || (section.sh_addr > segment.vaddr && section.sh_addr - segment.vaddr < segment.memsz)));
+
+    type_matches && alloc_matches && offset_matches && vma_matches && empty_matches
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn readelf_mapping() {
+        use super::*;
+        use crate::elf_file::ElfFile;
+        use std::fs;
+
+        // Thread local data and bss, which end up in PT_TLS, PT_LOAD and PT_GNU_RELRO
+        let content = fs::read("testdata/hello").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let mapping = elf.section_to_segment_mapping();
+        assert_eq!(mapping.len(), elf.phtable().len());
+
+        let names = |segment: usize| -> Vec<&str> {
+            mapping[segment].iter().map(|&i| elf.section_name(&elf.shtable()[i]).unwrap()).collect()
+        };
+        let tls = elf.phtable().iter().position(|ph| ph.r#type == ProgramHeaderType::PT_TLS).unwrap();
+        assert_eq!(names(tls), vec![".tdata", ".tbss"]);
+        for (i, segment) in elf.phtable().iter().enumerate() {
+            if segment.r#type == ProgramHeaderType::PT_LOAD && names(i).contains(&".tdata") {
+                assert!(!names(i).contains(&".tbss"));
+            }
+        }
+
+        // Same result as binutils, testdata/segment_map holds the output of
+        // GNU readelf 2.40 -lW for hello and the patched copies below
+        let same_as_readelf = |content: &[u8], golden: &str| {
+            let elf = Elf64::parse(content).unwrap();
+            let output = fs::read_to_string(format!("testdata/segment_map/{}.txt", golden)).unwrap();
+            let expected: Vec<Vec<&str>> = output.lines()
+                .skip_while(|line| !line.contains("Section to Segment mapping"))
+                .skip(2)
+                .take_while(|line| !line.trim().is_empty())
+                .map(|line| line.split_whitespace().skip(1).collect())
+                .collect();
+            let actual: Vec<Vec<&str>> = elf.section_to_segment_mapping().iter()
+                .map(|segment| segment.iter().map(|&i| elf.section_name(&elf.shtable()[i]).unwrap()).collect())
+                .collect();
+            assert_eq!(actual, expected);
+        };
+        same_as_readelf(&content, "hello");
+
+        // An empty section at the start of PT_NOTE is only in it when it is SHT_NOBITS
+        let note = elf.phtable().iter().find(|ph| ph.r#type == ProgramHeaderType::PT_NOTE).unwrap();
+        let comment = elf.shtable().iter().position(|sh| elf.section_name(sh) == Ok(".comment")).unwrap();
+        let header = elf.header().e_shoff as usize + comment * elf.header().e_shentsize as usize;
+        for (sh_type, golden) in [(SectionType::SHT_PROGBITS, "progbits"), (SectionType::SHT_NOBITS, "nobits")] {
+            let mut patched = content.clone();
+            patched[header + 0x04..header + 0x08].copy_from_slice(&sh_type.value().to_le_bytes());
+            patched[header + 0x18..header + 0x20].copy_from_slice(&note.offset.to_le_bytes());
+            patched[header + 0x20..header + 0x28].fill(0); // sh_size
+            same_as_readelf(&patched, golden);
+
+            let elf = Elf64::parse(&patched).unwrap();
+            assert_eq!(section_in_segment(&elf.shtable()[comment], note, true, true), sh_type == SectionType::SHT_NOBITS);
+        }
+    }
+}
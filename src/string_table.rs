@@ -0,0 +1,56 @@
+use crate::parse_error::ParseError;
+use std::str;
+
+// View over a string table section (.shstrtab, .strtab, .dynstr...)
+// made of NUL-terminated strings referenced by their offset
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct StringTable<'a> {
+    data: &'a [u8],
+    offset: u64 // Where the table is in the file, for the errors
+}
+
+impl<'a> StringTable<'a> {
+    pub fn new(data: &'a [u8], offset: u64) -> Self {
+        Self { data, offset }
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    // Raw bytes of the string at `offset`, without the NUL terminator
+    pub fn get_bytes(&self, offset: usize) -> Result<&'a [u8], ParseError> {
+        let rest = self.data.get(offset..).ok_or(ParseError::StringOutOfBounds { table: self.offset, offset, size: self.data.len() })?;
+        let len = rest.iter().position(|&b| b == 0).ok_or(ParseError::UnterminatedString { offset: self.file_offset(offset) })?;
+        Ok(&rest[..len])
+    }
+
+    pub fn get(&self, offset: usize) -> Result<&'a str, ParseError> {
+        str::from_utf8(self.get_bytes(offset)?).map_err(|_| ParseError::InvalidString { offset: self.file_offset(offset) })
+    }
+
+    fn file_offset(&self, offset: usize) -> u64 {
+        self.offset.saturating_add(offset as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn get_string() {
+        use super::*;
+
+        let table = StringTable::new(b"\0.text\0.data\0bad\xFF\0unterminated", 0x1000);
+        assert_eq!(table.get(0), Ok(""));
+        assert_eq!(table.get(1), Ok(".text"));
+        assert_eq!(table.get(3), Ok("ext"));
+        assert_eq!(table.get(7), Ok(".data"));
+        assert_eq!(table.get(13), Err(ParseError::InvalidString { offset: 0x100D }));
+        assert_eq!(table.get(18), Err(ParseError::UnterminatedString { offset: 0x1012 }));
+        assert_eq!(table.get(100), Err(ParseError::StringOutOfBounds { table: 0x1000, offset: 100, size: 30 }));
+    }
+}
@@ -0,0 +1,334 @@
+use crate::cursor::Cursor;
+use crate::dynamic::DynamicTag;
+use crate::elf::*;
+use crate::elf_file::ElfFile;
+use crate::endianness::Endianness;
+use crate::hash::dynamic_symbol_count;
+use crate::parse_error::ParseError;
+use crate::string_table::StringTable;
+use std::borrow::Cow;
+
+pub const SYMBOL32_SIZE: usize = 16;
+pub const SYMBOL64_SIZE: usize = 24;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum SymbolBinding {
+    STB_LOCAL,
+    STB_GLOBAL,
+    STB_WEAK,
+    // GNU specific
+    STB_GNU_UNIQUE,
+    OsSpecific(u8),
+    ProcessorSpecific(u8),
+    Unknown(u8)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum SymbolType {
+    STT_NOTYPE,
+    STT_OBJECT,
+    STT_FUNC,
+    STT_SECTION,
+    STT_FILE,
+    STT_COMMON,
+    STT_TLS,
+    // GNU specific
+    STT_GNU_IFUNC,
+    OsSpecific(u8),
+    ProcessorSpecific(u8),
+    Unknown(u8)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum SymbolVisibility {
+    STV_DEFAULT,
+    STV_INTERNAL,
+    STV_HIDDEN,
+    STV_PROTECTED
+}
+
+// Section a symbol is defined relative to
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum SymbolSection {
+    SHN_UNDEF,
+    SHN_ABS,
+    SHN_COMMON,
+    Section(usize), // Also used for indexes resolved through SHN_XINDEX
+    Reserved(u16)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Symbol<'a> {
+    pub name: &'a str,
+    pub value: u64,
+    pub size: u64,
+    pub binding: SymbolBinding,
+    pub r#type: SymbolType,
+    pub visibility: SymbolVisibility,
+    pub section: SymbolSection,
+    pub st_name: u32,
+    pub st_info: u8,
+    pub st_other: u8
+}
+
+impl Symbol<'_> {
+    pub fn is_undefined(&self) -> bool {
+        self.section == SymbolSection::SHN_UNDEF
+    }
+}
+
+pub fn parse_symbol_binding(v: u8) -> SymbolBinding {
+    match v {
+        0 => SymbolBinding::STB_LOCAL,
+        1 => SymbolBinding::STB_GLOBAL,
+        2 => SymbolBinding::STB_WEAK,
+        10 => SymbolBinding::STB_GNU_UNIQUE,
+        11..=12 => SymbolBinding::OsSpecific(v),
+        13..=15 => SymbolBinding::ProcessorSpecific(v),
+        _ => SymbolBinding::Unknown(v)
+    }
+}
+
+pub fn parse_symbol_type(v: u8) -> SymbolType {
+    match v {
+        0 => SymbolType::STT_NOTYPE,
+        1 => SymbolType::STT_OBJECT,
+        2 => SymbolType::STT_FUNC,
+        3 => SymbolType::STT_SECTION,
+        4 => SymbolType::STT_FILE,
+        5 => SymbolType::STT_COMMON,
+        6 => SymbolType::STT_TLS,
+        10 => SymbolType::STT_GNU_IFUNC,
+        11..=12 => SymbolType::OsSpecific(v),
+        13..=15 => SymbolType::ProcessorSpecific(v),
+        _ => SymbolType::Unknown(v)
+    }
+}
+
+pub fn parse_symbol_visibility(v: u8) -> SymbolVisibility {
+    match v & 0x3 {
+        0 => SymbolVisibility::STV_DEFAULT,
+        1 => SymbolVisibility::STV_INTERNAL,
+        2 => SymbolVisibility::STV_HIDDEN,
+        _ => SymbolVisibility::STV_PROTECTED
+    }
+}
+
+// View over a SHT_SYMTAB or SHT_DYNSYM section
+#[derive(Debug, Clone)]
+pub struct SymbolTable<'a> {
+    data: &'a [u8],
+    offset: u64, // Where the symbols are in the file
+    strtab: StringTable<'a>,
+    shndx: Option<(&'a [u8], u64)>, // Content of the matching SHT_SYMTAB_SHNDX section and where it is
+    bits: BitType,
+    endian: Endianness,
+    entsize: usize
+}
+
+impl<'a> SymbolTable<'a> {
+    // Symbol table stored in the section at `index`, using the string table it links to
+    pub fn parse<E: ElfFile<'a> + ?Sized>(elf: &E, index: usize) -> Result<Self, ParseError> {
+        let section = elf.section_header(index).ok_or(ParseError::InvalidSectionIndex(index))?;
+        let strtab = elf.section_header(section.sh_link as usize).ok_or(ParseError::InvalidSectionLink(section.sh_link))?;
+        let bits = elf.bits();
+        let entsize = symbol_size(bits, section.sh_entsize, index, section.sh_offset)?;
+
+        // Extended section indexes live in a section linking back to this one
+        let shndx = elf.section_headers()
+            .find(|s| s.sh_type == SectionType::SHT_SYMTAB_SHNDX && s.sh_link as usize == index)
+            .map(|s| elf.section_data(&s).map(|data| (data, s.sh_offset)))
+            .transpose()?;
+
+        Ok(Self {
+            data: elf.section_data(&section)?,
+            offset: section.sh_offset,
+            strtab: StringTable::new(elf.section_data(&strtab)?, strtab.sh_offset),
+            shndx,
+            bits,
+            endian: elf.endianness(),
+            entsize
+        })
+    }
+
+    // Dynamic symbol table found through DT_SYMTAB and DT_STRTAB, for files
+    // without section headers. Only the hash tables tell its length
+    pub fn from_dynamic<E: ElfFile<'a> + ?Sized>(elf: &E) -> Result<Option<Self>, ParseError> {
+        let Some(dynamic) = elf.dynamic()? else { return Ok(None) };
+        let (Some(addr), Some(strtab)) = (dynamic.get(DynamicTag::DT_SYMTAB), dynamic.strtab()) else { return Ok(None) };
+        let Some(count) = dynamic_symbol_count(elf)? else { return Ok(None) };
+        let bits = elf.bits();
+        let offset = elf.vaddr_to_offset(addr)?;
+        let entsize = symbol_size(bits, dynamic.get(DynamicTag::DT_SYMENT).unwrap_or(0), 0, offset)?;
+        let len = count.checked_mul(entsize).ok_or(ParseError::UnmappedAddress(addr))?;
+
+        // The loader reads the symbols in place, they can't be zero-filled
+        let data = match elf.read_at_vaddr(addr, len)? {
+            Cow::Borrowed(data) => data,
+            Cow::Owned(_) => return Err(ParseError::ZeroFilledAddress(addr))
+        };
+        Ok(Some(Self { data, offset, strtab, shndx: None, bits, endian: elf.endianness(), entsize }))
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len() / self.entsize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn strtab(&self) -> StringTable<'a> {
+        self.strtab
+    }
+
+    pub fn get(&self, index: usize) -> Result<Symbol<'a>, ParseError> {
+        if index >= self.len() { return Err(ParseError::SymbolOutOfBounds { index, count: self.len() }) }
+        let fields = || -> Result<_, ParseError> {
+            let mut c = Cursor::entry(self.data, self.offset, 0, index, self.entsize, self.endian)?;
+            // Fields are not in the same order on both classes
+            Ok(match self.bits {
+                BitType::_32 => {
+                    let (name, value, size) = (c.read32()?, c.read32()? as u64, c.read32()? as u64);
+                    (name, value, size, c.read8()?, c.read8()?, c.read16()?)
+                },
+                BitType::_64 => {
+                    let (name, info, other, shndx) = (c.read32()?, c.read8()?, c.read8()?, c.read16()?);
+                    (name, c.read64()?, c.read64()?, info, other, shndx)
+                }
+            })
+        };
+        let (st_name, st_value, st_size, st_info, st_other, st_shndx) = fields().map_err(|e| match e {
+            ParseError::UnexpectedEnd { offset, expected, actual } => ParseError::TooSmallSymbolTable { index, offset, expected, actual },
+            e => e
+        })?;
+
+        let section = match st_shndx {
+            SHN_UNDEF => SymbolSection::SHN_UNDEF,
+            SHN_ABS => SymbolSection::SHN_ABS,
+            SHN_COMMON => SymbolSection::SHN_COMMON,
+            SHN_XINDEX => SymbolSection::Section(self.extended_index(index)?),
+            i if i >= SHN_LORESERVE => SymbolSection::Reserved(i),
+            i => SymbolSection::Section(i as usize)
+        };
+
+        Ok(Symbol {
+            name: self.strtab.get(st_name as usize)?,
+            value: st_value,
+            size: st_size,
+            binding: parse_symbol_binding(st_info >> 4),
+            r#type: parse_symbol_type(st_info & 0xF),
+            visibility: parse_symbol_visibility(st_other),
+            section,
+            st_name,
+            st_info,
+            st_other
+        })
+    }
+
+    // Real section index of a symbol marked SHN_XINDEX
+    fn extended_index(&self, index: usize) -> Result<usize, ParseError> {
+        let (shndx, offset) = self.shndx.ok_or(ParseError::MissingExtendedSectionIndex { symbol: index })?;
+        Ok(Cursor::entry(shndx, offset, 0, index, 4, self.endian)?.read32()? as usize)
+    }
+
+    pub fn iter(&self) -> SymbolIterator<'a> {
+        SymbolIterator {
+            table: self.clone(),
+            index: 0
+        }
+    }
+}
+
+// Size of one symbol given the entry size of the table, 0 meaning the
+// class size. The errors name the section at `index` found at `offset`,
+// index 0 for the table found through DT_SYMTAB
+fn symbol_size(bits: BitType, entsize: u64, index: usize, offset: u64) -> Result<usize, ParseError> {
+    let min_size = match bits {
+        BitType::_32 => SYMBOL32_SIZE,
+        BitType::_64 => SYMBOL64_SIZE
+    };
+    let size = match entsize {
+        0 => min_size,
+        n => usize::try_from(n).unwrap_or(usize::MAX)
+    };
+    if size < min_size { return Err(ParseError::TooSmallSymbol { index, offset, expected: min_size, actual: entsize }) }
+    Ok(size)
+}
+
+pub struct SymbolIterator<'a> {
+    table: SymbolTable<'a>,
+    index: usize
+}
+
+impl<'a> Iterator for SymbolIterator<'a> {
+    type Item = Result<Symbol<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.table.len() { return None }
+        let symbol = self.table.get(self.index);
+        self.index += 1;
+        Some(symbol)
+    }
+}
+
+impl<'a> IntoIterator for &SymbolTable<'a> {
+    type Item = Result<Symbol<'a>, ParseError>;
+    type IntoIter = SymbolIterator<'a>;
+
+    fn into_iter(self) -> SymbolIterator<'a> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn symbols64() {
+        use super::*;
+        use std::fs;
+
+        let content = fs::read("testdata/symbols").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+
+        let symtab = elf.symbol_table().unwrap().unwrap();
+        let symbols: Vec<Symbol> = symtab.iter().map(Result::unwrap).collect();
+        assert_eq!(symbols[0].name, ""); // Null symbol
+
+        let find = |name: &str| *symbols.iter().find(|s| s.name == name).unwrap();
+        let main = find("main");
+        assert_eq!(main.r#type, SymbolType::STT_FUNC);
+        assert_eq!(main.binding, SymbolBinding::STB_GLOBAL);
+        let text = elf.shtable().iter().position(|s| elf.section_name(s).unwrap() == ".text").unwrap();
+        assert_eq!(main.section, SymbolSection::Section(text));
+        assert_eq!(find("global_var").r#type, SymbolType::STT_OBJECT);
+        assert_eq!(find("global_var").size, 4);
+        assert_eq!(find("local_var").binding, SymbolBinding::STB_LOCAL);
+        assert_eq!(find("weak_fn").binding, SymbolBinding::STB_WEAK);
+
+        // Imported functions are undefined in .dynsym
+        let getenv = elf.dynamic_symbols().map(Result::unwrap).find(|s| s.name == "getenv").unwrap();
+        assert!(getenv.is_undefined());
+        assert_eq!(getenv.r#type, SymbolType::STT_FUNC);
+    }
+
+    #[test]
+    fn symbols32() {
+        use super::*;
+        use std::fs;
+
+        let content = fs::read("testdata/static32").unwrap();
+        let elf = Elf::parse(&content).unwrap();
+
+        let counter = elf.symbols().map(Result::unwrap).find(|s| s.name == "counter").unwrap();
+        assert_eq!(counter.size, 4);
+        assert_eq!(counter.r#type, SymbolType::STT_OBJECT);
+        let data = elf.section_by_name(".data").unwrap();
+        assert_eq!(counter.value, data.sh_addr);
+        assert_eq!(elf.dynamic_symbols().count(), 0);
+    }
+}
@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+use std::{env, fs, process};
+
+// Empty directory of its own for the files test `name` writes
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("elf-parser-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,301 @@
+use crate::cursor::Cursor;
+use crate::elf::*;
+use crate::elf_file::ElfFile;
+use crate::endianness::Endianness;
+use crate::parse_error::ParseError;
+use crate::string_table::StringTable;
+use crate::symbol::{Symbol, SymbolSection};
+use std::fmt;
+
+// Special .gnu.version values
+pub const VER_NDX_LOCAL: u16 = 0;
+pub const VER_NDX_GLOBAL: u16 = 1;
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+pub const VERSYM_VERSION: u16 = 0x7FFF;
+
+// Flags of definitions and requirements
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+
+pub const VERDEF_SIZE: usize = 20;
+pub const VERDAUX_SIZE: usize = 8;
+pub const VERNEED_SIZE: usize = 16;
+pub const VERNAUX_SIZE: usize = 16;
+
+// Entry of .gnu.version_d, the first name is the version itself and the
+// following ones are the versions it inherits from
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct VersionDefinition<'a> {
+    pub offset: usize, // Position in the section
+    pub version: u16,
+    pub index: u16,
+    pub flags: u16,
+    pub hash: u32,
+    pub name: &'a str,
+    pub parents: Vec<&'a str>
+}
+
+// Version of a library needed by the file
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct RequiredVersion<'a> {
+    pub offset: usize,
+    pub index: u16,
+    pub flags: u16,
+    pub hash: u32,
+    pub name: &'a str
+}
+
+// Entry of .gnu.version_r, one per needed library
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct VersionRequirement<'a> {
+    pub offset: usize,
+    pub version: u16,
+    pub file: &'a str,
+    pub versions: Vec<RequiredVersion<'a>>
+}
+
+// Version attached to a dynamic symbol through .gnu.version
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct SymbolVersion<'a> {
+    pub index: u16,
+    pub name: &'a str,
+    pub file: Option<&'a str>, // Library providing it for required versions
+    pub hidden: bool
+}
+
+// Dynamic symbol with its version, displays as `name@VERSION` or
+// `name@@VERSION` for the default version of a defined symbol, like nm
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct VersionedSymbol<'a> {
+    pub symbol: Symbol<'a>,
+    pub version: Option<SymbolVersion<'a>>
+}
+
+impl VersionedSymbol<'_> {
+    // The absolute symbol the linker emits for each version definition
+    pub fn is_version_definition(&self) -> bool {
+        self.version.is_some_and(|version| version.file.is_none() && version.name == self.symbol.name)
+            && self.symbol.section == SymbolSection::SHN_ABS
+    }
+
+    pub fn is_default(&self) -> bool {
+        match self.version {
+            Some(version) => !version.hidden && version.file.is_none() && !self.symbol.is_undefined(),
+            None => false
+        }
+    }
+}
+
+impl fmt::Display for VersionedSymbol<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.version {
+            Some(_) if self.is_version_definition() => write!(f, "{}", self.symbol.name),
+            Some(version) if self.is_default() => write!(f, "{}@@{}", self.symbol.name, version.name),
+            Some(version) => write!(f, "{}@{}", self.symbol.name, version.name),
+            None => write!(f, "{}", self.symbol.name)
+        }
+    }
+}
+
+// `base` is where `data` is in the file, the errors report file offsets
+fn too_small(base: u64, offset: usize) -> ParseError {
+    ParseError::TooSmallVersion { offset: base.saturating_add(offset as u64) }
+}
+
+// Cursor on the `size` bytes entry at `offset`, the whole entry must be there
+fn entry(data: &[u8], base: u64, offset: usize, size: usize, endian: Endianness) -> Result<Cursor<'_>, ParseError> {
+    if offset.checked_add(size).is_none_or(|end| end > data.len()) { return Err(too_small(base, offset)) }
+    Ok(Cursor::with_base(data, base, offset, endian))
+}
+
+// Offset of the next entry of a chain, `None` at the end
+fn next_offset(base: u64, offset: usize, next: u32) -> Result<Option<usize>, ParseError> {
+    match next {
+        0 => Ok(None),
+        n => offset.checked_add(n as usize).map(Some).ok_or(too_small(base, offset))
+    }
+}
+
+// String table linked by a version section
+fn linked_strtab<'a, E: ElfFile<'a> + ?Sized>(elf: &E, section: &SectionHeader) -> Result<StringTable<'a>, ParseError> {
+    let strtab = elf.section_header(section.sh_link as usize).ok_or(ParseError::InvalidSectionLink(section.sh_link))?;
+    Ok(StringTable::new(elf.section_data(&strtab)?, strtab.sh_offset))
+}
+
+pub fn parse_version_definitions<'a>(data: &'a [u8], base: u64, count: usize, strtab: StringTable<'a>, endian: Endianness)
+    -> Result<Vec<VersionDefinition<'a>>, ParseError>
+{
+    let mut definitions = Vec::new();
+    let mut offset = Some(0usize);
+    while let Some(start) = offset {
+        if definitions.len() == count { break }
+        // vd_version, vd_flags, vd_ndx, vd_cnt, vd_hash, vd_aux, vd_next
+        let mut c = entry(data, base, start, VERDEF_SIZE, endian)?;
+        let (version, flags, index, aux_count) = (c.read16()?, c.read16()?, c.read16()?, c.read16()?);
+        let (hash, aux, next) = (c.read32()?, c.read32()?, c.read32()?);
+
+        let mut names = Vec::new();
+        let mut aux_offset = next_offset(base, start, aux)?;
+        while let Some(aux_start) = aux_offset {
+            if names.len() == aux_count as usize { break }
+            // vda_name, vda_next
+            let mut c = entry(data, base, aux_start, VERDAUX_SIZE, endian)?;
+            names.push(strtab.get(c.read32()? as usize)?);
+            aux_offset = next_offset(base, aux_start, c.read32()?)?;
+        }
+        if names.is_empty() { return Err(too_small(base, start)) }
+
+        let name = names.remove(0);
+        definitions.push(VersionDefinition { offset: start, version, index, flags, hash, name, parents: names });
+        offset = next_offset(base, start, next)?;
+    }
+    Ok(definitions)
+}
+
+pub fn parse_version_requirements<'a>(data: &'a [u8], base: u64, count: usize, strtab: StringTable<'a>, endian: Endianness)
+    -> Result<Vec<VersionRequirement<'a>>, ParseError>
+{
+    let mut requirements = Vec::new();
+    let mut offset = Some(0usize);
+    while let Some(start) = offset {
+        if requirements.len() == count { break }
+        // vn_version, vn_cnt, vn_file, vn_aux, vn_next
+        let mut c = entry(data, base, start, VERNEED_SIZE, endian)?;
+        let (version, aux_count) = (c.read16()?, c.read16()?);
+        let file = strtab.get(c.read32()? as usize)?;
+        let (aux, next) = (c.read32()?, c.read32()?);
+
+        let mut versions = Vec::new();
+        let mut aux_offset = next_offset(base, start, aux)?;
+        while let Some(aux_start) = aux_offset {
+            if versions.len() == aux_count as usize { break }
+            // vna_hash, vna_flags, vna_other, vna_name, vna_next
+            let mut c = entry(data, base, aux_start, VERNAUX_SIZE, endian)?;
+            versions.push(RequiredVersion {
+                offset: aux_start,
+                hash: c.read32()?,
+                flags: c.read16()?,
+                index: c.read16()?,
+                name: strtab.get(c.read32()? as usize)?
+            });
+            aux_offset = next_offset(base, aux_start, c.read32()?)?;
+        }
+
+        requirements.push(VersionRequirement { offset: start, version, file, versions });
+        offset = next_offset(base, start, next)?;
+    }
+    Ok(requirements)
+}
+
+// Content of the .gnu.version, .gnu.version_d and .gnu.version_r sections
+pub struct SymbolVersions<'a> {
+    versym: &'a [u8],
+    versym_offset: u64,
+    definitions: Vec<VersionDefinition<'a>>,
+    requirements: Vec<VersionRequirement<'a>>,
+    endian: Endianness
+}
+
+impl<'a> SymbolVersions<'a> {
+    // `None` when the file has no .gnu.version section
+    pub fn parse<E: ElfFile<'a> + ?Sized>(elf: &E) -> Result<Option<Self>, ParseError> {
+        let (versym, versym_offset) = match elf.section_index_by_type(SectionType::SHT_GNU_versym).and_then(|i| elf.section_header(i)) {
+            Some(section) => (elf.section_data(&section)?, section.sh_offset),
+            None => return Ok(None)
+        };
+        let endian = elf.endianness();
+
+        // sh_info holds the number of entries of both sections
+        let definitions = match elf.section_index_by_type(SectionType::SHT_GNU_verdef).and_then(|i| elf.section_header(i)) {
+            Some(section) => parse_version_definitions(elf.section_data(&section)?, section.sh_offset, section.sh_info as usize,
+                                                       linked_strtab(elf, &section)?, endian)?,
+            None => Vec::new()
+        };
+        let requirements = match elf.section_index_by_type(SectionType::SHT_GNU_verneed).and_then(|i| elf.section_header(i)) {
+            Some(section) => parse_version_requirements(elf.section_data(&section)?, section.sh_offset, section.sh_info as usize,
+                                                        linked_strtab(elf, &section)?, endian)?,
+            None => Vec::new()
+        };
+
+        Ok(Some(Self { versym, versym_offset, definitions, requirements, endian }))
+    }
+
+    pub fn definitions(&self) -> &[VersionDefinition<'a>] {
+        &self.definitions
+    }
+
+    // Versions needed from each library
+    pub fn requirements(&self) -> &[VersionRequirement<'a>] {
+        &self.requirements
+    }
+
+    // Raw .gnu.version entry of the dynamic symbol at `index`
+    pub fn versym(&self, index: usize) -> Result<u16, ParseError> {
+        entry(self.versym, self.versym_offset, index.saturating_mul(2), 2, self.endian)?.read16()
+    }
+
+    // Version of the dynamic symbol at `index`, `None` for local and global symbols
+    pub fn version_of(&self, index: usize) -> Result<Option<SymbolVersion<'a>>, ParseError> {
+        let versym = self.versym(index)?;
+        let version = versym & VERSYM_VERSION;
+        let hidden = versym & VERSYM_HIDDEN != 0;
+        if version == VER_NDX_LOCAL || version == VER_NDX_GLOBAL { return Ok(None) }
+
+        if let Some(definition) = self.definitions.iter().find(|d| d.index == version) {
+            return Ok(Some(SymbolVersion { index: version, name: definition.name, file: None, hidden }));
+        }
+        for requirement in &self.requirements {
+            if let Some(required) = requirement.versions.iter().find(|v| v.index == version) {
+                return Ok(Some(SymbolVersion { index: version, name: required.name, file: Some(requirement.file), hidden }));
+            }
+        }
+        Err(ParseError::InvalidVersionIndex(version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn versions() {
+        use super::*;
+        use std::fs;
+
+        // Two versions of `foo`, the old one is only reachable as foo@VERS_1
+        let content = fs::read("testdata/libversions.so.1").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+
+        let versions = elf.symbol_versions().unwrap().unwrap();
+        let definitions = versions.definitions();
+        assert!(definitions[0].flags & VER_FLG_BASE != 0);
+        assert_eq!(definitions[0].name, "libversions.so.1");
+        let vers_2 = definitions.iter().find(|d| d.name == "VERS_2").unwrap();
+        assert_eq!(vers_2.parents, vec!["VERS_1"]);
+
+        let libc = versions.requirements().iter().find(|r| r.file == "libc.so.6").unwrap();
+        assert!(libc.versions.iter().any(|v| v.name.starts_with("GLIBC_2.")));
+
+        let names: Vec<String> = elf.versioned_dynamic_symbols().unwrap().iter().map(|s| s.to_string()).collect();
+        assert!(names.contains(&"foo@VERS_1".to_string()));
+        assert!(names.contains(&"foo@@VERS_2".to_string()));
+        assert!(names.contains(&"bar@@VERS_1".to_string()));
+        assert!(names.iter().any(|n| n.starts_with("getenv@GLIBC_2.")));
+    }
+
+    #[test]
+    fn requirements() {
+        use super::*;
+
+        let content = std::fs::read("testdata/hello").unwrap();
+        let elf = Elf64::parse(&content).unwrap();
+        let versions = elf.symbol_versions().unwrap().unwrap();
+        let libc = versions.requirements().iter().find(|r| r.file == "libc.so.6").unwrap();
+        assert!(libc.versions.iter().any(|v| v.name == "GLIBC_2.2.5"));
+
+        // Every versioned symbol points to a version the file requires
+        for symbol in elf.versioned_dynamic_symbols().unwrap() {
+            if let Some(version) = symbol.version {
+                assert!(version.file.is_none_or(|file| versions.requirements().iter().any(|r| r.file == file)));
+            }
+        }
+    }
+}
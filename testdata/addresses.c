@@ -0,0 +1,6 @@
+// Fixture of the address translation tests, initialized data followed by a
+// zero-filled array in the same segment, built with gcc 12.2:
+// gcc testdata/addresses.c -o testdata/addresses
+
+int value = 0x12345678; char buffer[4096];
+int main(void) { return value + buffer[0]; }
@@ -0,0 +1,11 @@
+// Fixture of the debug file tests, a stripped file and its debug file,
+// built with gcc 12.2 and binutils 2.40:
+// gcc -g -Wl,--build-id testdata/debug.c -o testdata/debug
+// objcopy --only-keep-debug testdata/debug testdata/debug.debug
+// objcopy --strip-debug --add-gnu-debuglink=testdata/debug.debug testdata/debug
+// Same for 32 bits files:
+// gcc -m32 -nostdlib -static -e main -g -Wl,--build-id testdata/debug.c -o testdata/debug32
+// objcopy --only-keep-debug testdata/debug32 testdata/debug32.debug
+// objcopy --strip-debug --add-gnu-debuglink=testdata/debug32.debug testdata/debug32
+
+int main(void) { return 0; }
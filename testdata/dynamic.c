@@ -0,0 +1,8 @@
+// Fixture of the dynamic section tests, built with gcc 12.2:
+// gcc -shared -fPIC -Wl,-soname,libdynamic64.so.1 -Wl,-rpath,/opt/dynamic64 -Wl,--enable-new-dtags -Wl,-z,now
+//     testdata/dynamic.c -o testdata/libdynamic64.so.1
+// gcc -m32 -nostdlib -shared -fPIC -Wl,-soname,libdynamic32.so testdata/dynamic.c -o testdata/libdynamic32.so.1
+
+char *getenv(const char *name);
+
+int answer(void) { return getenv("HOME") != 0; }
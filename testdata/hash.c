@@ -0,0 +1,206 @@
+// Fixture of the hash table tests, with enough exports to fill several
+// buckets, built with gcc 12.2:
+// gcc -shared -fPIC -Wl,--hash-style=both testdata/hash.c -o testdata/libhash.so.1
+// gcc -shared -fPIC -Wl,--hash-style=gnu testdata/hash.c -o testdata/libhash-gnu.so.1
+// gcc -shared -fPIC -Wl,--hash-style=sysv testdata/hash.c -o testdata/libhash-sysv.so.1
+
+int export_0(void) { return 0; }
+int export_1(void) { return 1; }
+int export_2(void) { return 2; }
+int export_3(void) { return 3; }
+int export_4(void) { return 4; }
+int export_5(void) { return 5; }
+int export_6(void) { return 6; }
+int export_7(void) { return 7; }
+int export_8(void) { return 8; }
+int export_9(void) { return 9; }
+int export_10(void) { return 10; }
+int export_11(void) { return 11; }
+int export_12(void) { return 12; }
+int export_13(void) { return 13; }
+int export_14(void) { return 14; }
+int export_15(void) { return 15; }
+int export_16(void) { return 16; }
+int export_17(void) { return 17; }
+int export_18(void) { return 18; }
+int export_19(void) { return 19; }
+int export_20(void) { return 20; }
+int export_21(void) { return 21; }
+int export_22(void) { return 22; }
+int export_23(void) { return 23; }
+int export_24(void) { return 24; }
+int export_25(void) { return 25; }
+int export_26(void) { return 26; }
+int export_27(void) { return 27; }
+int export_28(void) { return 28; }
+int export_29(void) { return 29; }
+int export_30(void) { return 30; }
+int export_31(void) { return 31; }
+int export_32(void) { return 32; }
+int export_33(void) { return 33; }
+int export_34(void) { return 34; }
+int export_35(void) { return 35; }
+int export_36(void) { return 36; }
+int export_37(void) { return 37; }
+int export_38(void) { return 38; }
+int export_39(void) { return 39; }
+int export_40(void) { return 40; }
+int export_41(void) { return 41; }
+int export_42(void) { return 42; }
+int export_43(void) { return 43; }
+int export_44(void) { return 44; }
+int export_45(void) { return 45; }
+int export_46(void) { return 46; }
+int export_47(void) { return 47; }
+int export_48(void) { return 48; }
+int export_49(void) { return 49; }
+int export_50(void) { return 50; }
+int export_51(void) { return 51; }
+int export_52(void) { return 52; }
+int export_53(void) { return 53; }
+int export_54(void) { return 54; }
+int export_55(void) { return 55; }
+int export_56(void) { return 56; }
+int export_57(void) { return 57; }
+int export_58(void) { return 58; }
+int export_59(void) { return 59; }
+int export_60(void) { return 60; }
+int export_61(void) { return 61; }
+int export_62(void) { return 62; }
+int export_63(void) { return 63; }
+int export_64(void) { return 64; }
+int export_65(void) { return 65; }
+int export_66(void) { return 66; }
+int export_67(void) { return 67; }
+int export_68(void) { return 68; }
+int export_69(void) { return 69; }
+int export_70(void) { return 70; }
+int export_71(void) { return 71; }
+int export_72(void) { return 72; }
+int export_73(void) { return 73; }
+int export_74(void) { return 74; }
+int export_75(void) { return 75; }
+int export_76(void) { return 76; }
+int export_77(void) { return 77; }
+int export_78(void) { return 78; }
+int export_79(void) { return 79; }
+int export_80(void) { return 80; }
+int export_81(void) { return 81; }
+int export_82(void) { return 82; }
+int export_83(void) { return 83; }
+int export_84(void) { return 84; }
+int export_85(void) { return 85; }
+int export_86(void) { return 86; }
+int export_87(void) { return 87; }
+int export_88(void) { return 88; }
+int export_89(void) { return 89; }
+int export_90(void) { return 90; }
+int export_91(void) { return 91; }
+int export_92(void) { return 92; }
+int export_93(void) { return 93; }
+int export_94(void) { return 94; }
+int export_95(void) { return 95; }
+int export_96(void) { return 96; }
+int export_97(void) { return 97; }
+int export_98(void) { return 98; }
+int export_99(void) { return 99; }
+int export_100(void) { return 100; }
+int export_101(void) { return 101; }
+int export_102(void) { return 102; }
+int export_103(void) { return 103; }
+int export_104(void) { return 104; }
+int export_105(void) { return 105; }
+int export_106(void) { return 106; }
+int export_107(void) { return 107; }
+int export_108(void) { return 108; }
+int export_109(void) { return 109; }
+int export_110(void) { return 110; }
+int export_111(void) { return 111; }
+int export_112(void) { return 112; }
+int export_113(void) { return 113; }
+int export_114(void) { return 114; }
+int export_115(void) { return 115; }
+int export_116(void) { return 116; }
+int export_117(void) { return 117; }
+int export_118(void) { return 118; }
+int export_119(void) { return 119; }
+int export_120(void) { return 120; }
+int export_121(void) { return 121; }
+int export_122(void) { return 122; }
+int export_123(void) { return 123; }
+int export_124(void) { return 124; }
+int export_125(void) { return 125; }
+int export_126(void) { return 126; }
+int export_127(void) { return 127; }
+int export_128(void) { return 128; }
+int export_129(void) { return 129; }
+int export_130(void) { return 130; }
+int export_131(void) { return 131; }
+int export_132(void) { return 132; }
+int export_133(void) { return 133; }
+int export_134(void) { return 134; }
+int export_135(void) { return 135; }
+int export_136(void) { return 136; }
+int export_137(void) { return 137; }
+int export_138(void) { return 138; }
+int export_139(void) { return 139; }
+int export_140(void) { return 140; }
+int export_141(void) { return 141; }
+int export_142(void) { return 142; }
+int export_143(void) { return 143; }
+int export_144(void) { return 144; }
+int export_145(void) { return 145; }
+int export_146(void) { return 146; }
+int export_147(void) { return 147; }
+int export_148(void) { return 148; }
+int export_149(void) { return 149; }
+int export_150(void) { return 150; }
+int export_151(void) { return 151; }
+int export_152(void) { return 152; }
+int export_153(void) { return 153; }
+int export_154(void) { return 154; }
+int export_155(void) { return 155; }
+int export_156(void) { return 156; }
+int export_157(void) { return 157; }
+int export_158(void) { return 158; }
+int export_159(void) { return 159; }
+int export_160(void) { return 160; }
+int export_161(void) { return 161; }
+int export_162(void) { return 162; }
+int export_163(void) { return 163; }
+int export_164(void) { return 164; }
+int export_165(void) { return 165; }
+int export_166(void) { return 166; }
+int export_167(void) { return 167; }
+int export_168(void) { return 168; }
+int export_169(void) { return 169; }
+int export_170(void) { return 170; }
+int export_171(void) { return 171; }
+int export_172(void) { return 172; }
+int export_173(void) { return 173; }
+int export_174(void) { return 174; }
+int export_175(void) { return 175; }
+int export_176(void) { return 176; }
+int export_177(void) { return 177; }
+int export_178(void) { return 178; }
+int export_179(void) { return 179; }
+int export_180(void) { return 180; }
+int export_181(void) { return 181; }
+int export_182(void) { return 182; }
+int export_183(void) { return 183; }
+int export_184(void) { return 184; }
+int export_185(void) { return 185; }
+int export_186(void) { return 186; }
+int export_187(void) { return 187; }
+int export_188(void) { return 188; }
+int export_189(void) { return 189; }
+int export_190(void) { return 190; }
+int export_191(void) { return 191; }
+int export_192(void) { return 192; }
+int export_193(void) { return 193; }
+int export_194(void) { return 194; }
+int export_195(void) { return 195; }
+int export_196(void) { return 196; }
+int export_197(void) { return 197; }
+int export_198(void) { return 198; }
+int export_199(void) { return 199; }
@@ -0,0 +1,14 @@
+// Fixture of the tests reading a complete executable, built with gcc 12.2:
+// gcc -O1 -Wl,--build-id=sha1 testdata/hello.c -o testdata/hello
+
+#include <stdio.h>
+
+__thread int counter = 1;
+__thread int scratch;
+const char greeting[] = "hello";
+
+int main(void) {
+    scratch = counter + 1;
+    printf("%s %d\n", greeting, scratch);
+    return 0;
+}
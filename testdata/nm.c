@@ -0,0 +1,8 @@
+// Fixture of the nm tests, one symbol of each kind, built with gcc 12.2:
+// gcc -fcommon -c testdata/nm.c -o testdata/nm.o
+// gcc -shared -fPIC testdata/nm.c -o testdata/libnm.so.1
+
+int common; __attribute__((weak)) int weak_object = 1; __attribute__((weak)) void weak_function(void) {}
+extern int undefined; extern __attribute__((weak)) int weak_undefined; static int local(void) { return 0; }
+const int read_only = 3; __thread int thread_local; static int bss; int data = 2;
+int function(void) { return undefined + weak_undefined + local() + common + bss; }
@@ -0,0 +1,5 @@
+// Fixture of the note tests, a relocatable file with a GNU property note,
+// built with gcc 12.2:
+// gcc -c -fcf-protection=full testdata/properties.c -o testdata/properties.o
+
+int main(void) { return 0; }
@@ -0,0 +1,8 @@
+// Fixture of the relocation tests, built with gcc 12.2:
+// gcc -shared -fPIC -Wl,-z,now testdata/relocations.c -o testdata/librelocations.so.1
+// gcc -shared -fPIC -Wl,-z,now -Wl,-z,pack-relative-relocs testdata/relocations.c -o testdata/librelocations-relr.so.1
+// gcc -m32 -nostdlib -shared -fPIC testdata/relocations.c -o testdata/librelocations32.so.1
+
+char *getenv(const char *name);
+static int a, b, c, d; int *pointers[] = { &a, &b, &c, &d, &a, 0, 0, 0, &b };
+char *home(void) { return getenv("HOME"); }
@@ -0,0 +1,7 @@
+// Fixture of the size tests with code, read only data, data, bss, thread
+// locals and debug information, built with gcc 12.2:
+// gcc -g -c testdata/size.c -o testdata/size.o
+// gcc -shared -fPIC testdata/size.c -o testdata/libsize.so.1
+
+const int read_only = 3; __thread int thread_local; int bss; int data = 2;
+int function(void) { return read_only + thread_local + bss + data; }
@@ -0,0 +1,6 @@
+// Fixture of the 32 bits tests, a file that doesn't need a 32 bits libc,
+// built with gcc 12.2:
+// gcc -m32 -nostdlib -static testdata/static32.c -o testdata/static32
+
+int counter = 1; int bss_var;
+void _start(void) { counter += bss_var; for (;;); }
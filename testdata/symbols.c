@@ -0,0 +1,7 @@
+// Fixture of the symbol tests, built with gcc 12.2:
+// gcc testdata/symbols.c -o testdata/symbols
+
+#include <stdlib.h>
+
+int global_var = 1; static int local_var; __attribute__((weak)) int weak_fn(void) { return 0; }
+int main(void) { local_var++; return getenv("HOME") == 0 ? global_var : local_var + weak_fn(); }
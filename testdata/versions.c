@@ -0,0 +1,10 @@
+// Fixture of the symbol versioning tests, two versions of `foo` where the
+// old one is only reachable as foo@VERS_1, built with gcc 12.2:
+// gcc -shared -fPIC -Wl,--version-script=testdata/versions.map testdata/versions.c -o testdata/libversions.so.1
+
+char *getenv(const char *);
+__asm__(".symver old_foo, foo@VERS_1");
+__asm__(".symver new_foo, foo@@VERS_2");
+int old_foo(void) { return 1; }
+int new_foo(void) { return 2; }
+int bar(void) { return getenv("BAR") != 0; }
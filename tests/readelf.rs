@@ -0,0 +1,37 @@
+use elf_parser::elf::Elf64;
+use elf_parser::elf_file::ElfFile;
+use std::process::{self, Command};
+use std::{env, fs};
+
+fn readelf(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_elf-parser")).arg("readelf").args(args).output().unwrap()
+}
+
+#[test]
+fn golden_output() {
+    // testdata/hello.readelf is the output of GNU readelf 2.40 with the same options
+    let output = readelf(&["-W", "-hSldrsVn", "testdata/hello"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), fs::read_to_string("testdata/hello.readelf").unwrap());
+}
+
+#[test]
+fn broken_display() {
+    // .symtab linked to a section that doesn't exist only stops the symbols
+    let mut content = fs::read("testdata/hello").unwrap();
+    let elf = Elf64::parse(&content).unwrap();
+    let symtab = elf.shtable().iter().position(|sh| elf.section_name(sh) == Ok(".symtab")).unwrap();
+    let header = elf.header().e_shoff as usize + symtab * elf.header().e_shentsize as usize;
+    content[header + 0x28..header + 0x2C].copy_from_slice(&0xFFFFu32.to_le_bytes()); // sh_link
+    let path = env::temp_dir().join(format!("elf-parser-broken_symtab-{}", process::id()));
+    fs::write(&path, &content).unwrap();
+
+    let output = readelf(&["-s", "-n", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Symbol table '.dynsym'"));
+    assert!(stdout.contains("NT_GNU_BUILD_ID"));
+    assert_eq!(String::from_utf8(output.stderr).unwrap(),
+               format!("readelf: Error: {}: section link 65535 does not name a section\n", path.display()));
+}
@@ -0,0 +1,20 @@
+use std::fs;
+use std::process::Command;
+
+fn size(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_elf-parser")).arg("size").args(args).output().unwrap()
+}
+
+#[test]
+fn header_with_first_file() {
+    // A file that can't be read doesn't print the header, the next one does
+    let expected = fs::read_to_string("testdata/size/berkeley-t.txt").unwrap();
+    let lines: Vec<&str> = expected.lines().collect();
+    let output = size(&["testdata/missing", "testdata/hello"]);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), format!("{}\n{}\n", lines[0], lines[3]));
+
+    let output = size(&["testdata/missing"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}